@@ -3,7 +3,6 @@ use proc_macro2::TokenStream as TokenStream2;
 use proc_macro2::TokenTree as TokenTree2;
 use quote::{quote, ToTokens};
 use std::collections::VecDeque;
-use std::sync::atomic::AtomicU8;
 use syn::visit_mut::VisitMut;
 fn is_non_static_method(method: &syn::ImplItemFn) -> bool {
     if !method.sig.inputs.is_empty() {
@@ -343,10 +342,9 @@ pub fn subsystem_methods(_attr: TokenStream, input: TokenStream) -> TokenStream
     output_stream.into()
 }
 
-static SUID_COUNTER: AtomicU8 = AtomicU8::new(0);
 /// Automatically sets up some boilerplate needed for static subsystems.
 /// Expects Subsystem name as an argument.
-/// Example: subsystem!(TestSubsystem, 1u8)
+/// Example: subsystem!(TestSubsystem)
 #[proc_macro]
 pub fn subsystem(input: TokenStream) -> TokenStream {
     //get an ident and a literal int from the token stream
@@ -369,16 +367,13 @@ pub fn subsystem(input: TokenStream) -> TokenStream {
 
     let mut output = TokenStream2::new();
 
-    //turn SUID_COUNT into a literal int
-    let count = SUID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-    let suid_count = syn::LitInt::new(&count.to_string(), proc_macro2::Span::call_site());
-
     // create a static variable for the struct
     let static_variable = quote! {
         use wpilib::re_exports::parking_lot as p_l;
         use wpilib::re_exports::once_cell as o_c;
         static #struct_name_caps: o_c::sync::Lazy<p_l::Mutex<#struct_name>> = o_c::sync::Lazy::new(|| p_l::Mutex::new(#struct_name::__new()));
-        static SUID: u8 = #suid_count;
+        //handed out at runtime from the same counter as every other subsystem
+        static SUID: o_c::sync::Lazy<wpilib::command::SubsystemId> = o_c::sync::Lazy::new(wpilib::command::SubsystemId::next);
     };
     output.extend(static_variable);
 
@@ -389,8 +384,8 @@ pub fn subsystem(input: TokenStream) -> TokenStream {
                 let mut this = #struct_name_caps.lock();
                 this
             }
-            pub fn suid() -> u8 {
-                SUID as u8
+            pub fn suid() -> wpilib::command::SubsystemId {
+                *SUID
             }
             pub fn name() -> &'static str {
                 stringify!(#struct_name)
@@ -573,1453 +568,155 @@ pub fn use_subsystem(input: TokenStream) -> TokenStream {
 #[proc_macro]
 pub fn unit(input: TokenStream) -> TokenStream {
     let mut output = TokenStream2::new();
-    //get an ident and a type from the token stream
-    //filter out puncts and commas
-    let mut iter = TokenStream2::from(input).into_iter().filter(|token| {
-        !matches!(
-            token,
-            proc_macro2::TokenTree::Punct(_) | proc_macro2::TokenTree::Group(_)
-        )
-    });
-    let struct_name =
-        syn::parse2::<syn::Ident>(iter.next().expect("could not find first ident").into())
-            .expect("could not parse first ident as an ident");
-    let r#type = syn::parse2::<syn::Ident>(iter.next().expect("could not find second type").into())
-        .expect("could not parse second type");
-
-    //create a new struct with the given name and type
-    let struct_item = quote! {
-        #[forbid(non_camel_case_types)]
-        pub struct #struct_name {
-            pub(super) value: #r#type,
-        }
-    };
-
-    //impl clone, copy, debug and display for the struct
-    let impl_basic_block = quote! {
-        impl Clone for #struct_name {
-            fn clone(&self) -> Self {
-                Self {
-                    value: self.value.clone(),
-                }
-            }
-        }
-        impl Copy for #struct_name {}
-        impl std::fmt::Debug for #struct_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{}({})", stringify!(#struct_name), self.value)
-            }
-        }
-        impl std::fmt::Display for #struct_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{}({})", stringify!(#struct_name), self.value)
-            }
-        }
-        impl #struct_name {
-            #[inline(always)]
-            pub fn new(value: #r#type) -> Self {
-                Self { value }
-            }
-            #[inline(always)]
-            pub fn value(&self) -> #r#type {
-                self.value
-            }
-            #[inline(always)]
-            pub fn set(&mut self, value: #r#type) {
-                self.value = value;
-            }
-        }
-    };
-
-    //implement math operators for the struct
-    let impl_math_block = quote! {
-        impl std::ops::Add for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn add(self, rhs: Self) -> Self::Output {
-                Self {
-                    value: self.value + rhs.value,
-                }
-            }
-        }
-        impl std::ops::Add<&#struct_name> for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn add(self, rhs: &#struct_name) -> Self::Output {
-                Self {
-                    value: self.value + rhs.value,
-                }
-            }
-        }
-        impl std::ops::Add<#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn add(self, rhs: #struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value + rhs.value,
-                }
-            }
-        }
-        impl std::ops::Add<&#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn add(self, rhs: &#struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value + rhs.value,
-                }
-            }
-        }
-        impl std::ops::AddAssign for #struct_name {
-            #[inline(always)]
-            fn add_assign(&mut self, rhs: Self) {
-                self.value += rhs.value;
-            }
-        }
-        impl std::ops::Sub for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn sub(self, rhs: Self) -> Self::Output {
-                Self {
-                    value: self.value - rhs.value,
-                }
-            }
-        }
-        impl std::ops::Sub<&#struct_name> for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn sub(self, rhs: &#struct_name) -> Self::Output {
-                Self {
-                    value: self.value - rhs.value,
-                }
-            }
-        }
-        impl std::ops::Sub<#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn sub(self, rhs: #struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value - rhs.value,
-                }
-            }
-        }
-        impl std::ops::Sub<&#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn sub(self, rhs: &#struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value - rhs.value,
-                }
-            }
-        }
-        impl std::ops::SubAssign for #struct_name {
-            #[inline(always)]
-            fn sub_assign(&mut self, rhs: Self) {
-                self.value -= rhs.value;
-            }
-        }
-        impl std::ops::Mul for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn mul(self, rhs: Self) -> Self::Output {
-                Self {
-                    value: self.value * rhs.value,
-                }
-            }
-        }
-        impl std::ops::Mul<&#struct_name> for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn mul(self, rhs: &#struct_name) -> Self::Output {
-                Self {
-                    value: self.value * rhs.value,
-                }
-            }
-        }
-        impl std::ops::Mul<#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn mul(self, rhs: #struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value * rhs.value,
-                }
-            }
-        }
-        impl std::ops::Mul<&#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn mul(self, rhs: &#struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value * rhs.value,
-                }
-            }
-        }
-        impl std::ops::MulAssign for #struct_name {
-            #[inline(always)]
-            fn mul_assign(&mut self, rhs: Self) {
-                self.value *= rhs.value;
-            }
-        }
-        impl std::ops::Div for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn div(self, rhs: Self) -> Self::Output {
-                Self {
-                    value: self.value / rhs.value,
-                }
-            }
-        }
-        impl std::ops::Div<&#struct_name> for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn div(self, rhs: &#struct_name) -> Self::Output {
-                Self {
-                    value: self.value / rhs.value,
-                }
-            }
-        }
-        impl std::ops::Div<#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn div(self, rhs: #struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value / rhs.value,
-                }
-            }
-        }
-        impl std::ops::Div<&#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn div(self, rhs: &#struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value / rhs.value,
-                }
-            }
-        }
-        impl std::ops::DivAssign for #struct_name {
-            #[inline(always)]
-            fn div_assign(&mut self, rhs: Self) {
-                self.value /= rhs.value;
-            }
-        }
-        impl std::ops::Rem for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn rem(self, rhs: Self) -> Self::Output {
-                Self {
-                    value: self.value % rhs.value,
-                }
-            }
-        }
-        impl std::ops::Rem<&#struct_name> for #struct_name {
-            type Output = Self;
-            #[inline(always)]
-            fn rem(self, rhs: &#struct_name) -> Self::Output {
-                Self {
-                    value: self.value % rhs.value,
-                }
-            }
-        }
-        impl std::ops::Rem<#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn rem(self, rhs: #struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value % rhs.value,
-                }
-            }
-        }
-        impl std::ops::Rem<&#struct_name> for &#struct_name {
-            type Output = #struct_name;
-            #[inline(always)]
-            fn rem(self, rhs: &#struct_name) -> Self::Output {
-                #struct_name {
-                    value: self.value % rhs.value,
-                }
-            }
-        }
-        impl std::ops::RemAssign for #struct_name {
-            #[inline(always)]
-            fn rem_assign(&mut self, rhs: Self) {
-                self.value %= rhs.value;
-            }
-        }
-        impl #struct_name {
-            #[inline(always)]
-            pub fn square(&self) -> Self {
-                Self {
-                    value: self.value * self.value,
-                }
-            }
-            #[inline(always)]
-            pub fn cube(&self) -> Self {
-                Self {
-                    value: self.value * self.value * self.value,
-                }
-            }
-            #[inline(always)]
-            pub fn map(&self, f: impl FnOnce(#r#type) -> #r#type) -> Self {
-                Self {
-                    value: f(self.value),
-                }
-            }
-        }
-    };
-
-    //implement num traits for the struct
-    let impl_num_traits_block = quote! {
-        impl wpilib::re_exports::num::Zero for #struct_name {
-            fn zero() -> Self {
-                Self {
-                    value: #r#type::zero(),
-                }
-            }
-            fn is_zero(&self) -> bool {
-                self.value.is_zero()
-            }
-        }
-        impl wpilib::re_exports::num::One for #struct_name {
-            fn one() -> Self {
-                Self {
-                    value: #r#type::one(),
-                }
-            }
-            fn is_one(&self) -> bool {
-                self.value.is_one()
-            }
-        }
-        impl wpilib::re_exports::num::Num for #struct_name {
-            type FromStrRadixErr = <#r#type as wpilib::re_exports::num::Num>::FromStrRadixErr;
-            fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
-                Ok(Self {
-                    value: #r#type::from_str_radix(str, radix)?,
-                })
-            }
-        }
-        // impl wpilib::re_exports::num::NumCast for #struct_name {
-        //     fn from<T: wpilib::re_exports::num::ToPrimitive>(n: T) -> Option<Self> {
-        //         Some(Self {
-        //             value: #r#type::from(n)?,
-        //         })
-        //     }
-        // }
-        impl wpilib::re_exports::num::ToPrimitive for #struct_name {
-            fn to_i64(&self) -> Option<i64> {
-                self.value.to_i64()
-            }
-            fn to_u64(&self) -> Option<u64> {
-                self.value.to_u64()
-            }
-        }
-        impl wpilib::re_exports::num::FromPrimitive for #struct_name {
-            fn from_i64(n: i64) -> Option<Self> {
-                Some(Self {
-                    value: #r#type::from_i64(n)?,
-                })
-            }
-            fn from_u64(n: u64) -> Option<Self> {
-                Some(Self {
-                    value: #r#type::from_u64(n)?,
-                })
-            }
-            fn from_f64(n: f64) -> Option<Self> {
-                Some(Self {
-                    value: #r#type::from_f64(n)?,
-                })
-            }
-        }
-    };
-
-    //implement into and from for its type
-    let impl_into_from_block = quote! {
-        impl From<#struct_name> for #r#type {
-            #[inline(always)]
-            fn from(value: #struct_name) -> #r#type {
-                value.value
-            }
-        }
-        impl From<&#struct_name> for #r#type {
-            #[inline(always)]
-            fn from(value: &#struct_name) -> #r#type {
-                value.value
-            }
-        }
-        impl From<f64> for #struct_name {
-            #[inline(always)]
-            fn from(value: f64) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&f64> for #struct_name {
-            #[inline(always)]
-            fn from(value: &f64) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<f32> for #struct_name {
-            #[inline(always)]
-            fn from(value: f32) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&f32> for #struct_name {
-            #[inline(always)]
-            fn from(value: &f32) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<u64> for #struct_name {
-            #[inline(always)]
-            fn from(value: u64) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&u64> for #struct_name {
-            #[inline(always)]
-            fn from(value: &u64) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<u32> for #struct_name {
-            #[inline(always)]
-            fn from(value: u32) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&u32> for #struct_name {
-            #[inline(always)]
-            fn from(value: &u32) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<u16> for #struct_name {
-            #[inline(always)]
-            fn from(value: u16) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&u16> for #struct_name {
-            #[inline(always)]
-            fn from(value: &u16) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<u8> for #struct_name {
-            #[inline(always)]
-            fn from(value: u8) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&u8> for #struct_name {
-            #[inline(always)]
-            fn from(value: &u8) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<i64> for #struct_name {
-            #[inline(always)]
-            fn from(value: i64) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&i64> for #struct_name {
-            #[inline(always)]
-            fn from(value: &i64) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<i32> for #struct_name {
-            #[inline(always)]
-            fn from(value: i32) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&i32> for #struct_name {
-            #[inline(always)]
-            fn from(value: &i32) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<i16> for #struct_name {
-            #[inline(always)]
-            fn from(value: i16) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&i16> for #struct_name {
-            #[inline(always)]
-            fn from(value: &i16) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-        impl From<i8> for #struct_name {
-            #[inline(always)]
-            fn from(value: i8) -> Self {
-                Self {
-                    value: value as #r#type,
-                }
-            }
-        }
-        impl From<&i8> for #struct_name {
-            #[inline(always)]
-            fn from(value: &i8) -> Self {
-                Self {
-                    value: *value as #r#type,
-                }
-            }
-        }
-    };
-
-    //implement serde for the struct
-    let impl_serde_block = quote! {
-        impl wpilib::re_exports::serde::Serialize for #struct_name {
-            fn serialize<S: wpilib::re_exports::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-                self.value.serialize(serializer)
-            }
-        }
-        impl<'de> wpilib::re_exports::serde::Deserialize<'de> for #struct_name {
-            fn deserialize<D: wpilib::re_exports::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-                #r#type::deserialize(deserializer).map(|value| Self { value })
-            }
-        }
-    };
-
-    //implement partial eq and partial ord for the struct
-    let impl_partial_eq_block = quote! {
-        impl std::cmp::PartialEq for #struct_name {
-            fn eq(&self, other: &Self) -> bool {
-                self.value == other.value
-            }
-        }
-        impl std::cmp::PartialOrd for #struct_name {
-            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                self.value.partial_cmp(&other.value)
-            }
-        }
-    };
-
-    let impl_negative_block = quote! {
-        impl std::ops::Neg for #struct_name {
-            type Output = Self;
-            fn neg(self) -> Self::Output {
-                Self {
-                    value: -self.value,
-                }
+    // e.g. wpilib_macros::unit!(Feet, f64, Length, 0.3048, "ft");
+    //a name, the value type, the dimension and how many SI base units one of it is,
+    //optionally followed by where its zero is in the SI base unit and the symbol it's written with.
+    //the SI unit of a dimension is marked with `si` instead, e.g. unit!(Meter, f64, Length, si);
+    let parser = |input: syn::parse::ParseStream| {
+        let struct_name = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![,]>()?;
+        let r#type = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![,]>()?;
+        let dimension = input.parse::<syn::Type>()?;
+        input.parse::<syn::Token![,]>()?;
+        let fork = input.fork();
+        let scale = match fork.parse::<syn::Ident>() {
+            Ok(marker) if marker == "si" => {
+                input.parse::<syn::Ident>()?;
+                None
+            }
+            _ => Some(input.parse::<syn::Expr>()?),
+        };
+        let mut offset = None;
+        let mut symbol = None;
+        while input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            if input.peek(syn::LitStr) {
+                symbol = Some(input.parse::<syn::LitStr>()?);
+            } else {
+                offset = Some(input.parse::<syn::Expr>()?);
             }
         }
+        Ok((struct_name, r#type, dimension, scale, offset, symbol))
     };
+    let (struct_name, r#type, dimension, scale, offset, symbol) =
+        match syn::parse::Parser::parse(parser, input) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
 
-    let impl_simd_block = quote! {
-        impl wpilib::re_exports::nalgebra::SimdValue for #struct_name {
-            type Element = #struct_name;
-            type SimdBool = bool;
-
-            #[inline]
-            fn lanes() -> usize {
-                1
-            }
-            #[inline]
-            fn splat(val: Self::Element) -> Self {
-                val
-            }
-            #[inline]
-            fn extract(&self, _: usize) -> Self::Element {
-                *self
-            }
-            #[inline]
-            unsafe fn extract_unchecked(&self, _: usize) -> Self::Element {
-                *self
-            }
-            #[inline]
-            fn replace(&mut self, _: usize, val: Self::Element) {
-                self.value = val.value
-            }
-            #[inline]
-            unsafe fn replace_unchecked(&mut self, _: usize, val: Self::Element) {
-                self.value = val.value
-            }
-            #[inline]
-            fn select(self, cond: Self::SimdBool, other: Self) -> Self {
-                if cond {
-                    self
-                } else {
-                    other
-                }
-            }
-            #[inline]
-            fn map_lanes(self, f: impl Fn(Self::Element) -> Self::Element) -> Self
-                where
-                    Self: Clone, {
-                f(self)
-            }
-            #[inline]
-            fn zip_map_lanes(
-                    self,
-                    b: Self,
-                    f: impl Fn(Self::Element, Self::Element) -> Self::Element,
-                ) -> Self
-                where
-                    Self: Clone, {
-                f(self, b)
-            }
-        }
-        impl wpilib::re_exports::nalgebra::Field for #struct_name {}
-        impl wpilib::re_exports::simba::scalar::SubsetOf<#struct_name> for #struct_name {
-            #[inline]
-            fn is_in_subset(_element: &Self) -> bool {true}
-            fn to_superset(&self) -> #struct_name {*self}
-            fn from_superset(element: &#struct_name) -> Option<Self> {Some(*element)}
-            fn from_superset_unchecked(element: &#struct_name) -> Self {*element}
-        }
-        impl wpilib::re_exports::simba::scalar::SubsetOf<#struct_name> for f64 {
-            #[inline]
-            fn is_in_subset(_element: &#struct_name) -> bool {true}
-            fn to_superset(&self) -> #struct_name {#struct_name::new(*self)}
-            fn from_superset(element: &#struct_name) -> Option<Self> {Some(element.value as f64)}
-            fn from_superset_unchecked(element: &#struct_name) -> Self {element.value as f64}
-        }
-        impl wpilib::re_exports::nalgebra::ComplexField for #struct_name {
-            type RealField = #r#type;
-            #[inline]
-            fn is_finite(&self) -> bool {self.value.is_finite()}
-            #[inline]
-            fn try_sqrt(self) -> Option<Self> {Some(#struct_name::new(self.value.sqrt()))}
-            #[inline]
-            fn abs(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::abs(#r#type::from(self.value))
-            }
-            #[inline]
-            fn acos(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::acos(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn acosh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::acosh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn asin(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::asin(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn asinh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::asinh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn atan(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::atan(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn atanh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::atanh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn cos(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::cos(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn cosh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::cosh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn exp(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::exp(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn ln(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::ln(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn log(self, base: #r#type) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::log(#r#type::from(self.value), base))
-            }
-            #[inline]
-            fn powf(self, n: Self::RealField) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::powf(#r#type::from(self.value), n))
-            }
-            #[inline]
-            fn powi(self, n: i32) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::powi(#r#type::from(self.value), n))
-            }
-            #[inline]
-            fn recip(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::recip(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn sin(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::sin(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn sinh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::sinh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn sqrt(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::sqrt(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn tan(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::tan(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn tanh(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::tanh(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn argument(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::argument(#r#type::from(self.value))
-            }
-            #[inline]
-            fn modulus(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::modulus(#r#type::from(self.value))
-            }
-            #[inline]
-            fn to_exp(self) -> (Self::RealField, Self) {
-                let (r, theta) = wpilib::re_exports::nalgebra::ComplexField::to_exp(#r#type::from(self.value));
-                (r, #struct_name::new(theta))
-            }
-            #[inline]
-            fn cbrt(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::cbrt(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn hypot(self, other: Self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::hypot(#r#type::from(self.value), #r#type::from(other.value))
-            }
-            #[inline]
-            fn ceil(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::ceil(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn floor(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::floor(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn round(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::round(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn trunc(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::trunc(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn conjugate(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::conjugate(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn cosc(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::cosc(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn sinhc(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::sinhc(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn signum(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::signum(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn coshc(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::coshc(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn exp2(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::exp2(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn exp_m1(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::exp_m1(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn ln_1p(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::ln_1p(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn log10(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::log10(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn fract(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::fract(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn from_real(re: Self::RealField) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::from_real(re))
-            }
-            #[inline]
-            fn imaginary(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::imaginary(#r#type::from(self.value))
-            }
-            #[inline]
-            fn log2(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::log2(#r#type::from(self.value)))
-            }
-            #[inline]
-            fn modulus_squared(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::modulus_squared(#r#type::from(self.value))
-            }
-            #[inline]
-            fn mul_add(self,a:Self,b:Self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::mul_add(#r#type::from(self.value),#r#type::from(a.value),#r#type::from(b.value)))
-            }
-            #[inline]
-            fn norm1(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::norm1(#r#type::from(self.value))
-            }
-            #[inline]
-            fn powc(self,n:Self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::powc(#r#type::from(self.value),#r#type::from(n.value)))
-            }
-            #[inline]
-            fn real(self) -> Self::RealField {
-                wpilib::re_exports::nalgebra::ComplexField::real(#r#type::from(self.value))
-            }
-            #[inline]
-            fn scale(self,factor:Self::RealField) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::scale(#r#type::from(self.value),factor))
-            }
-            #[inline]
-            fn sin_cos(self) -> (Self,Self) {
-                let (s,c) = wpilib::re_exports::nalgebra::ComplexField::sin_cos(#r#type::from(self.value));
-                (#struct_name::new(s),#struct_name::new(c))
-            }
-            #[inline]
-            fn sinc(self) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::sinc(#r#type::from(self.value)))
-            }
-            fn sinh_cosh(self) -> (Self,Self) {
-                let (s,c) = wpilib::re_exports::nalgebra::ComplexField::sinh_cosh(#r#type::from(self.value));
-                (#struct_name::new(s),#struct_name::new(c))
-            }
-            fn to_polar(self) -> (Self::RealField,Self::RealField) {
-                let (r,theta) = wpilib::re_exports::nalgebra::ComplexField::to_polar(#r#type::from(self.value));
-                (r,theta)
-            }
-            fn unscale(self,factor:Self::RealField) -> Self {
-                #struct_name::new(wpilib::re_exports::nalgebra::ComplexField::unscale(#r#type::from(self.value),factor))
-            }
-        }
+    //SI units all share one scale so products and quotients come out as them
+    let Some(scale) = scale else {
+        if let Some(extra) = offset
+            .map(|offset| offset.to_token_stream())
+            .or_else(|| symbol.map(|symbol| symbol.to_token_stream()))
+        {
+            return syn::Error::new_spanned(
+                extra,
+                "SI units are written with the base units of their dimension and have no offset",
+            )
+            .to_compile_error()
+            .into();
+        }
+        output.extend(quote! {
+            pub type #struct_name = wpilib::math::units::Quantity<#dimension, wpilib::math::units::Si, #r#type>;
+        });
+        return output.into();
     };
 
-    let type_str = r#type.to_string();
-
-    output.extend(struct_item);
-    output.extend(impl_basic_block);
-    output.extend(impl_math_block);
-    output.extend(impl_num_traits_block);
-    output.extend(impl_into_from_block);
-    output.extend(impl_serde_block);
-    output.extend(impl_partial_eq_block);
-
-    if !type_str.contains("u") {
-        output.extend(impl_negative_block);
-    }
-    if type_str.contains("f64") || type_str.contains("f32") {
-        output.extend(impl_simd_block);
-    }
+    let scale_name = syn::Ident::new(&format!("{}Scale", struct_name), struct_name.span());
+    let offset = offset.map_or_else(|| quote! { 0.0 }, |offset| quote! { #offset });
+    let symbol = symbol.map_or_else(
+        || quote! { stringify!(#struct_name) },
+        |symbol| quote! { #symbol },
+    );
+    output.extend(quote! {
+        #[doc(hidden)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct #scale_name;
+        impl wpilib::math::units::Scale for #scale_name {
+            const SCALE: f64 = #scale;
+            const OFFSET: f64 = #offset;
+            fn symbol<D: wpilib::math::units::dimension::Dim>() -> String {
+                String::from(#symbol)
+            }
+        }
+        pub type #struct_name = wpilib::math::units::Quantity<#dimension, #scale_name, #r#type>;
+    });
 
     output.into()
 }
 
+#[deprecated(note = "units of a dimension all convert to each other with `Unit::to`")]
 #[proc_macro]
 pub fn unit_conversion(input: TokenStream) -> TokenStream {
-    let mut output = TokenStream2::new();
-
-    // e.g. wpilib_macros::unit_conversion!(meter f64, Feet f64, meter_to_feet);
-    //this would mean meter -> Feet
-
-    let mut iter = TokenStream2::from(input).into_iter().filter(|token| {
-        !matches!(
-            token,
-            proc_macro2::TokenTree::Punct(_) | proc_macro2::TokenTree::Group(_)
-        )
-    });
-    let from_name =
-        syn::parse2::<syn::Ident>(iter.next().expect("could not find from ident").into())
-            .expect("could not parse from ident as an ident");
-    let from_inner_type =
-        syn::parse2::<syn::Ident>(iter.next().expect("could not find from type ident").into())
-            .expect("could not parse from type ident as an ident");
-    let to_name = syn::parse2::<syn::Ident>(iter.next().expect("could not find to ident").into())
-        .expect("could not parse to ident as an ident");
-    let to_inner_type =
-        syn::parse2::<syn::Ident>(iter.next().expect("could not find to type ident").into())
-            .expect("could not parse to type ident as an ident");
-    let conv_func =
-        syn::parse2::<syn::Ident>(iter.next().expect("could not find third ident").into())
-            .expect("could not parse third ident as an ident");
-
-    let inv_conv_ident = syn::Ident::new(
-        &format!("inverse_{}", conv_func),
-        proc_macro2::Span::call_site(),
-    );
-
-    //create an inverse conv_func
-    let inv_conv_func_block = quote! {
-        fn #inv_conv_ident(value: #to_inner_type) -> #from_inner_type {
-            (value / #conv_func(#from_inner_type::from(1.0)) as #to_inner_type) as #from_inner_type
-        }
-    };
-
-    let impl_from_block = quote! {
-        impl From<#from_name> for #to_name {
-            fn from(value: #from_name) -> Self {
-                #to_name{ value: #conv_func(value.value)}
-            }
-        }
-        impl From<&#from_name> for #to_name {
-            fn from(value: &#from_name) -> Self {
-                #to_name{ value: #conv_func(value.value)}
-            }
-        }
-        impl From<#to_name> for #from_name {
-            fn from(value: #to_name) -> Self {
-                #from_name{ value: #inv_conv_ident(value.value)}
-            }
-        }
-        impl From<&#to_name> for #from_name {
-            fn from(value: &#to_name) -> Self {
-                #from_name{ value: #inv_conv_ident(value.value)}
-            }
-        }
+    // e.g. wpilib_macros::unit_conversion!(Meter f64, Feet f64, meter_to_feet);
+    //the conversions themselves come with the units now, this only checks the two
+    //share a dimension and keeps the inverse function it used to make
+    let parser = |input: syn::parse::ParseStream| {
+        let from_name = input.parse::<syn::Ident>()?;
+        let from_inner_type = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![,]>()?;
+        let to_name = input.parse::<syn::Ident>()?;
+        let to_inner_type = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![,]>()?;
+        let conv_func = input.parse::<syn::Ident>()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok((
+            from_name,
+            from_inner_type,
+            to_name,
+            to_inner_type,
+            conv_func,
+        ))
     };
+    let (from_name, from_inner_type, to_name, to_inner_type, conv_func) =
+        match syn::parse::Parser::parse(parser, input) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
 
-    //add math between the two types
-    let impl_to_op_from_block = quote! {
-        impl std::ops::Add<#to_name> for #from_name {
-            type Output = #from_name;
-            fn add(self, rhs: #to_name) -> Self::Output {
-                self + #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<&#to_name> for #from_name {
-            type Output = #from_name;
-            fn add(self, rhs: &#to_name) -> Self::Output {
-                self + #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<#to_name> for &#from_name {
-            type Output = #from_name;
-            fn add(self, rhs: #to_name) -> Self::Output {
-                self + #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<&#to_name> for &#from_name {
-            type Output = #from_name;
-            fn add(self, rhs: &#to_name) -> Self::Output {
-                self + #from_name::from(rhs)
-            }
-        }
-        impl std::ops::AddAssign<#to_name> for #from_name {
-            fn add_assign(&mut self, rhs: #to_name) {
-                *self += #from_name::from(rhs);
-            }
-        }
-        impl std::ops::Sub<#to_name> for #from_name {
-            type Output = #from_name;
-            fn sub(self, rhs: #to_name) -> Self::Output {
-                self - #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<&#to_name> for #from_name {
-            type Output = #from_name;
-            fn sub(self, rhs: &#to_name) -> Self::Output {
-                self - #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<#to_name> for &#from_name {
-            type Output = #from_name;
-            fn sub(self, rhs: #to_name) -> Self::Output {
-                self - #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<&#to_name> for &#from_name {
-            type Output = #from_name;
-            fn sub(self, rhs: &#to_name) -> Self::Output {
-                self - #from_name::from(rhs)
-            }
-        }
-        impl std::ops::SubAssign<#to_name> for #from_name {
-            fn sub_assign(&mut self, rhs: #to_name) {
-                *self -= #from_name::from(rhs);
-            }
-        }
-        impl std::ops::Mul<#to_name> for #from_name {
-            type Output = #from_name;
-            fn mul(self, rhs: #to_name) -> Self::Output {
-                self * #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<&#to_name> for #from_name {
-            type Output = #from_name;
-            fn mul(self, rhs: &#to_name) -> Self::Output {
-                self * #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<#to_name> for &#from_name {
-            type Output = #from_name;
-            fn mul(self, rhs: #to_name) -> Self::Output {
-                self * #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<&#to_name> for &#from_name {
-            type Output = #from_name;
-            fn mul(self, rhs: &#to_name) -> Self::Output {
-                self * #from_name::from(rhs)
-            }
-        }
-        impl std::ops::MulAssign<#to_name> for #from_name {
-            fn mul_assign(&mut self, rhs: #to_name) {
-                *self *= #from_name::from(rhs);
-            }
-        }
-        impl std::ops::Div<#to_name> for #from_name {
-            type Output = #from_name;
-            fn div(self, rhs: #to_name) -> Self::Output {
-                self / #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<&#to_name> for #from_name {
-            type Output = #from_name;
-            fn div(self, rhs: &#to_name) -> Self::Output {
-                self / #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<#to_name> for &#from_name {
-            type Output = #from_name;
-            fn div(self, rhs: #to_name) -> Self::Output {
-                self / #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<&#to_name> for &#from_name {
-            type Output = #from_name;
-            fn div(self, rhs: &#to_name) -> Self::Output {
-                self / #from_name::from(rhs)
-            }
-        }
-        impl std::ops::DivAssign<#to_name> for #from_name {
-            fn div_assign(&mut self, rhs: #to_name) {
-                *self /= #from_name::from(rhs);
-            }
-        }
-        impl std::ops::Rem<#to_name> for #from_name {
-            type Output = #from_name;
-            fn rem(self, rhs: #to_name) -> Self::Output {
-                self % #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<&#to_name> for #from_name {
-            type Output = #from_name;
-            fn rem(self, rhs: &#to_name) -> Self::Output {
-                self % #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<#to_name> for &#from_name {
-            type Output = #from_name;
-            fn rem(self, rhs: #to_name) -> Self::Output {
-                self % #from_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<&#to_name> for &#from_name {
-            type Output = #from_name;
-            fn rem(self, rhs: &#to_name) -> Self::Output {
-                self % #from_name::from(rhs)
-            }
-        }
-        impl std::ops::RemAssign<#to_name> for #from_name {
-            fn rem_assign(&mut self, rhs: #to_name) {
-                *self %= #from_name::from(rhs);
-            }
-        }
-    };
-    let impl_from_op_to_block = quote! {
-        impl std::ops::Add<#from_name> for #to_name {
-            type Output = #to_name;
-            fn add(self, rhs: #from_name) -> Self::Output {
-                self + #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<&#from_name> for #to_name {
-            type Output = #to_name;
-            fn add(self, rhs: &#from_name) -> Self::Output {
-                self + #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<#from_name> for &#to_name {
-            type Output = #to_name;
-            fn add(self, rhs: #from_name) -> Self::Output {
-                self + #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Add<&#from_name> for &#to_name {
-            type Output = #to_name;
-            fn add(self, rhs: &#from_name) -> Self::Output {
-                self + #to_name::from(rhs)
-            }
-        }
-        impl std::ops::AddAssign<#from_name> for #to_name {
-            fn add_assign(&mut self, rhs: #from_name) {
-                *self += #to_name::from(rhs);
-            }
-        }
-        impl std::ops::Sub<#from_name> for #to_name {
-            type Output = #to_name;
-            fn sub(self, rhs: #from_name) -> Self::Output {
-                self - #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<&#from_name> for #to_name {
-            type Output = #to_name;
-            fn sub(self, rhs: &#from_name) -> Self::Output {
-                self - #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<#from_name> for &#to_name {
-            type Output = #to_name;
-            fn sub(self, rhs: #from_name) -> Self::Output {
-                self - #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Sub<&#from_name> for &#to_name {
-            type Output = #to_name;
-            fn sub(self, rhs: &#from_name) -> Self::Output {
-                self - #to_name::from(rhs)
-            }
-        }
-        impl std::ops::SubAssign<#from_name> for #to_name {
-            fn sub_assign(&mut self, rhs: #from_name) {
-                *self -= #to_name::from(rhs);
-            }
-        }
-        impl std::ops::Mul<#from_name> for #to_name {
-            type Output = #to_name;
-            fn mul(self, rhs: #from_name) -> Self::Output {
-                self * #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<&#from_name> for #to_name {
-            type Output = #to_name;
-            fn mul(self, rhs: &#from_name) -> Self::Output {
-                self * #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<#from_name> for &#to_name {
-            type Output = #to_name;
-            fn mul(self, rhs: #from_name) -> Self::Output {
-                self * #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Mul<&#from_name> for &#to_name {
-            type Output = #to_name;
-            fn mul(self, rhs: &#from_name) -> Self::Output {
-                self * #to_name::from(rhs)
-            }
-        }
-        impl std::ops::MulAssign<#from_name> for #to_name {
-            fn mul_assign(&mut self, rhs: #from_name) {
-                *self *= #to_name::from(rhs);
-            }
-        }
-        impl std::ops::Div<#from_name> for #to_name {
-            type Output = #to_name;
-            fn div(self, rhs: #from_name) -> Self::Output {
-                self / #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<&#from_name> for #to_name {
-            type Output = #to_name;
-            fn div(self, rhs: &#from_name) -> Self::Output {
-                self / #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<#from_name> for &#to_name {
-            type Output = #to_name;
-            fn div(self, rhs: #from_name) -> Self::Output {
-                self / #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Div<&#from_name> for &#to_name {
-            type Output = #to_name;
-            fn div(self, rhs: &#from_name) -> Self::Output {
-                self / #to_name::from(rhs)
-            }
-        }
-        impl std::ops::DivAssign<#from_name> for #to_name {
-            fn div_assign(&mut self, rhs: #from_name) {
-                *self /= #to_name::from(rhs);
-            }
-        }
-        impl std::ops::Rem<#from_name> for #to_name {
-            type Output = #to_name;
-            fn rem(self, rhs: #from_name) -> Self::Output {
-                self % #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<&#from_name> for #to_name {
-            type Output = #to_name;
-            fn rem(self, rhs: &#from_name) -> Self::Output {
-                self % #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<#from_name> for &#to_name {
-            type Output = #to_name;
-            fn rem(self, rhs: #from_name) -> Self::Output {
-                self % #to_name::from(rhs)
-            }
-        }
-        impl std::ops::Rem<&#from_name> for &#to_name {
-            type Output = #to_name;
-            fn rem(self, rhs: &#from_name) -> Self::Output {
-                self % #to_name::from(rhs)
-            }
-        }
-        impl std::ops::RemAssign<#from_name> for #to_name {
-            fn rem_assign(&mut self, rhs: #from_name) {
-                *self %= #to_name::from(rhs);
-            }
-        }
-    };
+    let inv_conv_ident = syn::Ident::new(&format!("inverse_{}", conv_func), conv_func.span());
 
-    //implement partial eq and partial ord between the two types
-    let impl_partial_eq_ord_block = quote! {
-        impl std::cmp::PartialEq<#to_name> for #from_name {
-            fn eq(&self, other: &#to_name) -> bool {
-                self.value == #inv_conv_ident(other.value) as #from_inner_type
-            }
-        }
-        impl std::cmp::PartialEq<#from_name> for #to_name {
-            fn eq(&self, other: &#from_name) -> bool {
-                self.value == #conv_func(other.value) as #to_inner_type
-            }
-        }
-        impl std::cmp::PartialOrd<#to_name> for #from_name {
-            fn partial_cmp(&self, other: &#to_name) -> Option<std::cmp::Ordering> {
-                self.value.partial_cmp(&#inv_conv_ident(other.value))
-            }
-        }
-        impl std::cmp::PartialOrd<#from_name> for #to_name {
-            fn partial_cmp(&self, other: &#from_name) -> Option<std::cmp::Ordering> {
-                self.value.partial_cmp(&#conv_func(other.value))
-            }
+    quote! {
+        const _: fn(#from_name) -> #to_name = wpilib::math::units::Unit::to;
+        #[allow(dead_code)]
+        fn #inv_conv_ident(value: #to_inner_type) -> #from_inner_type {
+            wpilib::math::units::Unit::to::<#from_name>(#to_name::new(value)).value()
         }
-    };
-
-    output.extend(inv_conv_func_block);
-    output.extend(impl_from_block);
-    output.extend(impl_to_op_from_block);
-    output.extend(impl_from_op_to_block);
-    output.extend(impl_partial_eq_ord_block);
-
-    output.into()
+    }
+    .into()
 }
 
+#[deprecated(note = "products and quotients of units come out in the dimension they make")]
 #[proc_macro]
 pub fn unit_dimensional_analysis(input: TokenStream) -> TokenStream {
-    let mut output = TokenStream2::new();
-    //expect (ident operator ident = ident)
-    let input = TokenStream2::from(input);
-    let mut iter = input.into_iter();
-    let a_name = iter.next().unwrap();
-    let operator = iter.next().unwrap();
-    let b_name = iter.next().unwrap();
-    let eq = iter.next().unwrap();
-    let c_name = iter.next().unwrap();
-    let a_name = match a_name {
-        proc_macro2::TokenTree::Ident(ident) => ident,
-        _ => panic!("expected ident"),
-    };
-    let b_name = match b_name {
-        proc_macro2::TokenTree::Ident(ident) => ident,
-        _ => panic!("expected ident"),
-    };
-    let c_name = match c_name {
-        proc_macro2::TokenTree::Ident(ident) => ident,
-        _ => panic!("expected ident"),
-    };
-    let operator = match operator {
-        proc_macro2::TokenTree::Punct(ident) => ident,
-        _ => panic!("expected punct"),
+    // e.g. wpilib_macros::unit_dimensional_analysis!(Volt * Amp = Watt);
+    //the operators come with the units now, this only checks the result converts to the unit
+    let parser = |input: syn::parse::ParseStream| {
+        let a_name = input.parse::<syn::Ident>()?;
+        let lookahead = input.lookahead1();
+        let operator = if lookahead.peek(syn::Token![*]) {
+            input.parse::<syn::Token![*]>()?;
+            quote! { * }
+        } else if lookahead.peek(syn::Token![/]) {
+            input.parse::<syn::Token![/]>()?;
+            quote! { / }
+        } else {
+            return Err(lookahead.error());
+        };
+        let b_name = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![=]>()?;
+        let c_name = input.parse::<syn::Ident>()?;
+        Ok((a_name, operator, b_name, c_name))
     };
-    let eq = match eq {
-        proc_macro2::TokenTree::Punct(ident) => ident,
-        _ => panic!("expected punct"),
+    let (a_name, operator, b_name, c_name) = match syn::parse::Parser::parse(parser, input) {
+        Ok(parsed) => parsed,
+        Err(err) => return err.to_compile_error().into(),
     };
 
-    if eq.as_char() != '=' {
-        panic!("expected =");
-    }
-
-    let impl_block;
-
-    match operator.as_char() {
-        '*' => {
-            impl_block = quote! {
-                impl std::ops::Mul<#b_name> for #a_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: #b_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<#a_name> for #b_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: #a_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<&#b_name> for #a_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: &#b_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<#a_name> for &#b_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: #a_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<#b_name> for &#a_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: #b_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<&#a_name> for #b_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: &#a_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<&#b_name> for &#a_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: &#b_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-                impl std::ops::Mul<&#a_name> for &#b_name {
-                    type Output = #c_name;
-                    fn mul(self, rhs: &#a_name) -> Self::Output {
-                        #c_name::from(self.value * rhs.value)
-                    }
-                }
-
-                //other order
-                impl std::ops::Div<#a_name> for #c_name {
-                    type Output = #b_name;
-                    fn div(self, rhs: #a_name) -> Self::Output {
-                        #b_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<#b_name> for #c_name {
-                    type Output = #a_name;
-                    fn div(self, rhs: #b_name) -> Self::Output {
-                        #a_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#a_name> for #c_name {
-                    type Output = #b_name;
-                    fn div(self, rhs: &#a_name) -> Self::Output {
-                        #b_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<#a_name> for &#c_name {
-                    type Output = #b_name;
-                    fn div(self, rhs: #a_name) -> Self::Output {
-                        #b_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#a_name> for &#c_name {
-                    type Output = #b_name;
-                    fn div(self, rhs: &#a_name) -> Self::Output {
-                        #b_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#b_name> for #c_name {
-                    type Output = #a_name;
-                    fn div(self, rhs: &#b_name) -> Self::Output {
-                        #a_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<#b_name> for &#c_name {
-                    type Output = #a_name;
-                    fn div(self, rhs: #b_name) -> Self::Output {
-                        #a_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#b_name> for &#c_name {
-                    type Output = #a_name;
-                    fn div(self, rhs: &#b_name) -> Self::Output {
-                        #a_name::from(self.value / rhs.value)
-                    }
-                }
-            };
-        }
-        '/' => {
-            impl_block = quote! {
-                impl std::ops::Div<#b_name> for #a_name {
-                    type Output = #c_name;
-                    fn div(self, rhs: #b_name) -> Self::Output {
-                        #c_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#b_name> for #a_name {
-                    type Output = #c_name;
-                    fn div(self, rhs: &#b_name) -> Self::Output {
-                        #c_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<#b_name> for &#a_name {
-                    type Output = #c_name;
-                    fn div(self, rhs: #b_name) -> Self::Output {
-                        #c_name::from(self.value / rhs.value)
-                    }
-                }
-                impl std::ops::Div<&#b_name> for &#a_name {
-                    type Output = #c_name;
-                    fn div(self, rhs: &#b_name) -> Self::Output {
-                        #c_name::from(self.value / rhs.value)
-                    }
-                }
-            };
-        }
-        _ => panic!("expected * /"),
+    quote! {
+        const _: fn(#a_name, #b_name) -> #c_name =
+            |a, b| wpilib::math::units::Unit::to(a #operator b);
     }
-
-    output.extend(impl_block);
-
-    output.into()
+    .into()
 }
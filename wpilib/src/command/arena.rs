@@ -0,0 +1,100 @@
+/// Identifies a value in an [`Arena`].
+///
+/// A key goes stale once its value is removed, even after the slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct ArenaKey {
+    index: usize,
+    generation: u32,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Slot storage with generational keys, removing a value never moves the others.
+#[derive(Debug)]
+pub(super) struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> Arena<T> {
+    pub(super) const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub(super) fn insert(&mut self, value: T) -> ArenaKey {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            slot.value = Some(value);
+            ArenaKey {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                value: Some(value),
+            });
+            ArenaKey {
+                index: self.slots.len() - 1,
+                generation: 0,
+            }
+        }
+    }
+
+    pub(super) fn remove(&mut self, key: ArenaKey) -> Option<T> {
+        let slot = self
+            .slots
+            .get_mut(key.index)
+            .filter(|slot| slot.generation == key.generation)?;
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub(super) fn get(&self, key: ArenaKey) -> Option<&T> {
+        self.slots
+            .get(key.index)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub(super) fn get_mut(&mut self, key: ArenaKey) -> Option<&mut T> {
+        self.slots
+            .get_mut(key.index)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub(super) fn contains(&self, key: ArenaKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The keys of every value, in slot order.
+    pub(super) fn keys(&self) -> Vec<ArenaKey> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.value.is_some())
+            .map(|(index, slot)| ArenaKey {
+                index,
+                generation: slot.generation,
+            })
+            .collect()
+    }
+
+    pub(super) const fn len(&self) -> usize {
+        self.len
+    }
+}
@@ -1 +1,578 @@
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
+use parking_lot::Mutex;
+
+use super::{
+    conditions::{self, OnTrue, WhileTrue},
+    trigger::Trigger,
+};
+use crate::driver_station::{DriverStation, JoystickData, JoystickOutputs};
+
+/// Where HID devices read their state from and send their outputs to.
+pub trait JoystickSource: Send + Sync {
+    /// The state of the joystick on `port`, `None` when nothing is plugged in.
+    fn get_joystick(&self, port: usize) -> Option<JoystickData>;
+
+    fn set_outputs(&self, _port: usize, _outputs: HidOutputs) {}
+}
+
+/// Reads joysticks from the [`DriverStation`] and sends outputs back through it, see
+/// [`DriverStation::set_joystick_outputs`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DriverStationJoysticks;
+
+impl JoystickSource for DriverStationJoysticks {
+    fn get_joystick(&self, port: usize) -> Option<JoystickData> {
+        DriverStation::get_joystick(port)
+    }
+
+    fn set_outputs(&self, port: usize, outputs: HidOutputs) {
+        DriverStation::set_joystick_outputs(port, outputs.into());
+    }
+}
+
+/// Joysticks set directly from code, for tests and simulation.
+#[derive(Debug, Default)]
+pub struct SimJoysticks {
+    joysticks: Mutex<HashMap<usize, JoystickData>>,
+    outputs: Mutex<HashMap<usize, HidOutputs>>,
+}
+
+impl SimJoysticks {
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn set_joystick(&self, port: usize, joystick: JoystickData) {
+        self.joysticks.lock().insert(port, joystick);
+    }
+
+    pub fn unplug(&self, port: usize) {
+        self.joysticks.lock().remove(&port);
+    }
+
+    fn update_joystick(&self, port: usize, update: impl FnOnce(&mut JoystickData)) {
+        update(self.joysticks.lock().entry(port).or_default());
+    }
+
+    /// Sets a button, 1 indexed like [`GenericHID::get_raw_button`].
+    pub fn set_button(&self, port: usize, button: usize, pressed: bool) {
+        if button == 0 {
+            return;
+        }
+        self.update_joystick(port, |joystick| {
+            if joystick.buttons.len() < button {
+                joystick.buttons.resize(button, false);
+            }
+            joystick.buttons[button - 1] = pressed;
+        });
+    }
+
+    /// Sets an axis from -1.0 to 1.0, quantized like the driver station does.
+    pub fn set_axis(&self, port: usize, axis: usize, value: f64) {
+        self.update_joystick(port, |joystick| {
+            if joystick.axes.len() <= axis {
+                joystick.axes.resize(axis + 1, 0);
+            }
+            joystick.axes[axis] = axis_to_raw(value);
+        });
+    }
+
+    pub fn set_pov(&self, port: usize, pov: usize, angle: i16) {
+        self.update_joystick(port, |joystick| {
+            if joystick.povs.len() <= pov {
+                joystick.povs.resize(pov + 1, -1);
+            }
+            joystick.povs[pov] = angle;
+        });
+    }
+
+    #[must_use]
+    pub fn get_outputs(&self, port: usize) -> HidOutputs {
+        self.outputs.lock().get(&port).copied().unwrap_or_default()
+    }
+}
+
+impl JoystickSource for SimJoysticks {
+    fn get_joystick(&self, port: usize) -> Option<JoystickData> {
+        self.joysticks.lock().get(&port).cloned()
+    }
+
+    fn set_outputs(&self, port: usize, outputs: HidOutputs) {
+        self.outputs.lock().insert(port, outputs);
+    }
+}
+
+//the driver station sends axes as -128..=127
+fn axis_from_raw(raw: i8) -> f64 {
+    if raw < 0 {
+        f64::from(raw) / 128.0
+    } else {
+        f64::from(raw) / 127.0
+    }
+}
+
+fn axis_to_raw(value: f64) -> i8 {
+    let value = value.clamp(-1.0, 1.0);
+    let scaled = if value < 0.0 {
+        value * 128.0
+    } else {
+        value * 127.0
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let raw = scaled.round() as i8;
+    raw
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RumbleType {
+    Left,
+    Right,
+    Both,
+}
+
+/// What the robot sends back to a HID device.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HidOutputs {
+    pub left_rumble: f64,
+    pub right_rumble: f64,
+    /// One bit per output, 0 indexed.
+    pub outputs: u32,
+}
+
+impl From<HidOutputs> for JoystickOutputs {
+    fn from(outputs: HidOutputs) -> Self {
+        //rumble goes over the wire as a fraction of `u16::MAX`
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let scale = |rumble: f64| (rumble.clamp(0.0, 1.0) * f64::from(u16::MAX)).round() as u16;
+        Self {
+            outputs: outputs.outputs,
+            left_rumble: scale(outputs.left_rumble),
+            right_rumble: scale(outputs.right_rumble),
+        }
+    }
+}
+
+/// A HID device plugged into the driver station, addressed by its port.
+///
+/// Clones share their outputs.
+#[derive(Clone)]
+pub struct GenericHID {
+    port: usize,
+    source: Arc<dyn JoystickSource>,
+    outputs: Arc<Mutex<HidOutputs>>,
+}
+
+impl GenericHID {
+    /// A device read from the [`DriverStation`].
+    #[must_use]
+    pub fn new(port: usize) -> Self {
+        Self::with_source(port, Arc::new(DriverStationJoysticks))
+    }
+
+    #[must_use]
+    pub fn with_source(port: usize, source: Arc<dyn JoystickSource>) -> Self {
+        Self {
+            port,
+            source,
+            outputs: Arc::new(Mutex::new(HidOutputs::default())),
+        }
+    }
+
+    #[must_use]
+    pub const fn get_port(&self) -> usize {
+        self.port
+    }
+
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.source.get_joystick(self.port).is_some()
+    }
+
+    /// Whether `button` is held, buttons are 1 indexed and missing ones read as released.
+    #[must_use]
+    pub fn get_raw_button(&self, button: usize) -> bool {
+        button > 0
+            && self
+                .source
+                .get_joystick(self.port)
+                .and_then(|joystick| joystick.buttons.get(button - 1).copied())
+                .unwrap_or(false)
+    }
+
+    /// The value of `axis` from -1.0 to 1.0, missing axes read as 0.
+    #[must_use]
+    pub fn get_raw_axis(&self, axis: usize) -> f64 {
+        self.source
+            .get_joystick(self.port)
+            .and_then(|joystick| joystick.axes.get(axis).copied())
+            .map_or(0.0, axis_from_raw)
+    }
+
+    /// The angle in degrees of POV hat `pov`, -1 when it's not pressed or missing.
+    #[must_use]
+    pub fn get_pov(&self, pov: usize) -> i16 {
+        self.source
+            .get_joystick(self.port)
+            .and_then(|joystick| joystick.povs.get(pov).copied())
+            .unwrap_or(-1)
+    }
+
+    #[must_use]
+    pub fn get_button_count(&self) -> usize {
+        self.source
+            .get_joystick(self.port)
+            .map_or(0, |joystick| joystick.buttons.len())
+    }
+
+    #[must_use]
+    pub fn get_axis_count(&self) -> usize {
+        self.source
+            .get_joystick(self.port)
+            .map_or(0, |joystick| joystick.axes.len())
+    }
+
+    #[must_use]
+    pub fn get_pov_count(&self) -> usize {
+        self.source
+            .get_joystick(self.port)
+            .map_or(0, |joystick| joystick.povs.len())
+    }
+
+    fn update_outputs(&self, update: impl FnOnce(&mut HidOutputs)) {
+        let outputs = {
+            let mut outputs = self.outputs.lock();
+            update(&mut outputs);
+            *outputs
+        };
+        self.source.set_outputs(self.port, outputs);
+    }
+
+    /// Sets the rumble strength from 0.0 to 1.0.
+    pub fn set_rumble(&self, rumble: RumbleType, value: f64) {
+        let value = value.clamp(0.0, 1.0);
+        self.update_outputs(|outputs| match rumble {
+            RumbleType::Left => outputs.left_rumble = value,
+            RumbleType::Right => outputs.right_rumble = value,
+            RumbleType::Both => {
+                outputs.left_rumble = value;
+                outputs.right_rumble = value;
+            }
+        });
+    }
+
+    /// Sets a single output, 1 indexed.
+    pub fn set_output(&self, index: u32, value: bool) {
+        if index == 0 || index > 32 {
+            return;
+        }
+        let bit = 1 << (index - 1);
+        self.update_outputs(|outputs| {
+            if value {
+                outputs.outputs |= bit;
+            } else {
+                outputs.outputs &= !bit;
+            }
+        });
+    }
+
+    pub fn set_outputs(&self, value: u32) {
+        self.update_outputs(|outputs| outputs.outputs = value);
+    }
+
+    #[must_use]
+    pub fn get_outputs(&self) -> HidOutputs {
+        *self.outputs.lock()
+    }
+
+    #[must_use]
+    pub fn button(&self, button: usize) -> HidButton {
+        self.binding(HidInput::Button(button))
+    }
+
+    /// Held while POV hat `pov` points at `angle` degrees, -1 for centered.
+    #[must_use]
+    pub fn pov(&self, pov: usize, angle: i16) -> HidButton {
+        self.binding(HidInput::Pov { pov, angle })
+    }
+
+    #[must_use]
+    pub fn pov_up(&self) -> HidButton {
+        self.pov(0, 0)
+    }
+
+    #[must_use]
+    pub fn pov_right(&self) -> HidButton {
+        self.pov(0, 90)
+    }
+
+    #[must_use]
+    pub fn pov_down(&self) -> HidButton {
+        self.pov(0, 180)
+    }
+
+    #[must_use]
+    pub fn pov_left(&self) -> HidButton {
+        self.pov(0, 270)
+    }
+
+    #[must_use]
+    pub fn pov_center(&self) -> HidButton {
+        self.pov(0, -1)
+    }
+
+    #[must_use]
+    pub fn axis_greater_than(&self, axis: usize, threshold: f64) -> HidButton {
+        self.binding(HidInput::AxisGreaterThan { axis, threshold })
+    }
+
+    #[must_use]
+    pub fn axis_less_than(&self, axis: usize, threshold: f64) -> HidButton {
+        self.binding(HidInput::AxisLessThan { axis, threshold })
+    }
+
+    fn binding(&self, input: HidInput) -> HidButton {
+        HidButton {
+            hid: self.clone(),
+            input,
+        }
+    }
+}
+
+impl Debug for GenericHID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenericHID")
+            .field("port", &self.port)
+            .field("outputs", &self.get_outputs())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HidInput {
+    Button(usize),
+    Pov { pov: usize, angle: i16 },
+    AxisGreaterThan { axis: usize, threshold: f64 },
+    AxisLessThan { axis: usize, threshold: f64 },
+}
+
+/// A single input of a HID device read as pressed or not, used to bind commands.
+///
+/// ```ignore
+/// scheduler.add_cond(controller.a().on_true(), command_provider!{...});
+/// ```
+#[derive(Debug, Clone)]
+pub struct HidButton {
+    hid: GenericHID,
+    input: HidInput,
+}
+
+impl HidButton {
+    #[must_use]
+    pub fn get(&self) -> bool {
+        match self.input {
+            HidInput::Button(button) => self.hid.get_raw_button(button),
+            HidInput::Pov { pov, angle } => self.hid.get_pov(pov) == angle,
+            HidInput::AxisGreaterThan { axis, threshold } => {
+                self.hid.get_raw_axis(axis) > threshold
+            }
+            HidInput::AxisLessThan { axis, threshold } => self.hid.get_raw_axis(axis) < threshold,
+        }
+    }
+
+    /// The input as a [`Trigger`], for composing it with other conditions.
+    #[must_use]
+    pub fn trigger(&self) -> Trigger {
+        let button = self.clone();
+        Trigger::new(move || button.get())
+    }
+
+    /// Starts the command when the input is pressed.
+    #[must_use]
+    pub fn on_true(&self) -> OnTrue<impl Fn() -> bool + Send + Sync + 'static> {
+        let button = self.clone();
+        conditions::on_true(move || button.get())
+    }
+
+    /// Starts the command when the input is released.
+    #[must_use]
+    pub fn on_false(&self) -> OnTrue<impl Fn() -> bool + Send + Sync + 'static> {
+        let button = self.clone();
+        conditions::on_true(move || !button.get())
+    }
+
+    /// Runs the command while the input is held, cancelling it on release.
+    #[must_use]
+    pub fn while_true(&self) -> WhileTrue<impl Fn() -> bool + Send + Sync + 'static> {
+        let button = self.clone();
+        conditions::while_true(move || button.get())
+    }
+
+    /// Runs the command while the input is released, cancelling it when pressed.
+    #[must_use]
+    pub fn while_false(&self) -> WhileTrue<impl Fn() -> bool + Send + Sync + 'static> {
+        let button = self.clone();
+        conditions::while_false(move || button.get())
+    }
+}
+
+//declares a controller wrapping GenericHID with named buttons and axes
+macro_rules! hid_device {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        buttons { $($button:ident = $button_index:literal),* $(,)? },
+        axes { $($axis:ident = $axis_index:literal),* $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            hid: GenericHID,
+        }
+
+        impl $name {
+            #[must_use]
+            pub fn new(port: usize) -> Self {
+                Self {
+                    hid: GenericHID::new(port),
+                }
+            }
+
+            #[must_use]
+            pub fn with_source(port: usize, source: Arc<dyn JoystickSource>) -> Self {
+                Self {
+                    hid: GenericHID::with_source(port, source),
+                }
+            }
+
+            #[must_use]
+            pub const fn get_hid(&self) -> &GenericHID {
+                &self.hid
+            }
+
+            $(
+                #[must_use]
+                pub fn $button(&self) -> HidButton {
+                    self.hid.button($button_index)
+                }
+            )*
+
+            $(
+                #[must_use]
+                pub fn $axis(&self) -> f64 {
+                    self.hid.get_raw_axis($axis_index)
+                }
+            )*
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = GenericHID;
+
+            fn deref(&self) -> &GenericHID {
+                &self.hid
+            }
+        }
+    };
+}
+
+hid_device!(
+    /// A flight style joystick.
+    Joystick,
+    buttons { trigger = 1, top = 2 },
+    axes { get_x = 0, get_y = 1, get_z = 2, get_twist = 2, get_throttle = 3 }
+);
+
+hid_device!(
+    XboxController,
+    buttons {
+        a = 1,
+        b = 2,
+        x = 3,
+        y = 4,
+        left_bumper = 5,
+        right_bumper = 6,
+        back = 7,
+        start = 8,
+        left_stick = 9,
+        right_stick = 10,
+    },
+    axes {
+        get_left_x = 0,
+        get_left_y = 1,
+        get_left_trigger_axis = 2,
+        get_right_trigger_axis = 3,
+        get_right_x = 4,
+        get_right_y = 5,
+    }
+);
+
+impl XboxController {
+    /// Pressed while the left trigger is past `threshold`.
+    #[must_use]
+    pub fn left_trigger(&self, threshold: f64) -> HidButton {
+        self.hid.axis_greater_than(2, threshold)
+    }
+
+    /// Pressed while the right trigger is past `threshold`.
+    #[must_use]
+    pub fn right_trigger(&self, threshold: f64) -> HidButton {
+        self.hid.axis_greater_than(3, threshold)
+    }
+}
+
+hid_device!(
+    PS4Controller,
+    buttons {
+        square = 1,
+        cross = 2,
+        circle = 3,
+        triangle = 4,
+        l1 = 5,
+        r1 = 6,
+        l2 = 7,
+        r2 = 8,
+        share = 9,
+        options = 10,
+        l3 = 11,
+        r3 = 12,
+        ps = 13,
+        touchpad = 14,
+    },
+    axes {
+        get_left_x = 0,
+        get_left_y = 1,
+        get_right_x = 2,
+        get_l2_axis = 3,
+        get_r2_axis = 4,
+        get_right_y = 5,
+    }
+);
+
+hid_device!(
+    PS5Controller,
+    buttons {
+        square = 1,
+        cross = 2,
+        circle = 3,
+        triangle = 4,
+        l1 = 5,
+        r1 = 6,
+        l2 = 7,
+        r2 = 8,
+        create = 9,
+        options = 10,
+        l3 = 11,
+        r3 = 12,
+        ps = 13,
+        touchpad = 14,
+    },
+    axes {
+        get_left_x = 0,
+        get_left_y = 1,
+        get_right_x = 2,
+        get_l2_axis = 3,
+        get_r2_axis = 4,
+        get_right_y = 5,
+    }
+);
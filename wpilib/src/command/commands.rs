@@ -1,5 +1,15 @@
-use crate::command;
-use std::{collections::HashSet, fmt::Debug};
+use crate::{
+    command,
+    math::units::time::Second,
+    timer::{get_default_clock, ClockRef, Timer},
+};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc, time::Duration};
+
+use super::{
+    manager::{CommandManager, CommandScheduler, DeferredQueue, Proxied},
+    state_machine::StateMachineCommand,
+    Requirements,
+};
 
 pub trait CommandTrait {
     fn init(&mut self) {}
@@ -12,10 +22,8 @@ pub trait CommandTrait {
         false
     }
 
-    // fn add_requirements(&mut self, _subsystems: Vec<u8>) {}
-
-    fn get_requirements(&self) -> Vec<u8> {
-        Vec::new()
+    fn get_requirements(&self) -> Requirements {
+        Requirements::new()
     }
 
     fn run_when_disabled(&self) -> bool {
@@ -37,7 +45,8 @@ pub struct CommandBuilder {
     periodic: Option<Box<dyn FnMut()>>,
     end: Option<Box<dyn FnMut(bool)>>,
     is_finished: Option<Box<dyn FnMut() -> bool>>,
-    requirements: Vec<u8>,
+    requirements: Requirements,
+    cancel_incoming: bool,
 }
 
 impl CommandBuilder {
@@ -48,7 +57,8 @@ impl CommandBuilder {
             periodic: None,
             end: None,
             is_finished: None,
-            requirements: Vec::new(),
+            requirements: Requirements::new(),
+            cancel_incoming: false,
         }
     }
 
@@ -77,8 +87,16 @@ impl CommandBuilder {
     }
 
     #[must_use]
-    pub fn with_requirements(mut self, requirements: Vec<u8>) -> Self {
-        self.requirements = requirements;
+    pub fn with_requirements(mut self, requirements: impl Into<Requirements>) -> Self {
+        self.requirements = requirements.into();
+        self
+    }
+
+    /// Keeps the command running when another one needing its requirements is scheduled,
+    /// the incoming command isn't scheduled instead.
+    #[must_use]
+    pub const fn cancel_incoming(mut self, cancel_incoming: bool) -> Self {
+        self.cancel_incoming = cancel_incoming;
         self
     }
 
@@ -90,33 +108,43 @@ impl CommandBuilder {
             end: self.end,
             is_finished: self.is_finished,
             requirements: self.requirements,
+            cancel_incoming: self.cancel_incoming,
         })
     }
 }
 
 impl CommandBuilder {
-    pub fn start_only(init: impl FnMut() + 'static, requirements: Vec<u8>) -> Command {
+    pub fn start_only(
+        init: impl FnMut() + 'static,
+        requirements: impl Into<Requirements>,
+    ) -> Command {
         Self::new()
             .init(init)
             .with_requirements(requirements)
             .build()
     }
 
-    pub fn run_only(periodic: impl FnMut() + 'static, requirements: Vec<u8>) -> Command {
+    pub fn run_only(
+        periodic: impl FnMut() + 'static,
+        requirements: impl Into<Requirements>,
+    ) -> Command {
         Self::new()
             .periodic(periodic)
             .with_requirements(requirements)
             .build()
     }
 
-    pub fn end_only(end: impl FnMut(bool) + 'static, requirements: Vec<u8>) -> Command {
+    pub fn end_only(
+        end: impl FnMut(bool) + 'static,
+        requirements: impl Into<Requirements>,
+    ) -> Command {
         Self::new().end(end).with_requirements(requirements).build()
     }
 
     pub fn run_start(
         init: impl FnMut() + 'static,
         periodic: impl FnMut() + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .init(init)
@@ -128,7 +156,7 @@ impl CommandBuilder {
     pub fn run_end(
         periodic: impl FnMut() + 'static,
         end: impl FnMut(bool) + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .periodic(periodic)
@@ -140,7 +168,7 @@ impl CommandBuilder {
     pub fn start_end(
         init: impl FnMut() + 'static,
         end: impl FnMut(bool) + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .init(init)
@@ -153,7 +181,7 @@ impl CommandBuilder {
         init: impl FnMut() + 'static,
         periodic: impl FnMut() + 'static,
         end: impl FnMut(bool) + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .init(init)
@@ -166,7 +194,7 @@ impl CommandBuilder {
     pub fn run_until(
         is_finished: impl FnMut() -> bool + 'static,
         periodic: impl FnMut() + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .is_finished(is_finished)
@@ -179,7 +207,7 @@ impl CommandBuilder {
         is_finished: impl FnMut() -> bool + 'static,
         periodic: impl FnMut() + 'static,
         end: impl FnMut(bool) + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .is_finished(is_finished)
@@ -192,7 +220,7 @@ impl CommandBuilder {
     pub fn start_run_until(
         init: impl FnMut() + 'static,
         is_finished: impl FnMut() -> bool + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .init(init)
@@ -206,7 +234,7 @@ impl CommandBuilder {
         periodic: impl FnMut() + 'static,
         end: impl FnMut(bool) + 'static,
         is_finished: impl FnMut() -> bool + 'static,
-        requirements: Vec<u8>,
+        requirements: impl Into<Requirements>,
     ) -> Command {
         Self::new()
             .init(init)
@@ -223,7 +251,8 @@ pub struct SimpleBuiltCommand {
     periodic: Option<Box<dyn FnMut()>>,
     end: Option<Box<dyn FnMut(bool)>>,
     is_finished: Option<Box<dyn FnMut() -> bool>>,
-    requirements: Vec<u8>,
+    requirements: Requirements,
+    cancel_incoming: bool,
 }
 impl CommandTrait for SimpleBuiltCommand {
     fn init(&mut self) {
@@ -250,9 +279,13 @@ impl CommandTrait for SimpleBuiltCommand {
             .map_or(false, |is_finished| is_finished())
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
+    fn get_requirements(&self) -> Requirements {
         self.requirements.clone()
     }
+
+    fn cancel_incoming(&self) -> bool {
+        self.cancel_incoming
+    }
 }
 impl Debug for SimpleBuiltCommand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -262,19 +295,32 @@ impl Debug for SimpleBuiltCommand {
             .field("end", &self.end.is_some())
             .field("is_finished", &self.is_finished.is_some())
             .field("requirements", &self.requirements)
+            .field("cancel_incoming", &self.cancel_incoming)
             .finish()
     }
 }
 
+/// When a [`ParallelBuiltCommand`] finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParallelMode {
+    /// Once every command has.
+    All,
+    /// Once any command has.
+    Race,
+    /// Once the first command has.
+    Deadline,
+}
+
 #[derive(Debug)]
 pub struct ParallelBuiltCommand {
     commands: Vec<Command>,
     finished: Vec<bool>,
-    requirements: HashSet<u8>,
-    race: bool,
+    requirements: Requirements,
+    mode: ParallelMode,
 }
 impl CommandTrait for ParallelBuiltCommand {
     fn init(&mut self) {
+        self.finished.fill(false);
         for command in &mut self.commands {
             command.init();
         }
@@ -292,27 +338,34 @@ impl CommandTrait for ParallelBuiltCommand {
         }
     }
 
-    fn end(&mut self, interrupted: bool) {
-        if interrupted {
-            for (i, command) in self.commands.iter_mut().enumerate() {
-                if !self.finished[i] {
-                    command.end(true);
-                    self.finished[i] = true;
-                }
+    fn end(&mut self, _interrupted: bool) {
+        //races and deadlines end before all of their commands have finished
+        for (i, command) in self.commands.iter_mut().enumerate() {
+            if !self.finished[i] {
+                command.end(true);
+                self.finished[i] = true;
             }
         }
     }
 
     fn is_finished(&mut self) -> bool {
-        if self.race {
-            self.finished.iter().any(|&finished| finished)
-        } else {
-            self.finished.iter().all(|&finished| finished)
+        match self.mode {
+            ParallelMode::All => self.finished.iter().all(|&finished| finished),
+            ParallelMode::Race => self.finished.iter().any(|&finished| finished),
+            ParallelMode::Deadline => self.finished[0],
         }
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
-        self.requirements.clone().into_iter().collect()
+    fn get_requirements(&self) -> Requirements {
+        self.requirements.clone()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.commands.iter().all(CommandTrait::run_when_disabled)
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.commands.iter().any(CommandTrait::cancel_incoming)
     }
 
     fn get_name(&self) -> String {
@@ -329,11 +382,15 @@ impl CommandTrait for ParallelBuiltCommand {
 pub struct SequentialCommand {
     commands: Vec<Command>,
     current: usize,
-    requirements: HashSet<u8>,
+    requirements: Requirements,
 }
 impl CommandTrait for SequentialCommand {
     fn init(&mut self) {
-        self.commands[self.current].init();
+        //starts from the top every time so the sequence can be run again
+        self.current = 0;
+        if let Some(command) = self.commands.first_mut() {
+            command.init();
+        }
     }
 
     fn periodic(&mut self) {
@@ -359,8 +416,16 @@ impl CommandTrait for SequentialCommand {
         self.current >= self.commands.len()
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
-        self.requirements.clone().into_iter().collect()
+    fn get_requirements(&self) -> Requirements {
+        self.requirements.clone()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.commands.iter().all(CommandTrait::run_when_disabled)
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.commands.iter().any(CommandTrait::cancel_incoming)
     }
 
     fn get_name(&self) -> String {
@@ -372,42 +437,58 @@ impl CommandTrait for SequentialCommand {
     }
 }
 
+/// Builds its command every time it's initialized, see [`Command::defer`].
+///
+/// The requirements and flags are declared up front since the scheduler checks them
+/// before the command exists.
 pub struct ProxyCommand {
     command_supplier: Box<dyn FnMut() -> Command>,
     command: Option<Box<Command>>,
+    requirements: Requirements,
+    run_when_disabled: bool,
+    cancel_incoming: bool,
 }
 impl CommandTrait for ProxyCommand {
     fn init(&mut self) {
-        if self.command.is_none() {
-            self.command = Some(Box::new((self.command_supplier)()));
-        }
-        self.command.as_mut().expect("Command Empty").init();
+        let command = self.command.insert(Box::new((self.command_supplier)()));
+        command.init();
     }
 
     fn periodic(&mut self) {
-        self.command.as_mut().expect("Command Empty").periodic();
+        if let Some(command) = &mut self.command {
+            command.periodic();
+        }
     }
 
     fn end(&mut self, interrupted: bool) {
+        if let Some(command) = &mut self.command {
+            command.end(interrupted);
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
         self.command
             .as_mut()
-            .expect("Command Empty")
-            .end(interrupted);
+            .is_none_or(|command| command.is_finished())
     }
 
-    fn is_finished(&mut self) -> bool {
-        self.command.as_mut().expect("Command Empty").is_finished()
+    fn get_requirements(&self) -> Requirements {
+        self.requirements.clone()
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
-        self.command
-            .as_ref()
-            .expect("Command Empty")
-            .get_requirements()
+    fn run_when_disabled(&self) -> bool {
+        self.run_when_disabled
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.cancel_incoming
     }
 
     fn get_name(&self) -> String {
-        self.command.as_ref().expect("Command Empty").get_name()
+        self.command.as_ref().map_or_else(
+            || String::from("deferred command"),
+            |command| command.get_name(),
+        )
     }
 }
 impl Debug for ProxyCommand {
@@ -418,31 +499,56 @@ impl Debug for ProxyCommand {
         } else {
             dbg_struct.field("command", &"None");
         };
-        dbg_struct.finish()
+        dbg_struct
+            .field("requirements", &self.requirements)
+            .field("run_when_disabled", &self.run_when_disabled)
+            .field("cancel_incoming", &self.cancel_incoming)
+            .finish_non_exhaustive()
     }
 }
 
-#[allow(missing_copy_implementations)]
+/// Finishes once its time has passed, timed on a [`Clock`](crate::timer::Clock).
 #[derive(Debug)]
 pub struct WaitCommand {
-    start_instant: Option<std::time::Instant>,
-    duration: std::time::Duration,
+    timer: Timer,
+    duration: Duration,
+}
+impl WaitCommand {
+    /// Waits `time` on the default clock, a negative time finishes right away.
+    #[must_use]
+    pub fn new(time: Second) -> Self {
+        Self::with_clock(time, get_default_clock())
+    }
+
+    #[must_use]
+    pub fn with_clock(time: Second, clock: ClockRef) -> Self {
+        Self {
+            timer: Timer::with_clock(clock),
+            duration: Duration::try_from_secs_f64(time.value()).unwrap_or_default(),
+        }
+    }
 }
 impl CommandTrait for WaitCommand {
     fn init(&mut self) {
-        self.start_instant = Some(std::time::Instant::now());
+        self.timer.restart();
     }
 
     fn periodic(&mut self) {}
 
-    fn end(&mut self, _interrupted: bool) {}
+    fn end(&mut self, _interrupted: bool) {
+        self.timer.stop();
+    }
 
     fn is_finished(&mut self) -> bool {
-        self.start_instant.expect("Command Empty").elapsed() >= self.duration
+        self.timer.has_elapsed(self.duration)
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
-        vec![]
+    fn get_requirements(&self) -> Requirements {
+        Requirements::new()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        true
     }
 
     fn get_name(&self) -> String {
@@ -472,15 +578,426 @@ impl CommandTrait for NamedCommand {
         self.command.is_finished()
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
+    fn get_requirements(&self) -> Requirements {
         self.command.get_requirements()
     }
 
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
     fn get_name(&self) -> String {
         self.name.clone()
     }
 }
 
+/// Runs whichever of its commands a selector picks when initialized, see [`Command::select`].
+pub struct SelectCommand {
+    commands: Vec<Command>,
+    selector: Box<dyn FnMut() -> Option<usize>>,
+    selected: Option<usize>,
+    requirements: Requirements,
+}
+impl CommandTrait for SelectCommand {
+    fn init(&mut self) {
+        self.selected = (self.selector)();
+        if let Some(command) = self.selected.map(|i| &mut self.commands[i]) {
+            command.init();
+        } else {
+            tracing::warn!(
+                "No command for the selected key, {} does nothing",
+                self.get_name()
+            );
+        }
+    }
+
+    fn periodic(&mut self) {
+        if let Some(i) = self.selected {
+            self.commands[i].periodic();
+        }
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        if let Some(i) = self.selected {
+            self.commands[i].end(interrupted);
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.selected.is_none_or(|i| self.commands[i].is_finished())
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.requirements.clone()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.commands.iter().all(CommandTrait::run_when_disabled)
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.commands.iter().any(CommandTrait::cancel_incoming)
+    }
+
+    fn get_name(&self) -> String {
+        self.commands
+            .iter()
+            .map(command::commands::CommandTrait::get_name)
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+}
+impl Debug for SelectCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("SelectCommand")
+            .field("commands", &self.commands)
+            .field("selected", &self.selected)
+            .field("requirements", &self.requirements)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Interrupts the command once it has run for its timeout, see [`Command::with_timeout`].
+#[derive(Debug)]
+pub struct TimeoutCommand {
+    command: Box<Command>,
+    timer: WaitCommand,
+    finished: bool,
+}
+impl CommandTrait for TimeoutCommand {
+    fn init(&mut self) {
+        self.finished = false;
+        self.timer.init();
+        self.command.init();
+    }
+
+    fn periodic(&mut self) {
+        self.command.periodic();
+        self.finished = self.command.is_finished();
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted || !self.finished);
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.finished || self.timer.is_finished()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+
+/// Interrupts the command once a condition is true, see [`Command::until`].
+pub struct UntilCommand {
+    command: Box<Command>,
+    condition: Box<dyn FnMut() -> bool>,
+    finished: bool,
+}
+impl CommandTrait for UntilCommand {
+    fn init(&mut self) {
+        self.finished = false;
+        self.command.init();
+    }
+
+    fn periodic(&mut self) {
+        self.command.periodic();
+        self.finished = self.command.is_finished();
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted || !self.finished);
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.finished || (self.condition)()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+impl Debug for UntilCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("UntilCommand")
+            .field("command", &self.command)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs the command only if a condition is true when initialized, otherwise finishes
+/// right away, see [`Command::only_if`].
+pub struct OnlyIfCommand {
+    command: Box<Command>,
+    condition: Box<dyn FnMut() -> bool>,
+    skipped: bool,
+}
+impl CommandTrait for OnlyIfCommand {
+    fn init(&mut self) {
+        self.skipped = !(self.condition)();
+        if !self.skipped {
+            self.command.init();
+        }
+    }
+
+    fn periodic(&mut self) {
+        if !self.skipped {
+            self.command.periodic();
+        }
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        if !self.skipped {
+            self.command.end(interrupted);
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.skipped || self.command.is_finished()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+impl Debug for OnlyIfCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("OnlyIfCommand")
+            .field("command", &self.command)
+            .field("skipped", &self.skipped)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Starts the command again every time it finishes, see [`Command::repeatedly`].
+#[derive(Debug)]
+pub struct RepeatCommand {
+    command: Box<Command>,
+    ended: bool,
+}
+impl CommandTrait for RepeatCommand {
+    fn init(&mut self) {
+        self.ended = false;
+        self.command.init();
+    }
+
+    fn periodic(&mut self) {
+        if self.ended {
+            self.ended = false;
+            self.command.init();
+        }
+        self.command.periodic();
+        if self.command.is_finished() {
+            self.command.end(false);
+            self.ended = true;
+        }
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        //the command may have already ended in the last periodic
+        if !self.ended {
+            self.command.end(interrupted);
+            self.ended = true;
+        }
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+
+/// Overrides whether the command runs while the robot is disabled, see [`Command::ignoring_disable`].
+#[derive(Debug)]
+pub struct IgnoringDisableCommand {
+    command: Box<Command>,
+    run_when_disabled: bool,
+}
+impl CommandTrait for IgnoringDisableCommand {
+    fn init(&mut self) {
+        self.command.init();
+    }
+
+    fn periodic(&mut self) {
+        self.command.periodic();
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted);
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.command.is_finished()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.run_when_disabled
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+
+/// Calls a function after the command ends, see [`Command::finally_do`].
+pub struct FinallyCommand {
+    command: Box<Command>,
+    finally: Option<Box<dyn FnOnce(bool)>>,
+}
+impl CommandTrait for FinallyCommand {
+    fn init(&mut self) {
+        self.command.init();
+    }
+
+    fn periodic(&mut self) {
+        self.command.periodic();
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.command.end(interrupted);
+        if let Some(finally) = self.finally.take() {
+            finally(interrupted);
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.command.is_finished()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.command.get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.command.run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.command.cancel_incoming()
+    }
+
+    fn get_name(&self) -> String {
+        self.command.get_name()
+    }
+}
+impl Debug for FinallyCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("FinallyCommand")
+            .field("command", &self.command)
+            .field("finally", &self.finally.is_some())
+            .finish()
+    }
+}
+
+/// Schedules the command on its own when initialized and finishes when it does,
+/// see [`Command::as_proxy`].
+///
+/// The scheduler hands the command back once it has ended, so the proxy can run it again
+/// once it's been re-initialized.
+#[derive(Debug)]
+pub struct ScheduleProxyCommand {
+    command: Option<Box<Command>>,
+    name: String,
+    run_when_disabled: bool,
+    queue: DeferredQueue,
+    proxied: Option<Arc<Proxied>>,
+}
+impl CommandTrait for ScheduleProxyCommand {
+    fn init(&mut self) {
+        if let Some(command) = self
+            .proxied
+            .as_ref()
+            .and_then(|proxied| proxied.take_command())
+        {
+            self.command = Some(Box::new(command));
+        }
+        if let Some(command) = self.command.take() {
+            self.proxied = Some(self.queue.schedule(*command));
+        }
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        if let (true, Some(proxied)) = (interrupted, &self.proxied) {
+            if !proxied.is_done() {
+                self.queue.cancel(proxied);
+            }
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.proxied.as_deref().is_none_or(Proxied::is_done)
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.run_when_disabled
+    }
+
+    fn get_name(&self) -> String {
+        format!("Proxy({})", self.name)
+    }
+}
+
 pub enum Command {
     Parallel(ParallelBuiltCommand),
     Sequential(SequentialCommand),
@@ -489,6 +1006,15 @@ pub enum Command {
     Named(NamedCommand),
     Wait(WaitCommand),
     Proxy(ProxyCommand),
+    Select(SelectCommand),
+    Timeout(TimeoutCommand),
+    Until(UntilCommand),
+    OnlyIf(OnlyIfCommand),
+    Repeat(RepeatCommand),
+    IgnoringDisable(IgnoringDisableCommand),
+    Finally(FinallyCommand),
+    ScheduleProxy(ScheduleProxyCommand),
+    StateMachine(StateMachineCommand),
 }
 impl Debug for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -506,80 +1032,101 @@ impl Debug for Command {
             Self::Named(command) => f.debug_struct("Named").field("command", command).finish(),
             Self::Wait(command) => f.debug_struct("Wait").field("command", command).finish(),
             Self::Proxy(command) => f.debug_struct("Proxy").field("command", command).finish(),
+            Self::Select(command) => f.debug_struct("Select").field("command", command).finish(),
+            Self::Timeout(command) => f.debug_struct("Timeout").field("command", command).finish(),
+            Self::Until(command) => f.debug_struct("Until").field("command", command).finish(),
+            Self::OnlyIf(command) => f.debug_struct("OnlyIf").field("command", command).finish(),
+            Self::Repeat(command) => f.debug_struct("Repeat").field("command", command).finish(),
+            Self::IgnoringDisable(command) => f
+                .debug_struct("IgnoringDisable")
+                .field("command", command)
+                .finish(),
+            Self::Finally(command) => f.debug_struct("Finally").field("command", command).finish(),
+            Self::ScheduleProxy(command) => f
+                .debug_struct("ScheduleProxy")
+                .field("command", command)
+                .finish(),
+            Self::StateMachine(command) => f
+                .debug_struct("StateMachine")
+                .field("command", command)
+                .finish(),
         }
     }
 }
-impl CommandTrait for Command {
-    fn init(&mut self) {
+impl Command {
+    fn as_trait(&self) -> &dyn CommandTrait {
         match self {
-            Self::Parallel(command) => command.init(),
-            Self::Sequential(command) => command.init(),
-            Self::Simple(command) => command.init(),
-            Self::Custom(command) => command.init(),
-            Self::Named(command) => command.init(),
-            Self::Wait(command) => command.init(),
-            Self::Proxy(command) => command.init(),
+            Self::Parallel(command) => command,
+            Self::Sequential(command) => command,
+            Self::Simple(command) => command,
+            Self::Custom(command) => command.as_ref(),
+            Self::Named(command) => command,
+            Self::Wait(command) => command,
+            Self::Proxy(command) => command,
+            Self::Select(command) => command,
+            Self::Timeout(command) => command,
+            Self::Until(command) => command,
+            Self::OnlyIf(command) => command,
+            Self::Repeat(command) => command,
+            Self::IgnoringDisable(command) => command,
+            Self::Finally(command) => command,
+            Self::ScheduleProxy(command) => command,
+            Self::StateMachine(command) => command,
         }
     }
 
-    fn periodic(&mut self) {
+    fn as_trait_mut(&mut self) -> &mut dyn CommandTrait {
         match self {
-            Self::Parallel(command) => command.periodic(),
-            Self::Sequential(command) => command.periodic(),
-            Self::Simple(command) => command.periodic(),
-            Self::Custom(command) => command.periodic(),
-            Self::Named(command) => command.periodic(),
-            Self::Wait(command) => command.periodic(),
-            Self::Proxy(command) => command.periodic(),
+            Self::Parallel(command) => command,
+            Self::Sequential(command) => command,
+            Self::Simple(command) => command,
+            Self::Custom(command) => command.as_mut(),
+            Self::Named(command) => command,
+            Self::Wait(command) => command,
+            Self::Proxy(command) => command,
+            Self::Select(command) => command,
+            Self::Timeout(command) => command,
+            Self::Until(command) => command,
+            Self::OnlyIf(command) => command,
+            Self::Repeat(command) => command,
+            Self::IgnoringDisable(command) => command,
+            Self::Finally(command) => command,
+            Self::ScheduleProxy(command) => command,
+            Self::StateMachine(command) => command,
         }
     }
+}
+impl CommandTrait for Command {
+    fn init(&mut self) {
+        self.as_trait_mut().init();
+    }
+
+    fn periodic(&mut self) {
+        self.as_trait_mut().periodic();
+    }
 
     fn end(&mut self, interrupted: bool) {
-        match self {
-            Self::Parallel(command) => command.end(interrupted),
-            Self::Sequential(command) => command.end(interrupted),
-            Self::Simple(command) => command.end(interrupted),
-            Self::Custom(command) => command.end(interrupted),
-            Self::Named(command) => command.end(interrupted),
-            Self::Wait(command) => command.end(interrupted),
-            Self::Proxy(command) => command.end(interrupted),
-        }
+        self.as_trait_mut().end(interrupted);
     }
 
     fn is_finished(&mut self) -> bool {
-        match self {
-            Self::Parallel(command) => command.is_finished(),
-            Self::Sequential(command) => command.is_finished(),
-            Self::Simple(command) => command.is_finished(),
-            Self::Custom(command) => command.is_finished(),
-            Self::Named(command) => command.is_finished(),
-            Self::Wait(command) => command.is_finished(),
-            Self::Proxy(command) => command.is_finished(),
-        }
+        self.as_trait_mut().is_finished()
     }
 
-    fn get_requirements(&self) -> Vec<u8> {
-        match self {
-            Self::Parallel(command) => command.get_requirements(),
-            Self::Sequential(command) => command.get_requirements(),
-            Self::Simple(command) => command.get_requirements(),
-            Self::Custom(command) => command.get_requirements(),
-            Self::Named(command) => command.get_requirements(),
-            Self::Wait(command) => command.get_requirements(),
-            Self::Proxy(command) => command.get_requirements(),
-        }
+    fn get_requirements(&self) -> Requirements {
+        self.as_trait().get_requirements()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.as_trait().run_when_disabled()
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.as_trait().cancel_incoming()
     }
 
     fn get_name(&self) -> String {
-        match self {
-            Self::Parallel(command) => command.get_name(),
-            Self::Sequential(command) => command.get_name(),
-            Self::Simple(command) => command.get_name(),
-            Self::Custom(command) => command.get_name(),
-            Self::Named(command) => command.get_name(),
-            Self::Wait(command) => command.get_name(),
-            Self::Proxy(command) => command.get_name(),
-        }
+        self.as_trait().get_name()
     }
 }
 unsafe impl Send for Command {}
@@ -595,7 +1142,7 @@ impl Command {
                 .collect(),
             commands: vec![self, other],
             finished: vec![false, false],
-            race: false,
+            mode: ParallelMode::All,
         })
     }
 
@@ -610,7 +1157,7 @@ impl Command {
                 .flat_map(command::commands::CommandTrait::get_requirements)
                 .collect(),
             commands,
-            race: false,
+            mode: ParallelMode::All,
         })
     }
 
@@ -624,7 +1171,7 @@ impl Command {
                 .collect(),
             commands: vec![self, other],
             finished: vec![false, false],
-            race: true,
+            mode: ParallelMode::Race,
         })
     }
 
@@ -639,7 +1186,29 @@ impl Command {
                 .flat_map(command::commands::CommandTrait::get_requirements)
                 .collect(),
             commands,
-            race: true,
+            mode: ParallelMode::Race,
+        })
+    }
+
+    /// Runs `other` alongside this command until this one finishes, interrupting it if
+    /// it's still running.
+    #[must_use]
+    pub fn deadline_with(self, other: Self) -> Self {
+        self.deadline_with_many(vec![other])
+    }
+
+    #[must_use]
+    pub fn deadline_with_many(self, others: Vec<Self>) -> Self {
+        let mut commands = vec![self];
+        commands.extend(others);
+        Self::Parallel(ParallelBuiltCommand {
+            finished: vec![false; commands.len()],
+            requirements: commands
+                .iter()
+                .flat_map(command::commands::CommandTrait::get_requirements)
+                .collect(),
+            commands,
+            mode: ParallelMode::Deadline,
         })
     }
 
@@ -693,9 +1262,175 @@ impl Command {
 
     #[must_use]
     pub fn wait_for(self, seconds: f64) -> Self {
-        Self::Wait(WaitCommand {
-            duration: std::time::Duration::from_secs_f64(seconds),
-            start_instant: None,
+        Self::wait(Second::new(seconds))
+    }
+
+    /// Does nothing until `time` has passed on the default clock.
+    #[must_use]
+    pub fn wait(time: Second) -> Self {
+        Self::Wait(WaitCommand::new(time))
+    }
+
+    /// Interrupts the command if it hasn't finished after `timeout`.
+    #[must_use]
+    pub fn with_timeout(self, timeout: Second) -> Self {
+        Self::Timeout(TimeoutCommand {
+            command: Box::new(self),
+            timer: WaitCommand::new(timeout),
+            finished: false,
+        })
+    }
+
+    /// Interrupts the command once `condition` is true, it's checked after every periodic.
+    #[must_use]
+    pub fn until(self, condition: impl FnMut() -> bool + 'static) -> Self {
+        Self::Until(UntilCommand {
+            command: Box::new(self),
+            condition: Box::new(condition),
+            finished: false,
+        })
+    }
+
+    /// Interrupts the command once `condition` is false.
+    #[must_use]
+    pub fn only_while(self, mut condition: impl FnMut() -> bool + 'static) -> Self {
+        self.until(move || !condition())
+    }
+
+    /// Skips the command if `condition` is false when it's initialized.
+    #[must_use]
+    pub fn only_if(self, condition: impl FnMut() -> bool + 'static) -> Self {
+        Self::OnlyIf(OnlyIfCommand {
+            command: Box::new(self),
+            condition: Box::new(condition),
+            skipped: false,
+        })
+    }
+
+    /// Skips the command if `condition` is true when it's initialized.
+    #[must_use]
+    pub fn unless(self, mut condition: impl FnMut() -> bool + 'static) -> Self {
+        self.only_if(move || !condition())
+    }
+
+    /// Runs the command again every time it finishes, until interrupted.
+    #[must_use]
+    pub fn repeatedly(self) -> Self {
+        Self::Repeat(RepeatCommand {
+            command: Box::new(self),
+            ended: false,
+        })
+    }
+
+    /// Sets whether the command runs while the robot is disabled, the scheduler refuses or
+    /// interrupts the ones that don't while it's [disabled](CommandScheduler::set_disabled).
+    #[must_use]
+    pub fn ignoring_disable(self, run_when_disabled: bool) -> Self {
+        Self::IgnoringDisable(IgnoringDisableCommand {
+            command: Box::new(self),
+            run_when_disabled,
+        })
+    }
+
+    /// Calls `finally` with whether the command was interrupted, the first time it ends.
+    #[must_use]
+    pub fn finally_do(self, finally: impl FnOnce(bool) + 'static) -> Self {
+        Self::Finally(FinallyCommand {
+            command: Box::new(self),
+            finally: Some(Box::new(finally)),
+        })
+    }
+
+    /// Calls `handler` the first time the command is interrupted.
+    #[must_use]
+    pub fn handle_interrupt(self, handler: impl FnOnce() + 'static) -> Self {
+        self.finally_do(move |interrupted| {
+            if interrupted {
+                handler();
+            }
+        })
+    }
+
+    /// Schedules the command on the [`CommandManager`](super::CommandManager) scheduler
+    /// instead of running it inside whatever this is composed into, see [`Command::as_proxy_on`].
+    #[must_use]
+    pub fn as_proxy(self) -> Self {
+        self.as_proxy_on(&CommandManager::get_scheduler())
+    }
+
+    /// Schedules the command on `scheduler` when the proxy is initialized, the proxy
+    /// finishes when the command does and cancels it if interrupted.
+    ///
+    /// The proxy has no requirements, so a group holding it doesn't hold the command's.
+    /// The command is scheduled once the scheduler's current run is over.
+    #[must_use]
+    pub fn as_proxy_on(self, scheduler: &CommandScheduler) -> Self {
+        Self::ScheduleProxy(ScheduleProxyCommand {
+            name: self.get_name(),
+            run_when_disabled: self.run_when_disabled(),
+            command: Some(Box::new(self)),
+            queue: scheduler.get_deferred_queue(),
+            proxied: None,
+        })
+    }
+
+    /// Runs the command `selector` picks when initialized, nothing runs if it picks a key
+    /// without one.
+    ///
+    /// Requires everything every command does, whichever ends up running.
+    #[must_use]
+    pub fn select<K: Hash + Eq + 'static>(
+        commands: HashMap<K, Self>,
+        mut selector: impl FnMut() -> K + 'static,
+    ) -> Self {
+        let mut indices = HashMap::with_capacity(commands.len());
+        let mut list = Vec::with_capacity(commands.len());
+        for (key, command) in commands {
+            indices.insert(key, list.len());
+            list.push(command);
+        }
+        Self::Select(SelectCommand {
+            requirements: list
+                .iter()
+                .flat_map(command::commands::CommandTrait::get_requirements)
+                .collect(),
+            commands: list,
+            selector: Box::new(move || indices.get(&selector()).copied()),
+            selected: None,
+        })
+    }
+
+    /// Runs `on_true` if `condition` is true when initialized, otherwise `on_false`.
+    #[must_use]
+    pub fn either(
+        on_true: Self,
+        on_false: Self,
+        condition: impl FnMut() -> bool + 'static,
+    ) -> Self {
+        Self::select(
+            HashMap::from([(true, on_true), (false, on_false)]),
+            condition,
+        )
+    }
+
+    /// Builds the command from `supplier` every time it's initialized, so it can depend
+    /// on what's going on at the time.
+    ///
+    /// Only `requirements` are held and only `run_when_disabled` and `cancel_incoming` are
+    /// reported to the scheduler, whatever the built command says.
+    #[must_use]
+    pub fn defer(
+        supplier: impl FnMut() -> Self + 'static,
+        requirements: impl Into<Requirements>,
+        run_when_disabled: bool,
+        cancel_incoming: bool,
+    ) -> Self {
+        Self::Proxy(ProxyCommand {
+            command_supplier: Box::new(supplier),
+            command: None,
+            requirements: requirements.into(),
+            run_when_disabled,
+            cancel_incoming,
         })
     }
 
@@ -706,7 +1441,7 @@ impl Command {
 
     #[must_use]
     pub fn empty() -> Self {
-        CommandBuilder::start_only(|| {}, vec![])
+        CommandBuilder::start_only(|| {}, Requirements::new())
     }
 }
 impl Default for Command {
@@ -1,4 +1,5 @@
 use super::manager::{Condition, ConditionResponse};
+use crate::driver_station::{DriverStation, DsEvent, DsSubscriber};
 use std::sync::Arc;
 #[derive(Clone)]
 pub struct OnTrue<T>
@@ -91,6 +92,152 @@ where
     }
 }
 
+#[derive(Clone)]
+pub struct OnChange<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    pub function: Arc<T>,
+    pub last_state: bool,
+}
+
+impl<T> Condition for OnChange<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    fn get_condition(&mut self) -> ConditionResponse {
+        let state = (self.function)();
+        let changed = state != self.last_state;
+        self.last_state = state;
+        if changed {
+            ConditionResponse::Start
+        } else {
+            ConditionResponse::NoChange
+        }
+    }
+
+    fn clone_boxed(&self) -> Box<dyn Condition> {
+        Box::new(Self {
+            function: self.function.clone(),
+            last_state: self.last_state,
+        })
+    }
+}
+
+impl<T> std::fmt::Debug for OnChange<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnChange")
+            .field("last_state", &self.last_state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// On a rising edge, cancels the command if it's running and starts it otherwise.
+///
+/// Whether it's running is checked on every edge, so a command that finished on its own
+/// starts again on the next press rather than being "cancelled".
+#[derive(Clone)]
+pub struct ToggleOnTrue<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    pub function: Arc<T>,
+    pub last_state: bool,
+}
+
+impl<T> Condition for ToggleOnTrue<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    fn get_condition(&mut self) -> ConditionResponse {
+        let state = (self.function)();
+        let rising = state && !self.last_state;
+        self.last_state = state;
+        if rising {
+            ConditionResponse::Toggle
+        } else {
+            ConditionResponse::NoChange
+        }
+    }
+
+    fn clone_boxed(&self) -> Box<dyn Condition> {
+        Box::new(Self {
+            function: self.function.clone(),
+            last_state: self.last_state,
+        })
+    }
+}
+
+impl<T> std::fmt::Debug for ToggleOnTrue<T>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToggleOnTrue")
+            .field("last_state", &self.last_state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub struct OnDsEvent<T>
+where
+    T: Fn(&DsEvent) -> bool + Send + Sync + 'static,
+{
+    pub filter: Arc<T>,
+    pub subscriber: DsSubscriber,
+}
+
+impl<T> Condition for OnDsEvent<T>
+where
+    T: Fn(&DsEvent) -> bool + Send + Sync + 'static,
+{
+    fn get_condition(&mut self) -> ConditionResponse {
+        if self
+            .subscriber
+            .poll()
+            .iter()
+            .any(|event| (self.filter)(event))
+        {
+            ConditionResponse::Start
+        } else {
+            ConditionResponse::NoChange
+        }
+    }
+
+    fn clone_boxed(&self) -> Box<dyn Condition> {
+        Box::new(Self {
+            filter: self.filter.clone(),
+            subscriber: self.subscriber,
+        })
+    }
+}
+
+impl<T> std::fmt::Debug for OnDsEvent<T>
+where
+    T: Fn(&DsEvent) -> bool + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnDsEvent")
+            .field("subscriber", &self.subscriber)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Starts the command whenever the driver station publishes an event matching `filter`.
+pub fn on_ds_event<T>(filter: T) -> OnDsEvent<impl Fn(&DsEvent) -> bool + Send + Sync + 'static>
+where
+    T: Fn(&DsEvent) -> bool + Send + Sync + 'static,
+{
+    OnDsEvent {
+        filter: Arc::new(filter),
+        subscriber: DriverStation::subscribe(),
+    }
+}
+
 pub fn on_true<T>(f: T) -> OnTrue<impl Fn() -> bool + Send + Sync + 'static>
 where
     T: Fn() -> bool + Send + Sync + 'static,
@@ -101,7 +248,7 @@ where
     }
 }
 
-pub fn on_false<T, F>(f: T) -> OnTrue<impl Fn() -> bool + Send + Sync + 'static>
+pub fn on_false<T>(f: T) -> OnTrue<impl Fn() -> bool + Send + Sync + 'static>
 where
     T: Fn() -> bool + Send + Sync + 'static,
 {
@@ -130,3 +277,26 @@ where
         last_state: false,
     }
 }
+
+/// Starts the command every time `f` changes, in either direction.
+pub fn on_change<T>(f: T) -> OnChange<impl Fn() -> bool + Send + Sync + 'static>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    //starts from the current value so the first poll isn't a change
+    let last_state = f();
+    OnChange {
+        function: Arc::new(f),
+        last_state,
+    }
+}
+
+pub fn toggle_on_true<T>(f: T) -> ToggleOnTrue<impl Fn() -> bool + Send + Sync + 'static>
+where
+    T: Fn() -> bool + Send + Sync + 'static,
+{
+    ToggleOnTrue {
+        function: Arc::new(f),
+        last_state: false,
+    }
+}
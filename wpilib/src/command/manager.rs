@@ -1,39 +1,69 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet},
+    future::{Future, IntoFuture},
+    hash::{Hash, Hasher},
     ops::Deref,
-    sync::Arc,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
 
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
-use super::{commands::CommandTrait, Command};
-
-static MANAGER: Mutex<Lazy<CommandManager>> = Mutex::new(Lazy::new(CommandManager::new));
+use super::{
+    arena::{Arena, ArenaKey},
+    commands::CommandTrait,
+    subsystem::SubsystemId,
+    Command,
+};
+use crate::{
+    robots::Watchdog,
+    timer::{get_default_clock, ClockRef},
+};
 
-type SubsystemSUID = u8;
+static MANAGER: Lazy<CommandScheduler> = Lazy::new(CommandScheduler::new);
 
 pub trait Subsystem {
     fn periodic(&self) {}
+
+    fn get_name(&self) -> String {
+        String::from("unnamed subsystem")
+    }
 }
 
 type SubsystemArc = Arc<Mutex<dyn Subsystem + Sync + Send>>;
+/// A shared subsystem and the id commands require it by.
 #[derive(Debug)]
-pub struct SubsystemRef<T: Subsystem + Sync + Send>(pub Arc<Mutex<T>>);
+pub struct SubsystemRef<T: Subsystem + Sync + Send>(pub Arc<Mutex<T>>, SubsystemId);
 
 impl<T: Subsystem + Sync + Send + 'static> SubsystemRef<T> {
+    /// Wraps `subsystem` under a new [`SubsystemId`].
+    pub fn new(subsystem: T) -> Self {
+        Self(Arc::new(Mutex::new(subsystem)), SubsystemId::next())
+    }
+
+    #[must_use]
+    pub const fn get_id(&self) -> SubsystemId {
+        self.1
+    }
+
+    #[must_use]
     pub fn get_arc(&self) -> SubsystemArc {
         self.0.clone()
     }
+    #[must_use]
     pub fn get_arc_impl(&self) -> Arc<Mutex<T>> {
         self.0.clone()
     }
 }
 impl<T: Subsystem + Sync + Send + 'static> Clone for SubsystemRef<T> {
     fn clone(&self) -> Self {
-        Self {
-            0: self.get_arc_impl(),
-        }
+        Self(self.get_arc_impl(), self.1)
     }
 }
 
@@ -45,92 +75,402 @@ impl<T: Subsystem + Sync + Send + 'static> Clone for SubsystemRef<T> {
 //     }
 // }
 
+/// What holds a subsystem, scheduled commands are keyed by generation so a stale index
+/// never reaches the command that reused its slot.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum CommandIndex {
+pub(super) enum CommandIndex {
     DefaultCommand(usize),
-    Command(usize),
+    Command(ArenaKey),
+}
+
+/// Refers to one scheduled command.
+///
+/// Slots are reused once a command ends, the generation keeps an old handle from
+/// seeing the command that took its place. Awaiting a handle resolves once the command
+/// has ended, however it ended, or once its scheduler is dropped.
+///
+/// Handles can be used from anything the scheduler runs, like a trigger's condition or a
+/// command's `periodic`. A cancel made while the scheduler is busy happens once its run is over.
+#[derive(Debug, Clone)]
+pub struct CommandHandle {
+    scheduler: Weak<Mutex<SchedulerCore>>,
+    deferred: Weak<Mutex<Vec<Deferred>>>,
+    state: Arc<HandleState>,
+}
+
+impl CommandHandle {
+    #[must_use]
+    pub fn is_scheduled(&self) -> bool {
+        self.scheduler.strong_count() > 0
+            && self.state.get_key().is_some()
+            && !self.state.is_ended()
+    }
+
+    /// Ends the command as interrupted, returns false if it had already ended.
+    #[must_use]
+    pub fn cancel(&self) -> bool {
+        let Some(core) = self.scheduler.upgrade() else {
+            return false;
+        };
+        if let (Some(key), Some(mut core)) = (self.state.get_key(), core.try_lock()) {
+            let running = core.commands.contains(key);
+            if running {
+                core.interrupt_holder(CommandIndex::Command(key), &InterruptReason::Cancelled);
+            }
+            return running;
+        }
+        if self.state.is_ended() {
+            return false;
+        }
+        //the scheduler is busy, likely running whatever is cancelling
+        if let Some(deferred) = self.deferred.upgrade() {
+            deferred
+                .lock()
+                .push(Deferred::CancelHandle(self.state.clone()));
+        }
+        true
+    }
+
+    /// Time since the command was scheduled, `None` once it has ended.
+    #[must_use]
+    pub fn time_running(&self) -> Option<Duration> {
+        if !self.is_scheduled() {
+            return None;
+        }
+        let (_, scheduled_at) = (*self.state.scheduled.lock())?;
+        Some(self.state.clock.now().saturating_sub(scheduled_at))
+    }
+}
+
+impl PartialEq for CommandHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl Eq for CommandHandle {}
+
+impl Hash for CommandHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(Arc::as_ptr(&self.state), state);
+    }
+}
+
+/// What a [`CommandHandle`] knows about its command, kept apart from the scheduler so a
+/// handle can be read while the scheduler is running.
+#[derive(Debug)]
+struct HandleState {
+    clock: ClockRef,
+    //`None` until the command is in the scheduler, a deferred schedule fills it in later
+    scheduled: Mutex<Option<(ArenaKey, Duration)>>,
+    completion: Mutex<Completion>,
+}
+
+#[derive(Debug, Default)]
+struct Completion {
+    ended: bool,
+    waiters: Vec<Waker>,
+}
+
+impl HandleState {
+    fn new(clock: ClockRef) -> Arc<Self> {
+        Arc::new(Self {
+            clock,
+            scheduled: Mutex::new(None),
+            completion: Mutex::new(Completion::default()),
+        })
+    }
+
+    fn get_key(&self) -> Option<ArenaKey> {
+        self.scheduled.lock().map(|(key, _)| key)
+    }
+
+    fn is_ended(&self) -> bool {
+        self.completion.lock().ended
+    }
+
+    fn end(&self) {
+        let waiters = {
+            let mut completion = self.completion.lock();
+            completion.ended = true;
+            std::mem::take(&mut completion.waiters)
+        };
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+}
+
+impl IntoFuture for CommandHandle {
+    type Output = ();
+    type IntoFuture = CommandCompletion;
+
+    fn into_future(self) -> Self::IntoFuture {
+        CommandCompletion { handle: self }
+    }
+}
+
+/// Resolves once the command behind a [`CommandHandle`] finishes or is interrupted.
+#[derive(Debug)]
+pub struct CommandCompletion {
+    handle: CommandHandle,
+}
+
+impl Future for CommandCompletion {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.handle.scheduler.strong_count() == 0 {
+            return Poll::Ready(());
+        }
+        let mut completion = self.handle.state.completion.lock();
+        if completion.ended {
+            return Poll::Ready(());
+        }
+        if !completion
+            .waiters
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            completion.waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// A command scheduled on behalf of a [`ScheduleProxyCommand`](super::commands::ScheduleProxyCommand),
+/// the scheduler hands the command back once it's done with it so the proxy can run it again.
+#[derive(Debug, Default)]
+pub(super) struct Proxied {
+    key: Mutex<Option<ArenaKey>>,
+    command: Mutex<Option<Command>>,
+    done: AtomicBool,
+}
+
+impl Proxied {
+    /// True once the command has ended or if it couldn't be scheduled.
+    pub(super) fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+
+    fn finish(&self, command: Command) {
+        *self.command.lock() = Some(command);
+        self.done.store(true, Ordering::Release);
+    }
+
+    /// The command, once it's done.
+    pub(super) fn take_command(&self) -> Option<Command> {
+        self.command.lock().take()
+    }
+}
+
+#[derive(Debug)]
+enum Deferred {
+    Schedule(Command, Arc<Proxied>),
+    Cancel(Arc<Proxied>),
+    ScheduleHandle(Command, Arc<HandleState>),
+    CancelHandle(Arc<HandleState>),
+}
+
+/// Lets a command ask its scheduler for work while the scheduler is running it,
+/// the work is done once the current run is over.
+#[derive(Debug, Clone)]
+pub(super) struct DeferredQueue(Weak<Mutex<Vec<Deferred>>>);
+
+impl DeferredQueue {
+    pub(super) fn schedule(&self, command: Command) -> Arc<Proxied> {
+        let proxied = Arc::new(Proxied::default());
+        if let Some(queue) = self.0.upgrade() {
+            queue
+                .lock()
+                .push(Deferred::Schedule(command, proxied.clone()));
+        } else {
+            proxied.finish(command);
+        }
+        proxied
+    }
+
+    pub(super) fn cancel(&self, proxied: &Arc<Proxied>) {
+        if let Some(queue) = self.0.upgrade() {
+            queue.lock().push(Deferred::Cancel(proxied.clone()));
+        }
+    }
+}
+
+/// A command lifecycle event, passed to the hooks registered on a [`CommandScheduler`].
+#[derive(Debug, Clone)]
+pub struct CommandEvent {
+    /// The command's [`get_name`](CommandTrait::get_name).
+    pub name: String,
+    /// `None` for default commands.
+    pub handle: Option<CommandHandle>,
+    /// When it happened, on the scheduler's clock.
+    pub timestamp: Duration,
+}
+
+/// Why a command was interrupted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterruptReason {
+    /// A command needing one of its requirements was scheduled, holds that command's name.
+    Requirement(String),
+    /// Cancelled through its handle, a conditional scheduler or [`CommandScheduler::cancel_all`].
+    Cancelled,
+    /// The robot was disabled and the command doesn't [run while disabled](CommandTrait::run_when_disabled).
+    Disabled,
+}
+
+type CommandHook = Box<dyn Fn(&CommandEvent) + Send + Sync>;
+type InterruptHook = Box<dyn Fn(&CommandEvent, &InterruptReason) + Send + Sync>;
+
+struct EventHooks {
+    scheduler: Weak<Mutex<SchedulerCore>>,
+    deferred: Weak<Mutex<Vec<Deferred>>>,
+    init: Vec<CommandHook>,
+    execute: Vec<CommandHook>,
+    finish: Vec<CommandHook>,
+    interrupt: Vec<InterruptHook>,
+}
+impl std::fmt::Debug for EventHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventHooks")
+            .field("init", &self.init.len())
+            .field("execute", &self.execute.len())
+            .field("finish", &self.finish.len())
+            .field("interrupt", &self.interrupt.len())
+            .finish_non_exhaustive()
+    }
+}
+impl EventHooks {
+    const fn new(
+        scheduler: Weak<Mutex<SchedulerCore>>,
+        deferred: Weak<Mutex<Vec<Deferred>>>,
+    ) -> Self {
+        Self {
+            scheduler,
+            deferred,
+            init: Vec::new(),
+            execute: Vec::new(),
+            finish: Vec::new(),
+            interrupt: Vec::new(),
+        }
+    }
+
+    fn event(
+        &self,
+        command: &Command,
+        state: Option<&Arc<HandleState>>,
+        timestamp: Duration,
+    ) -> CommandEvent {
+        CommandEvent {
+            name: command.get_name(),
+            handle: state.map(|state| CommandHandle {
+                scheduler: self.scheduler.clone(),
+                deferred: self.deferred.clone(),
+                state: state.clone(),
+            }),
+            timestamp,
+        }
+    }
+
+    //names are only built when something is listening
+
+    fn init(&self, command: &Command, state: Option<&Arc<HandleState>>, timestamp: Duration) {
+        if !self.init.is_empty() {
+            let event = self.event(command, state, timestamp);
+            self.init.iter().for_each(|hook| hook(&event));
+        }
+    }
+
+    fn execute(&self, command: &Command, state: Option<&Arc<HandleState>>, timestamp: Duration) {
+        if !self.execute.is_empty() {
+            let event = self.event(command, state, timestamp);
+            self.execute.iter().for_each(|hook| hook(&event));
+        }
+    }
+
+    fn finish(&self, command: &Command, state: Option<&Arc<HandleState>>, timestamp: Duration) {
+        if !self.finish.is_empty() {
+            let event = self.event(command, state, timestamp);
+            self.finish.iter().for_each(|hook| hook(&event));
+        }
+    }
+
+    fn interrupt(
+        &self,
+        command: &Command,
+        state: Option<&Arc<HandleState>>,
+        timestamp: Duration,
+        reason: &InterruptReason,
+    ) {
+        if !self.interrupt.is_empty() {
+            let event = self.event(command, state, timestamp);
+            self.interrupt.iter().for_each(|hook| hook(&event, reason));
+        }
+    }
 }
 
-pub struct CommandManager {
+#[derive(Debug)]
+struct ScheduledCommand {
+    command: Command,
+    state: Arc<HandleState>,
+    initialized: bool,
+    interrupted: bool,
+    proxied: Option<Arc<Proxied>>,
+}
+
+struct SchedulerCore {
     periodic_callbacks: Vec<SubsystemArc>,
-    commands: Vec<Option<Command>>,
-    interrupt_state: HashMap<CommandIndex, bool>,
+    commands: Arena<ScheduledCommand>,
     default_commands: Vec<Option<Command>>,
-    subsystem_to_default: HashMap<SubsystemSUID, CommandIndex>,
-    requirements: HashMap<SubsystemSUID, CommandIndex>,
-    initialized_commands: HashSet<CommandIndex>,
-    orphaned_commands: HashSet<CommandIndex>,
+    initialized_defaults: HashSet<usize>,
+    subsystem_to_default: HashMap<SubsystemId, CommandIndex>,
+    requirements: HashMap<SubsystemId, CommandIndex>,
     cond_schedulers: Vec<ConditionalScheduler>,
-    suid: SubsystemSUID,
+    clock: ClockRef,
+    hooks: EventHooks,
+    disabled: bool,
 }
-impl std::fmt::Debug for CommandManager {
+impl std::fmt::Debug for SchedulerCore {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("CommandManager")
+        f.debug_struct("SchedulerCore")
             .field("periodic_callbacks", &self.periodic_callbacks.len())
             .field("commands", &self.commands)
-            .field("interrupt_state", &self.interrupt_state)
             .field("default_commands", &self.default_commands)
+            .field("initialized_defaults", &self.initialized_defaults)
+            .field("subsystem_to_default", &self.subsystem_to_default)
             .field("requirements", &self.requirements)
-            .field("initialized_commands", &self.initialized_commands)
-            .field("orphaned_commands", &self.orphaned_commands)
             .field("cond_schedulers", &self.cond_schedulers)
+            .field("clock", &self.clock)
+            .field("hooks", &self.hooks)
+            .field("disabled", &self.disabled)
             .finish()
     }
 }
-impl CommandManager {
-    fn new() -> Self {
+impl SchedulerCore {
+    fn new(this: Weak<Mutex<Self>>, deferred: Weak<Mutex<Vec<Deferred>>>, clock: ClockRef) -> Self {
         Self {
             periodic_callbacks: Vec::new(),
-            commands: Vec::new(),
-            interrupt_state: HashMap::new(),
+            commands: Arena::new(),
             default_commands: Vec::new(),
+            initialized_defaults: HashSet::new(),
             subsystem_to_default: HashMap::new(),
             requirements: HashMap::new(),
-            initialized_commands: HashSet::new(),
-            orphaned_commands: HashSet::new(),
             cond_schedulers: Vec::new(),
-            suid: 0,
+            clock,
+            hooks: EventHooks::new(this, deferred),
+            disabled: false,
         }
     }
 
-    pub fn register_subsystem(
-        suid: SubsystemSUID,
-        subsystem: SubsystemArc,
-        default_command: Option<Command>,
-    ) {
-        let mut scheduler = MANAGER.lock();
-        scheduler.periodic_callbacks.push(subsystem);
-        scheduler.default_commands.push(default_command);
-        let idx = scheduler.default_commands.len() - 1;
-        scheduler
-            .subsystem_to_default
-            .insert(suid, CommandIndex::DefaultCommand(idx));
-        scheduler
-            .interrupt_state
-            .insert(CommandIndex::DefaultCommand(idx), false);
-
-        drop(scheduler);
-    }
-
-    pub fn get_suid() -> SubsystemSUID {
-        let mut scheduler = MANAGER.lock();
-        let newsuid = scheduler.suid;
-        scheduler.suid = scheduler.suid + 1;
-        drop(scheduler);
-        newsuid
-    }
-    /// Will run all periodic callbacks, run all conditional schedulers, init all un-initialized commands, and run all commands
-    /// in that order.
-    pub fn run() {
-        let mut scheduler = MANAGER.lock();
-        scheduler.run_subsystems();
-        scheduler.run_cond_schedulers();
-        scheduler.run_commands();
-    }
-
-    fn run_subsystems(&mut self) {
+    fn run_subsystems(&mut self, mut watchdog: Option<&mut Watchdog>) {
         for callback in &self.periodic_callbacks {
-            callback.lock().periodic();
+            let subsystem = callback.lock();
+            subsystem.periodic();
+            let name = watchdog.is_some().then(|| subsystem.get_name());
+            drop(subsystem);
+            if let (Some(watchdog), Some(name)) = (watchdog.as_deref_mut(), name) {
+                watchdog.add_epoch(&format!("{name}.periodic()"));
+            }
         }
         for (suid, cmd_idx) in &self.subsystem_to_default {
             if !self.requirements.contains_key(suid) {
@@ -148,141 +488,511 @@ impl CommandManager {
         self.cond_schedulers = new_conds;
     }
 
-    fn run_commands(&mut self) {
-        let mut to_remove: Vec<usize> = Vec::new();
-        let mut cmds = self.requirements.values().collect::<Vec<&CommandIndex>>();
-        cmds.extend(self.orphaned_commands.iter());
+    fn run_commands(&mut self, mut watchdog: Option<&mut Watchdog>) {
+        //removing a command never moves the others, so keys taken up front stay valid
+        for key in self.commands.keys() {
+            let Some(scheduled) = self.commands.get_mut(key) else {
+                continue;
+            };
+            if scheduled.interrupted {
+                self.interrupt_holder(CommandIndex::Command(key), &InterruptReason::Cancelled);
+                continue;
+            }
+            if self.disabled && !scheduled.command.run_when_disabled() {
+                self.interrupt_holder(CommandIndex::Command(key), &InterruptReason::Disabled);
+                continue;
+            }
+            if !scheduled.initialized {
+                scheduled.initialized = true;
+                scheduled.command.init();
+                self.hooks
+                    .init(&scheduled.command, Some(&scheduled.state), self.clock.now());
+            }
+            scheduled.command.periodic();
+            self.hooks
+                .execute(&scheduled.command, Some(&scheduled.state), self.clock.now());
+            if let Some(watchdog) = watchdog.as_deref_mut() {
+                watchdog.add_epoch(&format!("{}.periodic()", scheduled.command.get_name()));
+            }
+            if scheduled.command.is_finished() {
+                scheduled.command.end(false);
+                self.hooks
+                    .finish(&scheduled.command, Some(&scheduled.state), self.clock.now());
+                self.remove_command(key);
+            }
+        }
+
+        let defaults = self
+            .requirements
+            .values()
+            .filter_map(|index| match index {
+                CommandIndex::DefaultCommand(idx) => Some(*idx),
+                CommandIndex::Command(_) => None,
+            })
+            .collect::<Vec<_>>();
+        for idx in defaults {
+            let Some(command) = &mut self.default_commands[idx] else {
+                continue;
+            };
+            //a default command that can't run while disabled waits to start again once enabled
+            if self.disabled && !command.run_when_disabled() {
+                self.interrupt_holder(
+                    CommandIndex::DefaultCommand(idx),
+                    &InterruptReason::Disabled,
+                );
+                continue;
+            }
+            if self.initialized_defaults.insert(idx) {
+                command.init();
+                self.hooks.init(command, None, self.clock.now());
+            }
+            command.periodic();
+            self.hooks.execute(command, None, self.clock.now());
+            if let Some(watchdog) = watchdog.as_deref_mut() {
+                watchdog.add_epoch(&format!("{}.periodic()", command.get_name()));
+            }
+            if command.is_finished() {
+                command.end(false);
+                self.hooks.finish(command, None, self.clock.now());
+                //runs again from init next cycle
+                self.initialized_defaults.remove(&idx);
+            }
+        }
+    }
 
-        for index in cmds {
-            if let Some(command) = match index {
-                CommandIndex::Command(cmd) => &mut self.commands[*cmd],
-                CommandIndex::DefaultCommand(cmd) => &mut self.default_commands[*cmd],
-            } {
-                if !self.initialized_commands.contains(index) {
-                    command.init();
-                    self.initialized_commands.insert(*index);
+    /// Drops a command without ending it and hands its subsystems back to their default commands.
+    fn remove_command(&mut self, key: ArenaKey) {
+        let Some(scheduled) = self.commands.remove(key) else {
+            return;
+        };
+        scheduled.state.end();
+        let cmd_idx = CommandIndex::Command(key);
+        for req in scheduled.command.get_requirements() {
+            if self.requirements.get(&req) == Some(&cmd_idx) {
+                self.requirements.remove(&req);
+                if let Some(&idx) = self.subsystem_to_default.get(&req) {
+                    self.requirements.insert(req, idx);
                 }
-                command.periodic();
-                if command.is_finished() {
-                    command.end(false);
-                    match *index {
-                        CommandIndex::Command(idx) => to_remove.push(idx),
-                        CommandIndex::DefaultCommand(_) => {}
+            }
+        }
+        if let Some(proxied) = scheduled.proxied {
+            proxied.finish(scheduled.command);
+        }
+    }
+
+    /// Ends the command holding a subsystem with `interrupted` set, a default command is
+    /// initialized again the next time it runs.
+    fn interrupt_holder(&mut self, holder: CommandIndex, reason: &InterruptReason) {
+        match holder {
+            CommandIndex::Command(key) => {
+                if let Some(scheduled) = self.commands.get_mut(key) {
+                    if scheduled.initialized {
+                        scheduled.command.end(true);
+                        self.hooks.interrupt(
+                            &scheduled.command,
+                            Some(&scheduled.state),
+                            self.clock.now(),
+                            reason,
+                        );
                     }
                 }
-                if self.interrupt_state[index] {
+                self.remove_command(key);
+            }
+            CommandIndex::DefaultCommand(idx) => {
+                if let (true, Some(command)) = (
+                    self.initialized_defaults.remove(&idx),
+                    &mut self.default_commands[idx],
+                ) {
                     command.end(true);
-                    match *index {
-                        CommandIndex::Command(idx) => to_remove.push(idx),
-                        CommandIndex::DefaultCommand(_) => {}
-                    }
+                    self.hooks
+                        .interrupt(command, None, self.clock.now(), reason);
                 }
             }
         }
-        for index in to_remove {
-            self.initialized_commands
-                .remove(&CommandIndex::Command(index));
-            if let Some(cmd) = self.commands.remove(index) {
-                let requirements = cmd.get_requirements();
-                if requirements.is_empty() {
-                    self.orphaned_commands.remove(&CommandIndex::Command(index));
-                } else {
-                    for req in cmd.get_requirements() {
-                        self.requirements.remove(&req);
-                        let idx = self.subsystem_to_default[&req];
-                        self.requirements.insert(req, idx);
-                    }
+    }
+
+    /// Schedules `command`, interrupting whatever holds its requirements.
+    ///
+    /// Nothing changes and `None` is returned if one of the holders
+    /// [cancels incoming](CommandTrait::cancel_incoming) commands, or if the robot is
+    /// disabled and the command doesn't [run while disabled](CommandTrait::run_when_disabled).
+    fn try_schedule(
+        &mut self,
+        command: Command,
+        state: Arc<HandleState>,
+    ) -> Result<ArenaKey, Command> {
+        if self.disabled && !command.run_when_disabled() {
+            return Err(command);
+        }
+        let requirements = command.get_requirements();
+        let mut holders = Vec::new();
+        for requirement in &requirements {
+            if let Some(&holder) = self.requirements.get(&requirement) {
+                if !holders.contains(&holder) {
+                    holders.push(holder);
                 }
             }
         }
-    }
+        //check every holder before interrupting any so a rejected command changes nothing
+        let rejected = holders.iter().any(|holder| match holder {
+            CommandIndex::Command(key) => self
+                .commands
+                .get(*key)
+                .is_some_and(|scheduled| scheduled.command.cancel_incoming()),
+            CommandIndex::DefaultCommand(_) => false,
+        });
+        if rejected {
+            return Err(command);
+        }
+        if !holders.is_empty() {
+            let reason = InterruptReason::Requirement(command.get_name());
+            for holder in holders {
+                self.interrupt_holder(holder, &reason);
+            }
+        }
 
-    fn add_command(&mut self, command: Command) -> CommandIndex {
-        if let Some(index) = self.commands.iter().position(Option::is_none) {
-            self.commands[index] = Some(command);
-            let cmd_idx = CommandIndex::Command(index);
-            self.interrupt_state.insert(cmd_idx, false);
-            cmd_idx
-        } else {
-            self.commands.push(Some(command));
-            let cmd_idx = CommandIndex::Command(self.commands.len() - 1);
-            self.interrupt_state.insert(cmd_idx, false);
-            cmd_idx
+        let key = self.commands.insert(ScheduledCommand {
+            command,
+            state: state.clone(),
+            initialized: false,
+            interrupted: false,
+            proxied: None,
+        });
+        *state.scheduled.lock() = Some((key, self.clock.now()));
+        for requirement in requirements {
+            self.requirements
+                .insert(requirement, CommandIndex::Command(key));
         }
+        Ok(key)
     }
 
+    /// Marks the command to be ended as interrupted on the next run, a stale index is ignored.
     fn interrupt_command(&mut self, idx: CommandIndex) {
-        if self.interrupt_state.contains_key(&idx) {
-            self.interrupt_state.insert(idx, true);
+        if let CommandIndex::Command(key) = idx {
+            if let Some(scheduled) = self.commands.get_mut(key) {
+                scheduled.interrupted = true;
+            }
         }
     }
 
-    pub(super) fn cond_schedule(&mut self, command: Command) -> CommandIndex {
-        let requirements = command.get_requirements();
-        let index = self.add_command(command);
-        if requirements.is_empty() {
-            self.orphaned_commands.insert(index);
-        } else {
-            for requirement in requirements {
-                //TODO: implement cancellation policy
-                self.requirements.insert(requirement, index);
+    fn is_scheduled(&self, idx: CommandIndex) -> bool {
+        match idx {
+            CommandIndex::Command(key) => self.commands.contains(key),
+            CommandIndex::DefaultCommand(_) => false,
+        }
+    }
+
+    pub(super) fn cond_schedule(&mut self, command: Command) -> Option<CommandIndex> {
+        let state = HandleState::new(self.clock.clone());
+        self.try_schedule(command, state)
+            .ok()
+            .map(CommandIndex::Command)
+    }
+
+    fn run_deferred(&mut self, deferred: Vec<Deferred>) {
+        for work in deferred {
+            match work {
+                Deferred::Schedule(command, proxied) => {
+                    let state = HandleState::new(self.clock.clone());
+                    let key = match self.try_schedule(command, state) {
+                        Ok(key) => key,
+                        Err(command) => {
+                            proxied.finish(command);
+                            continue;
+                        }
+                    };
+                    if let Some(scheduled) = self.commands.get_mut(key) {
+                        scheduled.proxied = Some(proxied.clone());
+                    }
+                    *proxied.key.lock() = Some(key);
+                }
+                Deferred::Cancel(proxied) => {
+                    let key = *proxied.key.lock();
+                    if let Some(key) = key {
+                        self.interrupt_holder(
+                            CommandIndex::Command(key),
+                            &InterruptReason::Cancelled,
+                        );
+                    }
+                }
+                Deferred::ScheduleHandle(command, state) => {
+                    if self.try_schedule(command, state.clone()).is_err() {
+                        state.end();
+                    }
+                }
+                Deferred::CancelHandle(state) => {
+                    if let Some(key) = state.get_key() {
+                        self.interrupt_holder(
+                            CommandIndex::Command(key),
+                            &InterruptReason::Cancelled,
+                        );
+                    }
+                }
             }
         }
-        index
     }
 
-    pub fn schedule(command: Command) {
-        let mut scheduler = MANAGER.lock();
-        let requirements = command.get_requirements();
-        let index = scheduler.add_command(command);
-        if requirements.is_empty() {
-            scheduler.orphaned_commands.insert(index);
+    fn cancel_all(&mut self) {
+        for key in self.commands.keys() {
+            self.interrupt_holder(CommandIndex::Command(key), &InterruptReason::Cancelled);
+        }
+        let defaults = self
+            .initialized_defaults
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        for idx in defaults {
+            self.interrupt_holder(
+                CommandIndex::DefaultCommand(idx),
+                &InterruptReason::Cancelled,
+            );
+        }
+        self.requirements.clear();
+    }
+}
+
+/// Owns subsystems, scheduled commands and conditional schedulers, and runs them when ticked.
+///
+/// Clones share the same state. Every scheduler is independent, so tests and simulations can
+/// run as many as they like side by side, [`CommandManager`] drives a default one.
+#[derive(Debug, Clone)]
+pub struct CommandScheduler {
+    core: Arc<Mutex<SchedulerCore>>,
+    deferred: Arc<Mutex<Vec<Deferred>>>,
+    clock: ClockRef,
+}
+
+impl CommandScheduler {
+    /// A scheduler on the [default clock](crate::timer::get_default_clock).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_clock(get_default_clock())
+    }
+
+    /// A scheduler that timestamps events and measures how long commands run on `clock`.
+    #[must_use]
+    pub fn with_clock(clock: ClockRef) -> Self {
+        let deferred = Arc::new(Mutex::new(Vec::new()));
+        Self {
+            core: Arc::new_cyclic(|this| {
+                Mutex::new(SchedulerCore::new(
+                    this.clone(),
+                    Arc::downgrade(&deferred),
+                    clock.clone(),
+                ))
+            }),
+            deferred,
+            clock,
+        }
+    }
+
+    pub fn register_subsystem(
+        &self,
+        id: SubsystemId,
+        subsystem: SubsystemArc,
+        default_command: Option<Command>,
+    ) {
+        let mut core = self.core.lock();
+        core.periodic_callbacks.push(subsystem);
+        core.default_commands.push(default_command);
+        let idx = core.default_commands.len() - 1;
+        core.subsystem_to_default
+            .insert(id, CommandIndex::DefaultCommand(idx));
+    }
+
+    /// Will run all periodic callbacks, run all conditional schedulers, init all un-initialized commands, and run all commands
+    /// in that order.
+    pub fn run(&self) {
+        let mut core = self.core.lock();
+        core.run_subsystems(None);
+        core.run_cond_schedulers();
+        core.run_commands(None);
+        core.run_deferred(std::mem::take(&mut *self.deferred.lock()));
+    }
+
+    /// Same as [`CommandScheduler::run`] but records an epoch on `watchdog` for every
+    /// subsystem and command periodic.
+    pub fn run_with_watchdog(&self, watchdog: &mut Watchdog) {
+        let mut core = self.core.lock();
+        core.run_subsystems(Some(watchdog));
+        core.run_cond_schedulers();
+        watchdog.add_epoch("ConditionalScheduler::poll()");
+        core.run_commands(Some(watchdog));
+        core.run_deferred(std::mem::take(&mut *self.deferred.lock()));
+    }
+
+    /// Schedules `command`, interrupting the commands that hold any of its requirements.
+    ///
+    /// Returns `None` if the command wasn't scheduled because a holder
+    /// [cancels incoming](CommandTrait::cancel_incoming) commands, or because the scheduler
+    /// is disabled and the command doesn't [run while disabled](CommandTrait::run_when_disabled).
+    ///
+    /// Scheduling from something the scheduler is running, like a command's `periodic`,
+    /// happens once the run is over. The handle is always returned then, it reports the
+    /// command as ended if it's refused.
+    #[must_use]
+    pub fn schedule(&self, command: Command) -> Option<CommandHandle> {
+        let state = HandleState::new(self.clock.clone());
+        if let Some(mut core) = self.core.try_lock() {
+            core.try_schedule(command, state.clone()).ok()?;
         } else {
-            for requirement in requirements {
-                //TODO: implement cancellation policy
-                scheduler.requirements.insert(requirement, index);
-            }
+            self.deferred
+                .lock()
+                .push(Deferred::ScheduleHandle(command, state.clone()));
         }
+        Some(CommandHandle {
+            scheduler: Arc::downgrade(&self.core),
+            deferred: Arc::downgrade(&self.deferred),
+            state,
+        })
+    }
+
+    pub fn cancel_all(&self) {
+        self.core.lock().cancel_all();
+    }
+
+    /// Tells the scheduler whether the robot is disabled, the robot loop keeps the
+    /// [`CommandManager`] scheduler in step with the robot's mode.
+    ///
+    /// While disabled, commands that don't [run while disabled](CommandTrait::run_when_disabled)
+    /// are refused and the ones already running are interrupted on the next run. Schedulers
+    /// start out enabled.
+    pub fn set_disabled(&self, disabled: bool) {
+        self.core.lock().disabled = disabled;
+    }
+
+    #[must_use]
+    pub fn is_disabled(&self) -> bool {
+        self.core.lock().disabled
+    }
+
+    /// Calls `hook` after a command is initialized.
+    ///
+    /// Hooks run while the scheduler is busy, calling back into it from one deadlocks except
+    /// through [`CommandScheduler::schedule`] and the event's [`CommandHandle`].
+    pub fn on_init(&self, hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        self.core.lock().hooks.init.push(Box::new(hook));
+    }
+
+    /// Calls `hook` after every periodic of a command.
+    pub fn on_execute(&self, hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        self.core.lock().hooks.execute.push(Box::new(hook));
+    }
+
+    /// Calls `hook` after a command finishes on its own.
+    pub fn on_finish(&self, hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        self.core.lock().hooks.finish.push(Box::new(hook));
+    }
+
+    /// Calls `hook` after a command is interrupted, commands interrupted before they were
+    /// initialized are never reported.
+    pub fn on_interrupt(
+        &self,
+        hook: impl Fn(&CommandEvent, &InterruptReason) + Send + Sync + 'static,
+    ) {
+        self.core.lock().hooks.interrupt.push(Box::new(hook));
+    }
+
+    pub(super) fn get_deferred_queue(&self) -> DeferredQueue {
+        DeferredQueue(Arc::downgrade(&self.deferred))
+    }
+
+    /// The number of scheduled commands, not counting default commands.
+    #[must_use]
+    pub fn get_scheduled_count(&self) -> usize {
+        self.core.lock().commands.len()
+    }
+
+    pub fn add_cond_scheduler(&self, scheduler: ConditionalScheduler) {
+        self.core.lock().cond_schedulers.push(scheduler);
+    }
+
+    pub fn clear_cond_schedulers(&self) {
+        self.core.lock().cond_schedulers.clear();
+    }
+}
+
+impl Default for CommandScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The static API, every call goes to the default [`CommandScheduler`].
+#[derive(Debug, Clone, Copy)]
+pub struct CommandManager;
+
+impl CommandManager {
+    /// The scheduler behind the static API.
+    #[must_use]
+    pub fn get_scheduler() -> CommandScheduler {
+        MANAGER.clone()
+    }
+
+    pub fn register_subsystem(
+        id: SubsystemId,
+        subsystem: SubsystemArc,
+        default_command: Option<Command>,
+    ) {
+        MANAGER.register_subsystem(id, subsystem, default_command);
+    }
+
+    /// See [`CommandScheduler::run`].
+    pub fn run() {
+        MANAGER.run();
+    }
+
+    /// See [`CommandScheduler::run_with_watchdog`].
+    pub fn run_with_watchdog(watchdog: &mut Watchdog) {
+        MANAGER.run_with_watchdog(watchdog);
+    }
+
+    /// See [`CommandScheduler::schedule`].
+    #[must_use]
+    pub fn schedule(command: Command) -> Option<CommandHandle> {
+        MANAGER.schedule(command)
     }
 
     pub fn cancel_all() {
-        let mut scheduler = MANAGER.lock();
-        for maybe_command in &mut scheduler.commands {
-            match maybe_command {
-                Some(command) => {
-                    command.end(true);
-                }
-                None => {}
-            }
-        }
-        scheduler.commands.clear();
-        scheduler.requirements.clear();
-        scheduler.initialized_commands.clear();
-        scheduler.orphaned_commands.clear();
+        MANAGER.cancel_all();
+    }
+
+    /// See [`CommandScheduler::set_disabled`].
+    pub fn set_disabled(disabled: bool) {
+        MANAGER.set_disabled(disabled);
+    }
+
+    #[must_use]
+    pub fn get_scheduled_count() -> usize {
+        MANAGER.get_scheduled_count()
     }
 
     pub fn add_cond_scheduler(scheduler: ConditionalScheduler) {
-        let mut manager = MANAGER.lock();
-        manager.cond_schedulers.push(scheduler);
+        MANAGER.add_cond_scheduler(scheduler);
     }
 
     pub fn clear_cond_schedulers() {
-        let mut manager = MANAGER.lock();
-        manager.cond_schedulers.clear();
+        MANAGER.clear_cond_schedulers();
     }
 
-    #[cfg(test)]
-    pub fn purge_state_test() {
-        let mut manager = MANAGER.lock();
-        manager.periodic_callbacks.clear();
-        manager.commands.clear();
-        manager.interrupt_state.clear();
-        manager.default_commands.clear();
-        manager.subsystem_to_default.clear();
-        manager.requirements.clear();
-        manager.initialized_commands.clear();
-        manager.orphaned_commands.clear();
-        manager.cond_schedulers.clear();
+    /// See [`CommandScheduler::on_init`].
+    pub fn on_init(hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        MANAGER.on_init(hook);
+    }
+
+    /// See [`CommandScheduler::on_execute`].
+    pub fn on_execute(hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        MANAGER.on_execute(hook);
+    }
+
+    /// See [`CommandScheduler::on_finish`].
+    pub fn on_finish(hook: impl Fn(&CommandEvent) + Send + Sync + 'static) {
+        MANAGER.on_finish(hook);
+    }
+
+    /// See [`CommandScheduler::on_interrupt`].
+    pub fn on_interrupt(hook: impl Fn(&CommandEvent, &InterruptReason) + Send + Sync + 'static) {
+        MANAGER.on_interrupt(hook);
     }
 }
 
@@ -291,6 +1001,8 @@ pub enum ConditionResponse {
     Start,
     Continue,
     Stop,
+    /// Stops the command if it's still scheduled, starts it otherwise.
+    Toggle,
     NoChange,
 }
 
@@ -340,7 +1052,7 @@ impl ConditionalScheduler {
         }
     }
 
-    pub fn poll(&mut self, manager: &mut CommandManager) {
+    fn poll(&mut self, manager: &mut SchedulerCore) {
         for i in 0..self.conds.len() {
             let (cond, cmd) = &mut self.conds[i];
             let condition_result = cond.get_condition();
@@ -348,17 +1060,19 @@ impl ConditionalScheduler {
             match condition_result {
                 ConditionResponse::Start => {
                     let command = cmd.call();
-                    let cmd_idx = manager.cond_schedule(command);
-                    println!("start");
-                    self.active_commands.insert(i, cmd_idx);
+                    if let Some(cmd_idx) = manager.cond_schedule(command) {
+                        println!("start");
+                        self.active_commands.insert(i, cmd_idx);
+                    }
                 }
                 ConditionResponse::Continue => {
-                    self.active_commands.entry(i).or_insert_with(|| {
+                    if let Entry::Vacant(entry) = self.active_commands.entry(i) {
                         let command = cmd.call();
-                        let cmd_idx = manager.cond_schedule(command);
-                        println!("continue");
-                        cmd_idx
-                    });
+                        if let Some(cmd_idx) = manager.cond_schedule(command) {
+                            println!("continue");
+                            entry.insert(cmd_idx);
+                        }
+                    }
                 }
                 ConditionResponse::Stop => {
                     if self.active_commands.contains_key(&i) {
@@ -366,6 +1080,16 @@ impl ConditionalScheduler {
                         self.active_commands.remove(&i);
                     }
                 }
+                ConditionResponse::Toggle => match self.active_commands.remove(&i) {
+                    Some(cmd_idx) if manager.is_scheduled(cmd_idx) => {
+                        manager.interrupt_command(cmd_idx);
+                    }
+                    _ => {
+                        if let Some(cmd_idx) = manager.cond_schedule(cmd.call()) {
+                            self.active_commands.insert(i, cmd_idx);
+                        }
+                    }
+                },
                 ConditionResponse::NoChange => {
                     println!("no_change");
                 }
@@ -386,13 +1110,19 @@ impl std::fmt::Debug for ConditionalScheduler {
 #[macro_export]
 macro_rules! register_subsystem {
     ($name:ident) => {{
-        let instance = SubsystemRef {
-            0: std::sync::Arc::<parking_lot::Mutex<$name>>::new(parking_lot::Mutex::<$name>::new(
-                $name::new(),
-            )),
-        };
+        let instance = SubsystemRef::new($name::new());
         CommandManager::register_subsystem(
-            CommandManager::get_suid(),
+            instance.get_id(),
+            instance.get_arc(),
+            Some(instance.default_command()),
+        );
+        instance.clone()
+    }};
+    ($name:ident, $scheduler:expr) => {{
+        let scheduler = &$scheduler;
+        let instance = SubsystemRef::new($name::new());
+        scheduler.register_subsystem(
+            instance.get_id(),
             instance.get_arc(),
             Some(instance.default_command()),
         );
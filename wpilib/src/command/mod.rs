@@ -1,18 +1,38 @@
 #[macro_use]
 pub mod manager;
+mod arena;
 pub mod command_hid;
 pub mod commands;
 pub mod conditions;
+pub mod registry;
+pub mod state_machine;
+pub mod subsystem;
 #[cfg(test)]
 mod test;
+pub mod trigger;
 
 pub use commands::Command;
+pub use conditions::on_change;
+pub use conditions::on_ds_event;
 pub use conditions::on_false;
 pub use conditions::on_true;
+pub use conditions::toggle_on_true;
 pub use conditions::while_false;
 pub use conditions::while_true;
+pub use conditions::OnChange;
+pub use conditions::OnDsEvent;
 pub use conditions::OnTrue;
+pub use conditions::ToggleOnTrue;
 pub use conditions::WhileTrue;
+pub use manager::CommandEvent;
+pub use manager::CommandHandle;
 pub use manager::CommandManager;
+pub use manager::CommandScheduler;
 pub use manager::ConditionalScheduler;
+pub use manager::InterruptReason;
 pub use manager::SubsystemRef;
+pub use registry::CommandRegistry;
+pub use state_machine::StateMachineBuilder;
+pub use subsystem::Requirements;
+pub use subsystem::SubsystemId;
+pub use trigger::Trigger;
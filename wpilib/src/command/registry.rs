@@ -0,0 +1,192 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::{commands::CommandTrait, Command, SubsystemId};
+use crate::math::units::time::Second;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RoutineError {
+    #[error("failed to read routine: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid routine: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("no command registered as {0}")]
+    UnknownCommand(String),
+    #[error("invalid wait time {0}")]
+    InvalidWait(f64),
+    #[error("a deadline group needs at least one command")]
+    EmptyDeadline,
+    #[error("{first} and {second} both require {requirement} in the same parallel group")]
+    RequirementConflict {
+        first: String,
+        second: String,
+        requirement: SubsystemId,
+    },
+}
+
+/// A step of an autonomous routine, in the same shape as `PathPlanner` auto files:
+/// ```json
+/// {
+///     "type": "sequential",
+///     "data": {
+///         "commands": [
+///             { "type": "named", "data": { "name": "intake" } },
+///             { "type": "wait", "data": { "waitTime": 0.5 } }
+///         ]
+///     }
+/// }
+/// ```
+/// The first command of a deadline group is its deadline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RoutineStep {
+    Named {
+        name: String,
+    },
+    Wait {
+        #[serde(rename = "waitTime")]
+        wait_time: f64,
+    },
+    Sequential {
+        commands: Vec<Self>,
+    },
+    Parallel {
+        commands: Vec<Self>,
+    },
+    Race {
+        commands: Vec<Self>,
+    },
+    Deadline {
+        commands: Vec<Self>,
+    },
+}
+
+type CommandFactory = Box<dyn Fn() -> Command + Send + Sync>;
+
+/// Command factories registered by name, routines described in data refer to them.
+#[derive(Default)]
+pub struct CommandRegistry {
+    factories: HashMap<String, CommandFactory>,
+}
+
+impl CommandRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `factory` as `name`, replacing whatever was registered as it before.
+    pub fn register(
+        &mut self,
+        name: &str,
+        factory: impl Fn() -> Command + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.factories.insert(String::from(name), Box::new(factory));
+        self
+    }
+
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// A new command from the factory registered as `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Command> {
+        self.factories.get(name).map(|factory| factory())
+    }
+
+    /// Builds the routine described by `json`.
+    ///
+    /// # Errors
+    /// If `json` doesn't describe a routine, or see [`CommandRegistry::build_routine`].
+    pub fn load(&self, json: &str) -> Result<Command, RoutineError> {
+        self.build_routine(&serde_json::from_str(json)?)
+    }
+
+    /// Builds the routine described by the json file at `path`.
+    ///
+    /// # Errors
+    /// If the file can't be read, or see [`CommandRegistry::load`].
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<Command, RoutineError> {
+        self.load(&std::fs::read_to_string(path)?)
+    }
+
+    /// # Errors
+    /// If the routine names a command that isn't registered, waits a negative time, has an
+    /// empty deadline group, or runs commands side by side that need the same subsystem.
+    pub fn build_routine(&self, step: &RoutineStep) -> Result<Command, RoutineError> {
+        match step {
+            RoutineStep::Named { name } => self
+                .get(name)
+                .ok_or_else(|| RoutineError::UnknownCommand(name.clone())),
+            RoutineStep::Wait { wait_time } => {
+                if wait_time.is_finite() && *wait_time >= 0.0 {
+                    Ok(Command::wait(Second::new(*wait_time)))
+                } else {
+                    Err(RoutineError::InvalidWait(*wait_time))
+                }
+            }
+            RoutineStep::Sequential { commands } => {
+                let mut commands = self.build_all(commands)?.into_iter();
+                Ok(commands
+                    .next()
+                    .map_or_else(instant, |first| first.and_then_many(commands.collect())))
+            }
+            RoutineStep::Parallel { commands } => {
+                let mut commands = self.build_parallel(commands)?.into_iter();
+                Ok(commands
+                    .next()
+                    .map_or_else(instant, |first| first.along_with_many(commands.collect())))
+            }
+            RoutineStep::Race { commands } => {
+                let mut commands = self.build_parallel(commands)?.into_iter();
+                Ok(commands
+                    .next()
+                    .map_or_else(instant, |first| first.race_with_many(commands.collect())))
+            }
+            RoutineStep::Deadline { commands } => {
+                let mut commands = self.build_parallel(commands)?.into_iter();
+                let deadline = commands.next().ok_or(RoutineError::EmptyDeadline)?;
+                Ok(deadline.deadline_with_many(commands.collect()))
+            }
+        }
+    }
+
+    fn build_all(&self, steps: &[RoutineStep]) -> Result<Vec<Command>, RoutineError> {
+        steps.iter().map(|step| self.build_routine(step)).collect()
+    }
+
+    /// Builds commands that run side by side, none of them may share a requirement.
+    fn build_parallel(&self, steps: &[RoutineStep]) -> Result<Vec<Command>, RoutineError> {
+        let commands = self.build_all(steps)?;
+        let mut holders: HashMap<SubsystemId, usize> = HashMap::new();
+        for (i, command) in commands.iter().enumerate() {
+            for requirement in command.get_requirements() {
+                if let Some(&holder) = holders.get(&requirement) {
+                    return Err(RoutineError::RequirementConflict {
+                        first: commands[holder].get_name(),
+                        second: command.get_name(),
+                        requirement,
+                    });
+                }
+                holders.insert(requirement, i);
+            }
+        }
+        Ok(commands)
+    }
+}
+
+impl std::fmt::Debug for CommandRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandRegistry")
+            .field("names", &self.factories.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+//what an empty group does
+fn instant() -> Command {
+    Command::wait(Second::new(0.0))
+}
@@ -0,0 +1,312 @@
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+use parking_lot::Mutex;
+
+use super::{
+    commands::{Command, CommandTrait},
+    Requirements,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StateMachineError {
+    #[error("state {0} is added more than once")]
+    DuplicateState(String),
+    #[error("no state named {0}")]
+    UnknownState(String),
+}
+
+enum Guard {
+    Condition(Box<dyn FnMut() -> bool>),
+    Finished,
+}
+
+struct Transition {
+    to: usize,
+    guard: Guard,
+}
+
+struct State {
+    name: String,
+    command: Command,
+    on_entry: Vec<Box<dyn FnMut()>>,
+    on_exit: Vec<Box<dyn FnMut()>>,
+    transitions: Vec<Transition>,
+}
+
+impl State {
+    fn enter(&mut self) {
+        for action in &mut self.on_entry {
+            action();
+        }
+        self.command.init();
+    }
+
+    fn exit(&mut self, interrupted: bool) {
+        self.command.end(interrupted);
+        for action in &mut self.on_exit {
+            action();
+        }
+    }
+}
+
+/// The state a [`StateMachineCommand`] is in, readable after the command is scheduled.
+#[derive(Debug, Clone, Default)]
+pub struct StateHandle(Arc<Mutex<Option<String>>>);
+
+impl StateHandle {
+    /// `None` while the state machine isn't running.
+    #[must_use]
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().clone()
+    }
+}
+
+/// Builds a [`StateMachineCommand`], states are referred to by name.
+///
+/// ```ignore
+/// let machine = StateMachineBuilder::new("stowed")
+///     .state("stowed", arm.cmd_stow())
+///     .state("intaking", arm.cmd_intake())
+///     .state("holding", arm.cmd_hold())
+///     .transition("stowed", "intaking", move || intake_button.get())
+///     .transition_on_finish("intaking", "holding")
+///     .on_entry("holding", || leds.set_green())
+///     .build()?;
+/// scheduler.schedule(Command::StateMachine(machine));
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct StateMachineBuilder {
+    initial: String,
+    states: Vec<(String, Command)>,
+    on_entry: Vec<(String, Box<dyn FnMut()>)>,
+    on_exit: Vec<(String, Box<dyn FnMut()>)>,
+    transitions: Vec<(String, String, Guard)>,
+}
+
+impl StateMachineBuilder {
+    /// The state machine starts in `initial` every time it's initialized.
+    #[must_use]
+    pub fn new(initial: &str) -> Self {
+        Self {
+            initial: String::from(initial),
+            states: Vec::new(),
+            on_entry: Vec::new(),
+            on_exit: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Adds a state that runs `command` while the machine is in it.
+    #[must_use]
+    pub fn state(mut self, name: &str, command: Command) -> Self {
+        self.states.push((String::from(name), command));
+        self
+    }
+
+    /// Runs `action` every time the machine enters `state`, before its command is initialized.
+    #[must_use]
+    pub fn on_entry(mut self, state: &str, action: impl FnMut() + 'static) -> Self {
+        self.on_entry.push((String::from(state), Box::new(action)));
+        self
+    }
+
+    /// Runs `action` every time the machine leaves `state`, after its command has ended.
+    #[must_use]
+    pub fn on_exit(mut self, state: &str, action: impl FnMut() + 'static) -> Self {
+        self.on_exit.push((String::from(state), Box::new(action)));
+        self
+    }
+
+    /// Moves from `from` to `to` once `condition` is true, it's checked after every periodic.
+    ///
+    /// Transitions are checked in the order they're added, the first one that passes is taken.
+    #[must_use]
+    pub fn transition(
+        mut self,
+        from: &str,
+        to: &str,
+        condition: impl FnMut() -> bool + 'static,
+    ) -> Self {
+        self.transitions.push((
+            String::from(from),
+            String::from(to),
+            Guard::Condition(Box::new(condition)),
+        ));
+        self
+    }
+
+    /// Moves from `from` to `to` once the command of `from` finishes.
+    #[must_use]
+    pub fn transition_on_finish(mut self, from: &str, to: &str) -> Self {
+        self.transitions
+            .push((String::from(from), String::from(to), Guard::Finished));
+        self
+    }
+
+    /// # Errors
+    /// If a state is added twice or a name doesn't match any state.
+    pub fn build(self) -> Result<StateMachineCommand, StateMachineError> {
+        let mut indices = HashMap::with_capacity(self.states.len());
+        let mut states = Vec::with_capacity(self.states.len());
+        for (name, command) in self.states {
+            if indices.insert(name.clone(), states.len()).is_some() {
+                return Err(StateMachineError::DuplicateState(name));
+            }
+            states.push(State {
+                name,
+                command,
+                on_entry: Vec::new(),
+                on_exit: Vec::new(),
+                transitions: Vec::new(),
+            });
+        }
+        let index = |name: &String| {
+            indices
+                .get(name)
+                .copied()
+                .ok_or_else(|| StateMachineError::UnknownState(name.clone()))
+        };
+
+        let initial = index(&self.initial)?;
+        for (name, action) in self.on_entry {
+            states[index(&name)?].on_entry.push(action);
+        }
+        for (name, action) in self.on_exit {
+            states[index(&name)?].on_exit.push(action);
+        }
+        for (from, to, guard) in self.transitions {
+            let to = index(&to)?;
+            states[index(&from)?]
+                .transitions
+                .push(Transition { to, guard });
+        }
+
+        Ok(StateMachineCommand {
+            requirements: states
+                .iter()
+                .flat_map(|state| state.command.get_requirements())
+                .collect(),
+            states,
+            initial,
+            current: None,
+            handle: StateHandle::default(),
+        })
+    }
+}
+
+/// Runs the command of its current state, moving between states as their transitions pass.
+///
+/// Finishes once the current state's command has finished and none of its transitions
+/// pass. Requires everything every state's command does, like a
+/// [`SequentialCommand`](super::commands::SequentialCommand).
+pub struct StateMachineCommand {
+    states: Vec<State>,
+    initial: usize,
+    current: Option<usize>,
+    requirements: Requirements,
+    handle: StateHandle,
+}
+
+impl StateMachineCommand {
+    /// `None` while the state machine isn't running.
+    #[must_use]
+    pub fn get_current_state(&self) -> Option<&str> {
+        self.current
+            .map(|current| self.states[current].name.as_str())
+    }
+
+    /// A handle that keeps reporting the current state once the command has been scheduled.
+    #[must_use]
+    pub fn get_state_handle(&self) -> StateHandle {
+        self.handle.clone()
+    }
+
+    fn set_current(&mut self, current: Option<usize>) {
+        self.current = current;
+        *self.handle.0.lock() = self.get_current_state().map(String::from);
+    }
+}
+
+impl CommandTrait for StateMachineCommand {
+    fn init(&mut self) {
+        self.set_current(Some(self.initial));
+        self.states[self.initial].enter();
+    }
+
+    fn periodic(&mut self) {
+        let Some(current) = self.current else {
+            return;
+        };
+        let state = &mut self.states[current];
+        state.command.periodic();
+        let finished = state.command.is_finished();
+        let next = state.transitions.iter_mut().find_map(|transition| {
+            let passed = match &mut transition.guard {
+                Guard::Condition(condition) => condition(),
+                Guard::Finished => finished,
+            };
+            passed.then_some(transition.to)
+        });
+
+        if let Some(next) = next {
+            state.exit(!finished);
+            self.set_current(Some(next));
+            self.states[next].enter();
+        } else if finished {
+            state.exit(false);
+            self.set_current(None);
+        }
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        //the machine may have already left its last state in periodic
+        if let Some(current) = self.current {
+            self.states[current].exit(interrupted);
+            self.set_current(None);
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.current.is_none()
+    }
+
+    fn get_requirements(&self) -> Requirements {
+        self.requirements.clone()
+    }
+
+    fn run_when_disabled(&self) -> bool {
+        self.states
+            .iter()
+            .all(|state| state.command.run_when_disabled())
+    }
+
+    fn cancel_incoming(&self) -> bool {
+        self.states
+            .iter()
+            .any(|state| state.command.cancel_incoming())
+    }
+
+    fn get_name(&self) -> String {
+        format!(
+            "StateMachine({})",
+            self.get_current_state().unwrap_or("stopped")
+        )
+    }
+}
+
+impl Debug for StateMachineCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("StateMachineCommand")
+            .field(
+                "states",
+                &self
+                    .states
+                    .iter()
+                    .map(|state| &state.name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("current", &self.get_current_state())
+            .field("requirements", &self.requirements)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::BTreeSet,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use super::manager::{Subsystem, SubsystemRef};
+
+//shared by SubsystemRef, the register_subsystem! macro and the subsystem! proc macro
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Identifies a subsystem, every call to [`SubsystemId::next`] hands out a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubsystemId(u32);
+
+impl SubsystemId {
+    #[must_use]
+    pub fn next() -> Self {
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[must_use]
+    pub const fn get_raw(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for SubsystemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "subsystem#{}", self.0)
+    }
+}
+
+/// The subsystems a command needs exclusive use of.
+///
+/// Anything naming subsystems converts into it, so builders take
+/// `impl Into<Requirements>`:
+/// ```ignore
+/// CommandBuilder::new().with_requirements(&drivetrain);
+/// CommandBuilder::new().with_requirements([drivetrain.get_id(), arm.get_id()]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Requirements(BTreeSet<SubsystemId>);
+
+impl Requirements {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(BTreeSet::new())
+    }
+
+    #[must_use]
+    pub fn with(mut self, requirement: impl Into<Self>) -> Self {
+        self.extend(requirement.into());
+        self
+    }
+
+    pub fn insert(&mut self, id: SubsystemId) -> bool {
+        self.0.insert(id)
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: SubsystemId) -> bool {
+        self.0.contains(&id)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = SubsystemId> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<SubsystemId> for Requirements {
+    fn from_iter<I: IntoIterator<Item = SubsystemId>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<SubsystemId> for Requirements {
+    fn extend<I: IntoIterator<Item = SubsystemId>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for Requirements {
+    type Item = SubsystemId;
+    type IntoIter = std::collections::btree_set::IntoIter<SubsystemId>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Requirements {
+    type Item = SubsystemId;
+    type IntoIter = std::iter::Copied<std::collections::btree_set::Iter<'a, SubsystemId>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().copied()
+    }
+}
+
+impl From<SubsystemId> for Requirements {
+    fn from(id: SubsystemId) -> Self {
+        Self(BTreeSet::from([id]))
+    }
+}
+
+impl<const N: usize> From<[SubsystemId; N]> for Requirements {
+    fn from(ids: [SubsystemId; N]) -> Self {
+        Self(BTreeSet::from(ids))
+    }
+}
+
+impl From<Vec<SubsystemId>> for Requirements {
+    fn from(ids: Vec<SubsystemId>) -> Self {
+        ids.into_iter().collect()
+    }
+}
+
+impl<T: Subsystem + Sync + Send + 'static> From<&SubsystemRef<T>> for Requirements {
+    fn from(subsystem: &SubsystemRef<T>) -> Self {
+        subsystem.get_id().into()
+    }
+}
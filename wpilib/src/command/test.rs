@@ -1,3 +1,13 @@
+use std::{
+    collections::HashMap,
+    future::{Future, IntoFuture},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 use wpilib::command::manager::{Subsystem, SubsystemRef};
 use wpilib_macros::{command, command_end, command_provider, use_subsystem};
 
@@ -5,17 +15,25 @@ crate_namespace!();
 
 use crate::{
     command::{
+        command_hid::{GenericHID, PS5Controller, RumbleType, SimJoysticks, XboxController},
         commands::CommandTrait,
         conditions::{self},
-        manager::CommandManager,
-        Command, ConditionalScheduler,
+        Command, CommandEvent, CommandScheduler, ConditionalScheduler, Requirements, SubsystemId,
+        Trigger,
     },
     crate_namespace,
+    driver_station::{DriverStation, DriverStationData, DsEvent, JoystickOutputs},
+    math::{filter::debouncer::DebounceType, units::time::Second},
+    robots::{ControlWord, RobotMode, Watchdog},
+    timer::SimClock,
 };
 
 use super::{
-    commands::CommandBuilder,
+    arena::Arena,
+    commands::{CommandBuilder, WaitCommand},
     manager::{Condition, ConditionResponse},
+    registry::{CommandRegistry, RoutineError, RoutineStep},
+    state_machine::{StateMachineBuilder, StateMachineError},
 };
 
 struct TestSubsystem {
@@ -56,7 +74,7 @@ impl SubsystemRef<TestSubsystem> {
             })
             .is_finished(|| false)
             .end(command_end! {{}})
-            .with_requirements(vec![1])
+            .with_requirements(self)
             .build()
             .with_name("Activate Motor")
     }
@@ -80,7 +98,7 @@ impl SubsystemRef<TestSubsystem> {
                     self.motor_running = false;
                 }
             })
-            .with_requirements(vec![1])
+            .with_requirements(self)
             .build()
             .with_name("Activate Motor")
     }
@@ -103,39 +121,45 @@ impl Subsystem for TestSubsystem {
     fn periodic(&self) {
         println!("Periodic");
     }
+
+    fn get_name(&self) -> String {
+        String::from("TestSubsystem")
+    }
 }
 
 fn test_command() {
-    fn schedule_test() {
+    fn schedule_test(commands: &CommandScheduler) {
         struct TestCommand {}
         impl CommandTrait for TestCommand {}
 
         let command = TestCommand {};
 
-        CommandManager::schedule(Command::custom(Box::new(command)));
+        let _ = commands.schedule(Command::custom(Box::new(command)));
     }
 
-    schedule_test();
+    let commands = CommandScheduler::new();
+    schedule_test(&commands);
 
-    std::thread::spawn(|| {
-        schedule_test();
-        CommandManager::run();
+    let thread_commands = commands.clone();
+    std::thread::spawn(move || {
+        schedule_test(&thread_commands);
+        thread_commands.run();
     })
     .join()
     .expect("Failed to join thread");
+    assert_eq!(commands.get_scheduled_count(), 2);
 }
 
 fn test_subsystem() {
-    CommandManager::clear_cond_schedulers();
-    CommandManager::cancel_all();
-    // CommandManager::register_subsystem(
+    let commands = CommandScheduler::new();
+    // commands.register_subsystem(
     //     TestSubsystem::suid(),
     //     || TestSubsystem::periodic(),
     //     Some(TestSubsystem::default_command()),
     // );
-    let instance = register_subsystem!(TestSubsystem);
+    let instance = register_subsystem!(TestSubsystem, commands);
     assert!(!instance.0.lock().is_default_running());
-    CommandManager::run();
+    commands.run();
     assert!(instance.0.lock().is_default_running());
 }
 
@@ -151,9 +175,8 @@ impl Condition for Immediately {
 }
 
 fn test_on_true() {
-    CommandManager::clear_cond_schedulers();
-    CommandManager::cancel_all();
-    let instance = register_subsystem!(TestSubsystem);
+    let commands = CommandScheduler::new();
+    let instance = register_subsystem!(TestSubsystem, commands);
     let mut scheduler = ConditionalScheduler::new();
 
     let cond = conditions::on_true(|| true);
@@ -170,30 +193,1559 @@ fn test_on_true() {
     assert!(!instance.0.lock().is_motor_running());
     assert_eq!(instance.0.lock().get_calls(), 0);
 
-    CommandManager::add_cond_scheduler(scheduler);
-    CommandManager::run();
-    CommandManager::run();
-    CommandManager::run();
+    commands.add_cond_scheduler(scheduler);
+    commands.run();
+    commands.run();
+    commands.run();
     assert!(instance.0.lock().is_motor_running());
     assert_eq!(instance.0.lock().get_calls(), 1);
 }
 
-fn run_in_clean_state(func: fn()) {
-    func();
-    CommandManager::purge_state_test();
+fn test_watchdog_epochs() {
+    let commands = CommandScheduler::new();
+    let _instance = register_subsystem!(TestSubsystem, commands);
+    let mut watchdog = Watchdog::new(Duration::from_secs(1));
+    watchdog.enable();
+    commands.run_with_watchdog(&mut watchdog);
+    assert!(!watchdog.disable());
+
+    let tracer = watchdog.get_tracer();
+    assert!(tracer.get_epoch("TestSubsystem.periodic()").is_some());
+    assert!(tracer.get_epoch("ConditionalScheduler::poll()").is_some());
+    assert!(tracer.get_epoch("Activate Motor.periodic()").is_some());
+}
+
+fn test_on_ds_event() {
+    //the driver station is global, keep robot loops from feeding it meanwhile
+    let _guard = crate::robots::LOOP_TEST_LOCK.lock();
+    let commands = CommandScheduler::new();
+    DriverStation::set_data(DriverStationData::new());
+    let instance = register_subsystem!(TestSubsystem, commands);
+    let mut scheduler = ConditionalScheduler::new();
+    scheduler.add_cond(
+        conditions::on_ds_event(|event| *event == DsEvent::Enabled),
+        command_provider! {instance,
+            {
+                instance.cmd_activate_motor()
+            }
+        },
+    );
+    commands.add_cond_scheduler(scheduler);
+
+    commands.run();
+    assert!(!instance.0.lock().is_motor_running());
+
+    DriverStation::update(|data| data.control_word = ControlWord::enabled(RobotMode::Teleop));
+    commands.run();
+    commands.run();
+    assert!(instance.0.lock().is_motor_running());
+    assert_eq!(instance.0.lock().get_calls(), 1);
+    DriverStation::set_data(DriverStationData::new());
+}
+
+fn test_hid_binding() {
+    let commands = CommandScheduler::new();
+    let joysticks = SimJoysticks::new();
+    let controller = XboxController::with_source(0, joysticks.clone());
+    let instance = register_subsystem!(TestSubsystem, commands);
+    let mut scheduler = ConditionalScheduler::new();
+    scheduler.add_cond(
+        controller.a().while_true(),
+        command_provider! {instance,
+            {
+                instance.cmd_activate_motor()
+            }
+        },
+    );
+    commands.add_cond_scheduler(scheduler);
+
+    commands.run();
+    assert!(!instance.0.lock().is_motor_running());
+
+    joysticks.set_button(0, 1, true);
+    commands.run();
+    commands.run();
+    assert!(instance.0.lock().is_motor_running());
+
+    joysticks.set_button(0, 1, false);
+    commands.run();
+    commands.run();
+    assert!(!instance.0.lock().is_motor_running());
+}
+
+fn test_trigger_toggle() {
+    let commands = CommandScheduler::new();
+    let joysticks = SimJoysticks::new();
+    let controller = XboxController::with_source(0, joysticks.clone());
+    let instance = register_subsystem!(TestSubsystem, commands);
+    let mut scheduler = ConditionalScheduler::new();
+    let provider = instance.clone();
+    controller.x().trigger().toggle_on_true(
+        &mut scheduler,
+        command_provider! {provider,
+            {
+                provider.cmd_activate_motor()
+            }
+        },
+    );
+    commands.add_cond_scheduler(scheduler);
+
+    let press = || {
+        joysticks.set_button(0, 3, true);
+        commands.run();
+        commands.run();
+        joysticks.set_button(0, 3, false);
+        commands.run();
+    };
+
+    press();
+    assert!(instance.0.lock().is_motor_running());
+    press();
+    assert!(!instance.0.lock().is_motor_running());
+    press();
+    assert!(instance.0.lock().is_motor_running());
+}
+
+fn test_toggle_finished_command() {
+    let commands = CommandScheduler::new();
+    let log = Log::default();
+    let id = SubsystemId::next();
+    let button = Arc::new(AtomicBool::new(false));
+    let finished = Arc::new(AtomicBool::new(false));
+    let mut scheduler = ConditionalScheduler::new();
+    let (input, provider_log, is_finished) = (button.clone(), log.clone(), finished.clone());
+    scheduler.add_cond(
+        conditions::toggle_on_true(move || input.load(Ordering::Relaxed)),
+        move || {
+            let is_finished = is_finished.clone();
+            logged_command("toggled", &provider_log, id)
+                .is_finished(move || is_finished.load(Ordering::Relaxed))
+                .build()
+        },
+    );
+    commands.add_cond_scheduler(scheduler);
+    let press = || {
+        button.store(true, Ordering::Relaxed);
+        commands.run();
+        button.store(false, Ordering::Relaxed);
+        commands.run();
+    };
+
+    press();
+    finished.store(true, Ordering::Relaxed);
+    commands.run();
+    finished.store(false, Ordering::Relaxed);
+    //the command ended on its own, so the next press starts it rather than stopping it
+    press();
+    press();
+    assert_eq!(
+        *log.lock(),
+        [
+            "toggled init",
+            "toggled end(false)",
+            "toggled init",
+            "toggled end(true)"
+        ]
+    );
+    assert_eq!(commands.get_scheduled_count(), 0);
+}
+
+type Log = Arc<parking_lot::Mutex<Vec<String>>>;
+
+fn subsystem_ids<const N: usize>() -> [SubsystemId; N] {
+    std::array::from_fn(|_| SubsystemId::next())
+}
+
+fn logged_command(
+    name: &'static str,
+    log: &Log,
+    requirements: impl Into<Requirements>,
+) -> CommandBuilder {
+    let (init_log, end_log) = (log.clone(), log.clone());
+    CommandBuilder::new()
+        .init(move || init_log.lock().push(format!("{name} init")))
+        .end(move |interrupted| end_log.lock().push(format!("{name} end({interrupted})")))
+        .with_requirements(requirements)
+}
+
+fn test_interrupt_holder() {
+    let scheduler = CommandScheduler::new();
+    let [a, b] = subsystem_ids();
+    let log = Log::default();
+    assert!(scheduler
+        .schedule(logged_command("first", &log, [a, b]).build())
+        .is_some());
+    scheduler.run();
+    assert!(scheduler
+        .schedule(logged_command("second", &log, b).build())
+        .is_some());
+    //the holder ends as soon as it's displaced, not on the next run
+    assert_eq!(*log.lock(), ["first init", "first end(true)"]);
+    scheduler.run();
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        ["first init", "first end(true)", "second init"]
+    );
+}
+
+fn test_cancel_incoming() {
+    let scheduler = CommandScheduler::new();
+    let [a, c] = subsystem_ids();
+    let log = Log::default();
+    assert!(scheduler
+        .schedule(
+            logged_command("holder", &log, a)
+                .cancel_incoming(true)
+                .build()
+        )
+        .is_some());
+    scheduler.run();
+    assert!(scheduler
+        .schedule(logged_command("incoming", &log, [c, a]).build())
+        .is_none());
+    scheduler.run();
+    assert_eq!(*log.lock(), ["holder init"]);
+
+    //a rejected command doesn't take the requirements it could have had either
+    assert!(scheduler
+        .schedule(logged_command("other", &log, c).build())
+        .is_some());
+    scheduler.run();
+    assert_eq!(*log.lock(), ["holder init", "other init"]);
+}
+
+fn test_interrupt_default() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let id = SubsystemId::next();
+    scheduler.register_subsystem(
+        id,
+        Arc::new(parking_lot::Mutex::new(TestSubsystem::new())),
+        Some(logged_command("default", &log, id).build()),
+    );
+    scheduler.run();
+    let finished = Arc::new(AtomicBool::new(false));
+    let is_finished = finished.clone();
+    assert!(scheduler
+        .schedule(
+            logged_command("command", &log, id)
+                .is_finished(move || is_finished.load(Ordering::Relaxed))
+                .build()
+        )
+        .is_some());
+    scheduler.run();
+    finished.store(true, Ordering::Relaxed);
+    scheduler.run();
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        [
+            "default init",
+            "default end(true)",
+            "command init",
+            "command end(false)",
+            "default init"
+        ]
+    );
+}
+
+fn test_command_handle() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let finished = Arc::new(AtomicBool::new(false));
+    let is_finished = finished.clone();
+    let first = scheduler
+        .schedule(
+            logged_command("first", &log, Requirements::new())
+                .is_finished(move || is_finished.load(Ordering::Relaxed))
+                .build(),
+        )
+        .expect("nothing to conflict with");
+    assert!(first.is_scheduled());
+    assert!(first.time_running().is_some());
+    scheduler.run();
+    finished.store(true, Ordering::Relaxed);
+    scheduler.run();
+    assert!(!first.is_scheduled());
+    assert!(first.time_running().is_none());
+
+    //takes over the slot the first command left behind
+    let second = scheduler
+        .schedule(logged_command("second", &log, Requirements::new()).build())
+        .expect("nothing to conflict with");
+    assert_ne!(first, second);
+    assert!(!first.is_scheduled());
+    assert!(!first.cancel());
+    assert!(second.is_scheduled());
+
+    scheduler.run();
+    assert!(second.cancel());
+    assert!(!second.is_scheduled());
+    assert!(!second.cancel());
+    assert_eq!(
+        *log.lock(),
+        [
+            "first init",
+            "first end(false)",
+            "second init",
+            "second end(true)"
+        ]
+    );
+}
+
+fn test_handles_while_running() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let first = scheduler
+        .schedule(logged_command("first", &log, Requirements::new()).build())
+        .expect("nothing to conflict with");
+
+    //polled by run() while it holds the scheduler
+    let watched = first.clone();
+    let mut conditions = ConditionalScheduler::new();
+    let after_log = log.clone();
+    Trigger::new(move || !watched.is_scheduled()).on_true(&mut conditions, move || {
+        logged_command("after first", &after_log, Requirements::new()).build()
+    });
+    scheduler.add_cond_scheduler(conditions);
+
+    //cancels the first command and schedules another from its periodic
+    let (inner, cancelled) = (scheduler.clone(), first.clone());
+    let spawned = Arc::new(parking_lot::Mutex::new(None));
+    let (spawned_handle, spawned_log) = (spawned.clone(), log.clone());
+    assert!(scheduler
+        .schedule(
+            logged_command("canceller", &log, Requirements::new())
+                .periodic(move || {
+                    assert!(cancelled.cancel());
+                    *spawned_handle.lock() = inner.schedule(
+                        logged_command("spawned", &spawned_log, Requirements::new()).build(),
+                    );
+                })
+                .is_finished(|| true)
+                .build(),
+        )
+        .is_some());
+
+    scheduler.run();
+    //both were put off until the run was over
+    assert_eq!(
+        *log.lock(),
+        [
+            "first init",
+            "canceller init",
+            "canceller end(false)",
+            "first end(true)"
+        ]
+    );
+    assert!(!first.is_scheduled());
+    let spawned = spawned
+        .lock()
+        .clone()
+        .expect("deferred schedules return a handle");
+    assert!(spawned.is_scheduled());
+
+    scheduler.run();
+    assert!(log.lock().iter().any(|line| line == "after first init"));
+    assert!(log.lock().iter().any(|line| line == "spawned init"));
+    assert_eq!(scheduler.get_scheduled_count(), 2);
+}
+
+fn test_command_handle_await() {
+    struct Flag(AtomicBool);
+    impl std::task::Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let handle = scheduler
+        .schedule(logged_command("awaited", &log, Requirements::new()).build())
+        .expect("nothing to conflict with");
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = std::task::Waker::from(flag.clone());
+    let mut context = std::task::Context::from_waker(&waker);
+    let mut completion = std::pin::pin!(handle.clone().into_future());
+
+    assert!(completion.as_mut().poll(&mut context).is_pending());
+    scheduler.run();
+    assert!(completion.as_mut().poll(&mut context).is_pending());
+    assert!(!flag.0.load(Ordering::Relaxed));
+
+    let _ = handle.cancel();
+    assert!(flag.0.load(Ordering::Relaxed));
+    assert!(completion.as_mut().poll(&mut context).is_ready());
+}
+
+fn test_finish_before_later_commands() {
+    let scheduler = CommandScheduler::new();
+    let [a, b] = subsystem_ids();
+    //finishing a command used to shift every later one down a slot
+    let log = Log::default();
+    let finished = Arc::new(AtomicBool::new(false));
+    let is_finished = finished.clone();
+    let first = scheduler
+        .schedule(
+            logged_command("first", &log, a)
+                .is_finished(move || is_finished.load(Ordering::Relaxed))
+                .build(),
+        )
+        .expect("nothing to conflict with");
+    let second = scheduler
+        .schedule(logged_command("second", &log, b).build())
+        .expect("nothing to conflict with");
+    scheduler.run();
+    finished.store(true, Ordering::Relaxed);
+    scheduler.run();
+    assert!(!first.is_scheduled());
+    assert!(second.is_scheduled());
+
+    //the second command still holds its subsystem and is interrupted through it
+    let third = scheduler
+        .schedule(logged_command("third", &log, b).build())
+        .expect("nothing to conflict with");
+    assert!(!second.is_scheduled());
+    scheduler.run();
+    assert!(third.is_scheduled());
+    assert_eq!(
+        *log.lock(),
+        [
+            "first init",
+            "second init",
+            "first end(false)",
+            "second end(true)",
+            "third init"
+        ]
+    );
+}
+
+fn test_reschedule_same_cycle() {
+    let scheduler = CommandScheduler::new();
+    let [a, b] = subsystem_ids();
+    let log = Log::default();
+    let mut conditional = ConditionalScheduler::new();
+    let provider_log = log.clone();
+    conditional.add_cond(conditions::on_true(|| true), move || {
+        logged_command("conditional", &provider_log, a).build()
+    });
+    scheduler.add_cond_scheduler(conditional);
+
+    //a stale stop from the scheduler must not hit the command that reused the slot
+    let handle = scheduler
+        .schedule(logged_command("once", &log, b).is_finished(|| true).build())
+        .expect("nothing to conflict with");
+    scheduler.run();
+    assert!(!handle.is_scheduled());
+    let replacement = scheduler
+        .schedule(logged_command("replacement", &log, b).build())
+        .expect("nothing to conflict with");
+    scheduler.run();
+    scheduler.run();
+    assert!(replacement.is_scheduled());
+    assert_eq!(scheduler.get_scheduled_count(), 2);
+    assert_eq!(
+        *log.lock(),
+        [
+            "once init",
+            "once end(false)",
+            "conditional init",
+            "replacement init"
+        ]
+    );
+}
+
+fn test_many_commands() {
+    const COUNT: usize = 64;
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let ticks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut handles = Vec::new();
+    let pairs: [SubsystemId; COUNT / 2] = subsystem_ids();
+    for i in 0..COUNT {
+        let ticks = ticks.clone();
+        //every command shares a subsystem with its neighbour and finishes at its own time
+        let requirements = pairs[i / 2];
+        let name: &'static str = Box::leak(format!("{i}").into_boxed_str());
+        let command = logged_command(name, &log, requirements)
+            .is_finished(move || ticks.load(Ordering::Relaxed) >= i % 7)
+            .build();
+        handles.push(
+            scheduler
+                .schedule(command)
+                .expect("nothing cancels incoming"),
+        );
+    }
+    //each even command was interrupted by the odd one after it before running
+    for (i, handle) in handles.iter().enumerate() {
+        assert_eq!(handle.is_scheduled(), i % 2 == 1, "command {i}");
+    }
+    assert_eq!(scheduler.get_scheduled_count(), COUNT / 2);
+
+    for tick in 0..8 {
+        ticks.store(tick, Ordering::Relaxed);
+        scheduler.run();
+        //reschedule into the slots freed this cycle
+        if tick == 2 {
+            for i in (0..COUNT).step_by(2) {
+                let name: &'static str = Box::leak(format!("again {i}").into_boxed_str());
+                handles[i] = scheduler
+                    .schedule(
+                        logged_command(name, &log, Requirements::new())
+                            .is_finished(|| true)
+                            .build(),
+                    )
+                    .expect("nothing to conflict with");
+            }
+        }
+    }
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert!(handles.iter().all(|handle| !handle.is_scheduled()));
+
+    let log = log.lock();
+    for i in 0..COUNT {
+        let (init, end) = if i % 2 == 1 {
+            (format!("{i} init"), format!("{i} end(false)"))
+        } else {
+            (format!("again {i} init"), format!("again {i} end(false)"))
+        };
+        assert_eq!(
+            log.iter().filter(|line| **line == init).count(),
+            1,
+            "{init}"
+        );
+        assert_eq!(log.iter().filter(|line| **line == end).count(), 1, "{end}");
+        //the interrupted commands never started so they never end either
+        if i % 2 == 0 {
+            assert!(!log.iter().any(|line| line.starts_with(&format!("{i} "))));
+        }
+    }
+    drop(log);
+}
+
+fn test_independent_schedulers() {
+    let log = Log::default();
+    let id = SubsystemId::next();
+    let first = CommandScheduler::new();
+    let second = CommandScheduler::new();
+    let handle = first
+        .schedule(logged_command("first", &log, id).build())
+        .expect("nothing to conflict with");
+    //the same requirement in another scheduler doesn't conflict
+    second
+        .schedule(logged_command("second", &log, id).build())
+        .expect("nothing to conflict with");
+    first.run();
+    assert!(handle.is_scheduled());
+    assert_eq!(*log.lock(), ["first init"]);
+
+    let mut completion = std::pin::pin!(handle.clone().into_future());
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    drop(first);
+    assert!(!handle.is_scheduled());
+    assert!(!handle.cancel());
+    assert!(completion.as_mut().poll(&mut context).is_ready());
+    assert_eq!(second.get_scheduled_count(), 1);
+}
+
+fn test_decorators() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let id = SubsystemId::next();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = stop.clone();
+    let until = logged_command("until", &log, id)
+        .build()
+        .until(move || stop_flag.load(Ordering::Relaxed));
+    assert_eq!(until.get_requirements(), Requirements::from(id));
+    let _ = scheduler.schedule(until);
+    scheduler.run();
+    stop.store(true, Ordering::Relaxed);
+    scheduler.run();
+    //ending the command early counts as interrupting it
+    assert_eq!(*log.lock(), ["until init", "until end(true)"]);
+    log.lock().clear();
+
+    let _ = scheduler.schedule(
+        logged_command("skipped", &log, Requirements::new())
+            .build()
+            .unless(|| true),
+    );
+    let _ = scheduler.schedule(
+        logged_command("timeout", &log, id)
+            .build()
+            .only_if(|| true)
+            .with_timeout(Second::new(0.0)),
+    );
+    scheduler.run();
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert_eq!(*log.lock(), ["timeout init", "timeout end(true)"]);
+    log.lock().clear();
+
+    let finally_log = log.clone();
+    let repeated = scheduler
+        .schedule(
+            logged_command("repeated", &log, id)
+                .is_finished(|| true)
+                .build()
+                .repeatedly()
+                .finally_do(move |interrupted| {
+                    finally_log.lock().push(format!("finally({interrupted})"));
+                }),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    scheduler.run();
+    assert!(repeated.cancel());
+    assert_eq!(
+        *log.lock(),
+        [
+            "repeated init",
+            "repeated end(false)",
+            "repeated init",
+            "repeated end(false)",
+            "finally(true)"
+        ]
+    );
+    log.lock().clear();
+
+    let interrupt_log = log.clone();
+    let handled = scheduler
+        .schedule(
+            logged_command("handled", &log, id)
+                .build()
+                .handle_interrupt(move || interrupt_log.lock().push(String::from("interrupted"))),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    let _ = handled.cancel();
+    assert_eq!(
+        *log.lock(),
+        ["handled init", "handled end(true)", "interrupted"]
+    );
+
+    let command = CommandBuilder::new().build();
+    assert!(!command.run_when_disabled());
+    let command = command.ignoring_disable(true);
+    assert!(command.run_when_disabled());
+    //a group only runs while disabled if all of its commands do
+    assert!(!command.along_with(Command::empty()).run_when_disabled());
+}
+
+fn test_run_when_disabled() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let [a, b, c] = subsystem_ids();
+    scheduler.register_subsystem(
+        c,
+        Arc::new(parking_lot::Mutex::new(TestSubsystem::new())),
+        Some(logged_command("default", &log, c).build()),
+    );
+    scheduler
+        .schedule(logged_command("teleop", &log, a).build())
+        .expect("nothing to conflict with");
+    let survivor = scheduler
+        .schedule(
+            logged_command("survivor", &log, b)
+                .build()
+                .ignoring_disable(true),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    log.lock().clear();
+
+    //disabling interrupts what can't run while disabled, default commands included
+    scheduler.set_disabled(true);
+    let reasons = Log::default();
+    let reason_log = reasons.clone();
+    scheduler.on_interrupt(move |event, reason| {
+        reason_log.lock().push(format!("{} {reason:?}", event.name));
+    });
+    scheduler.run();
+    assert_eq!(*log.lock(), ["teleop end(true)", "default end(true)"]);
+    assert!(reasons
+        .lock()
+        .iter()
+        .all(|reason| reason.ends_with("Disabled")));
+    assert!(survivor.is_scheduled());
+    assert_eq!(scheduler.get_scheduled_count(), 1);
+
+    //and refuses them without touching what holds their requirements
+    assert!(scheduler
+        .schedule(logged_command("refused", &log, b).build())
+        .is_none());
+    assert!(survivor.is_scheduled());
+    scheduler.run();
+    assert_eq!(*log.lock(), ["teleop end(true)", "default end(true)"]);
+
+    scheduler.set_disabled(false);
+    assert!(scheduler
+        .schedule(logged_command("enabled", &log, a).build())
+        .is_some());
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        [
+            "teleop end(true)",
+            "default end(true)",
+            "enabled init",
+            "default init"
+        ]
+    );
+}
+
+fn test_repeated_sequence() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let id = SubsystemId::next();
+    let sequence = logged_command("first", &log, id)
+        .is_finished(|| true)
+        .build()
+        .before(
+            logged_command("second", &log, id)
+                .is_finished(|| true)
+                .build(),
+        )
+        .repeatedly();
+    let handle = scheduler
+        .schedule(sequence)
+        .expect("nothing to conflict with");
+    for _ in 0..5 {
+        scheduler.run();
+    }
+    let _ = handle.cancel();
+    assert_eq!(
+        *log.lock(),
+        [
+            "first init",
+            "first end(false)",
+            "second init",
+            "second end(false)",
+            "first init",
+            "first end(false)",
+            "second init",
+            "second end(false)",
+            "first init",
+            "first end(false)",
+            "second init",
+            "second end(true)",
+        ]
+    );
+}
+
+fn test_as_proxy() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let [a, b] = subsystem_ids();
+
+    let group = logged_command("proxied", &log, a)
+        .build()
+        .as_proxy_on(&scheduler)
+        .along_with(logged_command("other", &log, b).build());
+    //the group doesn't hold what the proxied command requires
+    assert_eq!(group.get_requirements(), Requirements::from(b));
+    let group = scheduler.schedule(group).expect("nothing to conflict with");
+    scheduler.run();
+    assert_eq!(scheduler.get_scheduled_count(), 2);
+    scheduler.run();
+    assert_eq!(*log.lock(), ["other init", "proxied init"]);
+
+    //interrupting the proxied command leaves the group running
+    let _ = scheduler.schedule(logged_command("arm", &log, a).build());
+    scheduler.run();
+    assert!(group.is_scheduled());
+    assert_eq!(
+        *log.lock(),
+        [
+            "other init",
+            "proxied init",
+            "proxied end(true)",
+            "arm init"
+        ]
+    );
+    log.lock().clear();
+
+    //cancelling the proxy cancels the proxied command
+    let proxy = scheduler
+        .schedule(
+            logged_command("inner", &log, a)
+                .build()
+                .as_proxy_on(&scheduler),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    scheduler.run();
+    let _ = proxy.cancel();
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        ["arm end(true)", "inner init", "inner end(true)"]
+    );
+    assert_eq!(scheduler.get_scheduled_count(), 1);
+    log.lock().clear();
+
+    //the proxied command comes back once it ends, so a repeated proxy runs it every time
+    let finished = Arc::new(AtomicBool::new(false));
+    let is_finished = finished.clone();
+    let repeated = scheduler
+        .schedule(
+            logged_command("again", &log, a)
+                .is_finished(move || is_finished.load(Ordering::Relaxed))
+                .build()
+                .as_proxy_on(&scheduler)
+                .repeatedly(),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    scheduler.run();
+    finished.store(true, Ordering::Relaxed);
+    scheduler.run();
+    finished.store(false, Ordering::Relaxed);
+    scheduler.run();
+    scheduler.run();
+    let _ = repeated.cancel();
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        [
+            "again init",
+            "again end(false)",
+            "again init",
+            "again end(true)"
+        ]
+    );
+}
+
+fn test_deadline() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let [a, b] = subsystem_ids();
+    let done = Arc::new(AtomicBool::new(false));
+    let done_flag = done.clone();
+    let group = logged_command("deadline", &log, a)
+        .is_finished(move || done_flag.load(Ordering::Relaxed))
+        .build()
+        .deadline_with(logged_command("other", &log, b).build());
+    assert_eq!(group.get_requirements(), Requirements::from([a, b]));
+    let _ = scheduler.schedule(group);
+    scheduler.run();
+    scheduler.run();
+    done.store(true, Ordering::Relaxed);
+    scheduler.run();
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert_eq!(
+        *log.lock(),
+        [
+            "deadline init",
+            "other init",
+            "deadline end(false)",
+            "other end(true)"
+        ]
+    );
+}
+
+fn test_select() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let [a, b] = subsystem_ids();
+    let selected = Arc::new(parking_lot::Mutex::new("left"));
+    let selector = selected.clone();
+    let select = Command::select(
+        HashMap::from([
+            ("left", logged_command("left", &log, a).build()),
+            ("right", logged_command("right", &log, b).build()),
+        ]),
+        move || *selector.lock(),
+    );
+    assert_eq!(select.get_requirements(), Requirements::from([a, b]));
+    let handle = scheduler
+        .schedule(select)
+        .expect("nothing to conflict with");
+    scheduler.run();
+    *selected.lock() = "right";
+    //the choice is made once, when initialized
+    scheduler.run();
+    let _ = handle.cancel();
+    assert_eq!(*log.lock(), ["left init", "left end(true)"]);
+    log.lock().clear();
+
+    *selected.lock() = "missing";
+    let select = Command::select(
+        HashMap::from([("left", logged_command("left", &log, a).build())]),
+        move || *selected.lock(),
+    );
+    let _ = scheduler.schedule(select);
+    scheduler.run();
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert!(log.lock().is_empty());
+
+    let _ = scheduler.schedule(Command::either(
+        logged_command("true", &log, a).is_finished(|| true).build(),
+        logged_command("false", &log, a)
+            .is_finished(|| true)
+            .build(),
+        || false,
+    ));
+    scheduler.run();
+    assert_eq!(*log.lock(), ["false init", "false end(false)"]);
+}
+
+fn test_defer() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let id = SubsystemId::next();
+    let supplier_log = log.clone();
+    let mut built = 0;
+    let deferred = Command::defer(
+        move || {
+            built += 1;
+            let name = if built == 1 { "first" } else { "second" };
+            logged_command(name, &supplier_log, Requirements::new())
+                .is_finished(|| true)
+                .build()
+        },
+        id,
+        false,
+        false,
+    );
+    //the declared requirements are known before anything is built
+    assert_eq!(deferred.get_requirements(), Requirements::from(id));
+    let _ = scheduler.schedule(deferred.repeatedly());
+    scheduler.run();
+    scheduler.run();
+    assert_eq!(
+        *log.lock(),
+        [
+            "first init",
+            "first end(false)",
+            "second init",
+            "second end(false)"
+        ]
+    );
+
+    //the flags are declared up front too, the scheduler checks them before anything is built
+    let disabled = CommandScheduler::new();
+    disabled.set_disabled(true);
+    let supplier_log = log.clone();
+    let runs_disabled = Command::defer(
+        move || logged_command("disabled", &supplier_log, Requirements::new()).build(),
+        Requirements::new(),
+        true,
+        true,
+    );
+    assert!(runs_disabled.run_when_disabled());
+    assert!(runs_disabled.cancel_incoming());
+    assert!(disabled.schedule(runs_disabled).is_some());
+    disabled.run();
+    assert_eq!(log.lock().last().map(String::as_str), Some("disabled init"));
+    assert!(disabled
+        .schedule(Command::defer(
+            Command::empty,
+            Requirements::new(),
+            false,
+            false
+        ))
+        .is_none());
+}
+
+fn test_wait_on_clock() {
+    let scheduler = CommandScheduler::new();
+    let clock = SimClock::new();
+    let log = Log::default();
+    let wait = Command::Wait(WaitCommand::with_clock(
+        Second::new(1.0),
+        Arc::new(clock.clone()),
+    ))
+    .before(logged_command("after", &log, Requirements::new()).build());
+    let _ = scheduler.schedule(wait);
+    scheduler.run();
+    clock.step(Duration::from_millis(999));
+    scheduler.run();
+    assert!(log.lock().is_empty());
+    clock.step(Duration::from_millis(1));
+    scheduler.run();
+    assert_eq!(*log.lock(), ["after init"]);
+}
+
+fn test_event_hooks() {
+    let clock = SimClock::new();
+    let scheduler = CommandScheduler::with_clock(Arc::new(clock.clone()));
+    let events = Log::default();
+    let record = |kind: &'static str| {
+        let events = events.clone();
+        move |event: &CommandEvent| {
+            events.lock().push(format!(
+                "{kind} {} {}ms",
+                event.name,
+                event.timestamp.as_millis()
+            ));
+        }
+    };
+    scheduler.on_init(record("init"));
+    scheduler.on_execute(record("execute"));
+    scheduler.on_finish(record("finish"));
+    let interrupts = events.clone();
+    scheduler.on_interrupt(move |event, reason| {
+        interrupts
+            .lock()
+            .push(format!("interrupt {} {reason:?}", event.name));
+    });
+
+    let id = SubsystemId::next();
+    let first = scheduler
+        .schedule(
+            CommandBuilder::new()
+                .with_requirements(id)
+                .build()
+                .with_name("first"),
+        )
+        .expect("nothing to conflict with");
+    scheduler.run();
+    clock.step(Duration::from_millis(20));
+    //never initialized, so never reported
+    let _ = scheduler.schedule(
+        CommandBuilder::new()
+            .with_requirements(id)
+            .build()
+            .with_name("unrun"),
+    );
+    let _ = scheduler.schedule(
+        CommandBuilder::new()
+            .is_finished(|| true)
+            .with_requirements(id)
+            .build()
+            .with_name("second"),
+    );
+    scheduler.run();
+    assert!(!first.is_scheduled());
+    assert_eq!(
+        *events.lock(),
+        [
+            "init first 0ms",
+            "execute first 0ms",
+            "interrupt first Requirement(\"unrun\")",
+            "init second 20ms",
+            "execute second 20ms",
+            "finish second 20ms"
+        ]
+    );
+    events.lock().clear();
+
+    let handles = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let init_handles = handles.clone();
+    scheduler.on_init(move |event| init_handles.lock().extend(event.handle.clone()));
+    let third = scheduler
+        .schedule(Command::empty().with_name("third"))
+        .expect("nothing to conflict with");
+    scheduler.run();
+    let _ = third.cancel();
+    assert_eq!(*handles.lock(), [third]);
+    assert_eq!(
+        *events.lock(),
+        [
+            "init third 20ms",
+            "execute third 20ms",
+            "interrupt third Cancelled"
+        ]
+    );
+}
+
+fn test_state_machine() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let [a, b] = subsystem_ids();
+    let intake = Arc::new(AtomicBool::new(false));
+    let intake_pressed = intake.clone();
+    let (entry_log, exit_log) = (log.clone(), log.clone());
+    let machine = StateMachineBuilder::new("stowed")
+        .state("stowed", logged_command("stowed", &log, a).build())
+        .state("intaking", logged_command("intaking", &log, b).build())
+        .state(
+            "holding",
+            logged_command("holding", &log, a)
+                .is_finished(|| true)
+                .build(),
+        )
+        .transition("stowed", "intaking", move || {
+            intake_pressed.load(Ordering::Relaxed)
+        })
+        .transition("intaking", "holding", || true)
+        .on_entry("intaking", move || {
+            entry_log.lock().push(String::from("enter intaking"));
+        })
+        .on_exit("intaking", move || {
+            exit_log.lock().push(String::from("exit intaking"));
+        })
+        .build()
+        .expect("every state exists");
+    assert_eq!(machine.get_current_state(), None);
+    let state = machine.get_state_handle();
+    let machine = Command::StateMachine(machine);
+    //requires everything any state does, like a sequence
+    assert_eq!(machine.get_requirements(), Requirements::from([a, b]));
+
+    let _ = scheduler.schedule(machine);
+    scheduler.run();
+    scheduler.run();
+    assert_eq!(state.get().as_deref(), Some("stowed"));
+    intake.store(true, Ordering::Relaxed);
+    scheduler.run();
+    assert_eq!(state.get().as_deref(), Some("intaking"));
+    scheduler.run();
+    assert_eq!(state.get().as_deref(), Some("holding"));
+    //holding finishes without a way out, so the machine does too
+    scheduler.run();
+    assert_eq!(state.get(), None);
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert_eq!(
+        *log.lock(),
+        [
+            "stowed init",
+            "stowed end(true)",
+            "enter intaking",
+            "intaking init",
+            "intaking end(true)",
+            "exit intaking",
+            "holding init",
+            "holding end(false)"
+        ]
+    );
+
+    let unknown = StateMachineBuilder::new("stowed")
+        .state("stowed", Command::empty())
+        .transition_on_finish("stowed", "scoring")
+        .build();
+    assert_eq!(
+        unknown.err(),
+        Some(StateMachineError::UnknownState(String::from("scoring")))
+    );
+    let duplicate = StateMachineBuilder::new("stowed")
+        .state("stowed", Command::empty())
+        .state("stowed", Command::empty())
+        .build();
+    assert_eq!(
+        duplicate.err(),
+        Some(StateMachineError::DuplicateState(String::from("stowed")))
+    );
+}
+
+fn logging_registry(log: &Log, [a, b]: [SubsystemId; 2]) -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+    for (name, requirement) in [("intake", a), ("shoot", b), ("aim", b)] {
+        let log = log.clone();
+        registry.register(name, move || {
+            logged_command(name, &log, requirement)
+                .is_finished(|| true)
+                .build()
+                .with_name(name)
+        });
+    }
+    registry
+}
+
+fn test_routine_loader() {
+    let scheduler = CommandScheduler::new();
+    let log = Log::default();
+    let ids = subsystem_ids();
+    let registry = logging_registry(&log, ids);
+    let routine = registry
+        .load(
+            r#"{
+                "type": "sequential",
+                "data": {
+                    "commands": [
+                        { "type": "named", "data": { "name": "aim" } },
+                        { "type": "wait", "data": { "waitTime": 0.0 } },
+                        {
+                            "type": "deadline",
+                            "data": {
+                                "commands": [
+                                    { "type": "named", "data": { "name": "shoot" } },
+                                    { "type": "named", "data": { "name": "intake" } }
+                                ]
+                            }
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .expect("a valid routine");
+    assert_eq!(routine.get_requirements(), Requirements::from(ids));
+    let _ = scheduler.schedule(routine);
+    for _ in 0..3 {
+        scheduler.run();
+    }
+    assert_eq!(scheduler.get_scheduled_count(), 0);
+    assert_eq!(
+        *log.lock(),
+        [
+            "aim init",
+            "aim end(false)",
+            "shoot init",
+            "intake init",
+            "shoot end(false)",
+            "intake end(false)"
+        ]
+    );
+
+    let step = |name: &str| RoutineStep::Named {
+        name: String::from(name),
+    };
+    assert!(matches!(
+        registry.build_routine(&RoutineStep::Parallel {
+            commands: vec![step("intake"), step("climb")],
+        }),
+        Err(RoutineError::UnknownCommand(name)) if name == "climb"
+    ));
+    //sequences may reuse a subsystem, parallel groups may not
+    assert!(registry
+        .build_routine(&RoutineStep::Sequential {
+            commands: vec![step("shoot"), step("aim")],
+        })
+        .is_ok());
+    assert!(matches!(
+        registry.build_routine(&RoutineStep::Race {
+            commands: vec![step("intake"), step("shoot"), step("aim")],
+        }),
+        Err(RoutineError::RequirementConflict { first, second, requirement })
+            if first == "shoot" && second == "aim" && requirement == ids[1]
+    ));
+    assert!(matches!(
+        registry.build_routine(&RoutineStep::Deadline { commands: vec![] }),
+        Err(RoutineError::EmptyDeadline)
+    ));
+    assert!(matches!(
+        registry.load(r#"{ "type": "wait", "data": { "waitTime": -1.0 } }"#),
+        Err(RoutineError::InvalidWait(_))
+    ));
+    assert!(matches!(
+        registry.load(r#"{ "type": "path", "data": { "pathName": "start" } }"#),
+        Err(RoutineError::Parse(_))
+    ));
 }
 
 #[test]
 fn command() {
-    run_in_clean_state(test_command);
+    test_command();
 }
 
 #[test]
 fn subsystem() {
-    run_in_clean_state(test_subsystem);
+    test_subsystem();
 }
 
 #[test]
 fn on_true() {
-    run_in_clean_state(test_on_true);
+    test_on_true();
+}
+
+#[test]
+fn watchdog_epochs() {
+    test_watchdog_epochs();
+}
+
+#[test]
+fn on_ds_event() {
+    test_on_ds_event();
+}
+
+#[test]
+fn hid_binding() {
+    test_hid_binding();
+}
+
+#[test]
+fn trigger_toggle() {
+    test_trigger_toggle();
+}
+
+#[test]
+fn toggle_finished_command() {
+    test_toggle_finished_command();
+}
+
+#[test]
+fn interrupt_holder() {
+    test_interrupt_holder();
+}
+
+#[test]
+fn cancel_incoming() {
+    test_cancel_incoming();
+}
+
+#[test]
+fn interrupt_default() {
+    test_interrupt_default();
+}
+
+#[test]
+fn command_handle() {
+    test_command_handle();
+}
+
+#[test]
+fn handles_while_running() {
+    test_handles_while_running();
+}
+
+#[test]
+fn command_handle_await() {
+    test_command_handle_await();
+}
+
+#[test]
+fn finish_before_later_commands() {
+    test_finish_before_later_commands();
+}
+
+#[test]
+fn reschedule_same_cycle() {
+    test_reschedule_same_cycle();
+}
+
+#[test]
+fn many_commands() {
+    test_many_commands();
+}
+
+#[test]
+fn independent_schedulers() {
+    test_independent_schedulers();
+}
+
+#[test]
+fn decorators() {
+    test_decorators();
+}
+
+#[test]
+fn run_when_disabled() {
+    test_run_when_disabled();
+}
+
+#[test]
+fn repeated_sequence() {
+    test_repeated_sequence();
+}
+
+#[test]
+fn as_proxy() {
+    test_as_proxy();
+}
+
+#[test]
+fn deadline() {
+    test_deadline();
+}
+
+#[test]
+fn select() {
+    test_select();
+}
+
+#[test]
+fn defer() {
+    test_defer();
+}
+
+#[test]
+fn wait_on_clock() {
+    test_wait_on_clock();
+}
+
+#[test]
+fn event_hooks() {
+    test_event_hooks();
+}
+
+#[test]
+fn state_machine() {
+    test_state_machine();
+}
+
+#[test]
+fn routine_loader() {
+    test_routine_loader();
+}
+
+#[test]
+fn requirements() {
+    let drivetrain = SubsystemRef::new(TestSubsystem::new());
+    let arm = SubsystemRef::new(TestSubsystem::new());
+    assert_ne!(drivetrain.get_id(), arm.get_id());
+    assert_eq!(drivetrain.clone().get_id(), drivetrain.get_id());
+
+    let requirements = Requirements::from(&drivetrain)
+        .with(&arm)
+        .with(drivetrain.get_id());
+    assert_eq!(requirements.len(), 2);
+    assert!(requirements.contains(arm.get_id()));
+
+    //compositions require everything their parts do
+    let command = drivetrain
+        .cmd_activate_motor()
+        .along_with(CommandBuilder::new().with_requirements(&arm).build());
+    assert_eq!(command.get_requirements(), requirements);
+}
+
+#[test]
+fn arena() {
+    let mut arena = Arena::new();
+    let first = arena.insert("first");
+    let second = arena.insert("second");
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.remove(first), Some("first"));
+    assert_eq!(arena.remove(first), None);
+
+    //the freed slot is reused under a new generation
+    let third = arena.insert("third");
+    assert_ne!(first, third);
+    assert_eq!(arena.get(first), None);
+    assert_eq!(arena.get(third), Some(&"third"));
+    assert_eq!(arena.get(second), Some(&"second"));
+    assert_eq!(arena.keys().len(), 2);
+    assert!(arena.contains(second));
+    arena.remove(second);
+    arena.remove(third);
+    assert!(arena.keys().is_empty());
+}
+
+#[test]
+fn trigger_composition() {
+    let a = Arc::new(AtomicBool::new(false));
+    let b = Arc::new(AtomicBool::new(false));
+    let trigger_a = {
+        let a = a.clone();
+        Trigger::new(move || a.load(Ordering::Relaxed))
+    };
+    let trigger_b = {
+        let b = b.clone();
+        Trigger::new(move || b.load(Ordering::Relaxed))
+    };
+    let both = trigger_a.and(&trigger_b);
+    let either = trigger_a.or(&trigger_b);
+    let neither = either.negate();
+
+    assert!(!both.get() && !either.get() && neither.get());
+    a.store(true, Ordering::Relaxed);
+    assert!(!both.get() && either.get() && !neither.get());
+    b.store(true, Ordering::Relaxed);
+    assert!(both.get() && either.get() && !neither.get());
+}
+
+#[test]
+fn trigger_debounce() {
+    let input = Arc::new(AtomicBool::new(false));
+    let clock = SimClock::new();
+    let debounced = {
+        let input = input.clone();
+        Trigger::new(move || input.load(Ordering::Relaxed))
+    }
+    .debounce_with_clock(
+        Second::new(0.05),
+        DebounceType::Rising,
+        Arc::new(clock.clone()),
+    );
+
+    assert!(!debounced.get());
+    input.store(true, Ordering::Relaxed);
+    assert!(!debounced.get());
+    clock.step(Duration::from_millis(40));
+    assert!(!debounced.get());
+    clock.step(Duration::from_millis(40));
+    assert!(debounced.get());
+    //only rising edges are debounced
+    input.store(false, Ordering::Relaxed);
+    assert!(!debounced.get());
+}
+
+#[test]
+fn on_change() {
+    let input = Arc::new(AtomicBool::new(true));
+    let mut cond = {
+        let input = input.clone();
+        conditions::on_change(move || input.load(Ordering::Relaxed))
+    };
+    assert!(matches!(cond.get_condition(), ConditionResponse::NoChange));
+    input.store(false, Ordering::Relaxed);
+    assert!(matches!(cond.get_condition(), ConditionResponse::Start));
+    assert!(matches!(cond.get_condition(), ConditionResponse::NoChange));
+    input.store(true, Ordering::Relaxed);
+    assert!(matches!(cond.get_condition(), ConditionResponse::Start));
+}
+
+#[test]
+fn hid_inputs() {
+    let joysticks = SimJoysticks::new();
+    let controller = XboxController::with_source(1, joysticks.clone());
+    assert!(!controller.is_connected());
+    assert!(!controller.b().get());
+    assert!(controller.get_left_x().abs() < f64::EPSILON);
+    assert_eq!(controller.get_pov(0), -1);
+
+    joysticks.set_button(1, 2, true);
+    joysticks.set_axis(1, 0, 1.0);
+    joysticks.set_axis(1, 1, -1.0);
+    joysticks.set_axis(1, 3, 0.5);
+    joysticks.set_pov(1, 0, 90);
+
+    assert!(controller.is_connected());
+    assert!(controller.b().get());
+    assert!(!controller.a().get());
+    assert!((controller.get_left_x() - 1.0).abs() < f64::EPSILON);
+    assert!((controller.get_left_y() + 1.0).abs() < f64::EPSILON);
+    //axes are quantized to 1/127
+    assert!((controller.get_right_trigger_axis() - 0.5).abs() < 0.01);
+    assert!(controller.right_trigger(0.4).get());
+    assert!(!controller.left_trigger(0.4).get());
+    assert!(controller.pov_right().get());
+    assert!(!controller.pov_up().get());
+    assert_eq!(controller.get_button_count(), 2);
+
+    //the same port read through another device type
+    let ps5 = PS5Controller::with_source(1, joysticks.clone());
+    assert!(ps5.cross().get());
+    assert!(!GenericHID::with_source(0, joysticks.clone()).is_connected());
+
+    controller.set_rumble(RumbleType::Left, 2.0);
+    controller.set_rumble(RumbleType::Right, 0.25);
+    controller.set_output(3, true);
+    let outputs = joysticks.get_outputs(1);
+    assert!((outputs.left_rumble - 1.0).abs() < f64::EPSILON);
+    assert!((outputs.right_rumble - 0.25).abs() < f64::EPSILON);
+    assert_eq!(outputs.outputs, 0b100);
+
+    joysticks.unplug(1);
+    assert!(!controller.b().get());
+}
+
+#[test]
+fn driver_station_outputs() {
+    //devices on the driver station send their outputs back through it
+    let controller = GenericHID::new(4);
+    controller.set_rumble(RumbleType::Both, 0.5);
+    controller.set_output(1, true);
+    assert_eq!(
+        DriverStation::get_joystick_outputs(4),
+        JoystickOutputs {
+            outputs: 1,
+            left_rumble: 32768,
+            right_rumble: 32768,
+        }
+    );
+    controller.set_rumble(RumbleType::Both, 0.0);
+    controller.set_output(1, false);
+
+    //ports the driver station doesn't have are dropped rather than grown into
+    let missing = GenericHID::new(usize::MAX);
+    missing.set_rumble(RumbleType::Both, 1.0);
+    assert!((missing.get_outputs().left_rumble - 1.0).abs() < f64::EPSILON);
+    assert_eq!(
+        DriverStation::get_joystick_outputs(usize::MAX),
+        JoystickOutputs::default()
+    );
 }
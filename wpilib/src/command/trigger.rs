@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use super::{
+    conditions,
+    manager::{BoxedFn, ConditionalScheduler},
+};
+use crate::{
+    math::{
+        filter::debouncer::{DebounceType, Debouncer},
+        units::time::Second,
+    },
+    timer::{get_default_clock, ClockRef},
+};
+
+/// A boolean condition that commands can be bound to.
+///
+/// Triggers are cheap to clone and compose, every binding registers a condition into the
+/// given [`ConditionalScheduler`]:
+/// ```ignore
+/// let shoot = controller.a().trigger().and(&ready).debounce(Second::new(0.1), DebounceType::Rising);
+/// shoot
+///     .on_true(&mut scheduler, command_provider!{...})
+///     .while_false(&mut scheduler, command_provider!{...});
+/// ```
+#[derive(Clone)]
+pub struct Trigger {
+    condition: Arc<dyn Fn() -> bool + Send + Sync>,
+}
+
+impl Trigger {
+    pub fn new(condition: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            condition: Arc::new(condition),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self) -> bool {
+        (self.condition)()
+    }
+
+    /// True while both triggers are, `other` isn't polled when this one is false.
+    #[must_use]
+    pub fn and(&self, other: &Self) -> Self {
+        let (this, other) = (self.clone(), other.clone());
+        Self::new(move || this.get() && other.get())
+    }
+
+    /// True while either trigger is, `other` isn't polled when this one is true.
+    #[must_use]
+    pub fn or(&self, other: &Self) -> Self {
+        let (this, other) = (self.clone(), other.clone());
+        Self::new(move || this.get() || other.get())
+    }
+
+    #[must_use]
+    pub fn negate(&self) -> Self {
+        let this = self.clone();
+        Self::new(move || !this.get())
+    }
+
+    /// Only changes once the condition has held its new value for `time`.
+    ///
+    /// The debouncer is shared between clones of the returned trigger, poll it from one place.
+    #[must_use]
+    pub fn debounce(&self, time: Second, debounce_type: DebounceType) -> Self {
+        self.debounce_with_clock(time, debounce_type, get_default_clock())
+    }
+
+    /// Same as [`Trigger::debounce`] but timed on `clock` instead of the default clock.
+    #[must_use]
+    pub fn debounce_with_clock(
+        &self,
+        time: Second,
+        debounce_type: DebounceType,
+        clock: ClockRef,
+    ) -> Self {
+        let this = self.clone();
+        let debouncer = Mutex::new(Debouncer::with_clock(time, debounce_type, false, clock));
+        Self::new(move || debouncer.lock().calculate(this.get()))
+    }
+
+    /// Starts the command when the condition becomes true.
+    pub fn on_true(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::on_true(move || this.get()), command);
+        self
+    }
+
+    /// Starts the command when the condition becomes false.
+    pub fn on_false(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::on_false(move || this.get()), command);
+        self
+    }
+
+    /// Starts the command every time the condition changes.
+    pub fn on_change(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::on_change(move || this.get()), command);
+        self
+    }
+
+    /// Runs the command while the condition is true, cancelling it when it becomes false.
+    pub fn while_true(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::while_true(move || this.get()), command);
+        self
+    }
+
+    /// Runs the command while the condition is false, cancelling it when it becomes true.
+    pub fn while_false(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::while_false(move || this.get()), command);
+        self
+    }
+
+    /// Starts the command when the condition becomes true and cancels it the next time it does.
+    pub fn toggle_on_true(
+        &self,
+        scheduler: &mut ConditionalScheduler,
+        command: impl BoxedFn + 'static,
+    ) -> &Self {
+        let this = self.clone();
+        scheduler.add_cond(conditions::toggle_on_true(move || this.get()), command);
+        self
+    }
+}
+
+impl std::fmt::Debug for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Trigger").finish_non_exhaustive()
+    }
+}
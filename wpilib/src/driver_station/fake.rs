@@ -0,0 +1,101 @@
+use std::{
+    io::{Error, ErrorKind},
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use super::{
+    protocol::{AllianceStation, ControlPacket, JoystickData, StatusPacket},
+    server::{DriverStationConfig, DriverStationServer},
+};
+use crate::robots::ControlWord;
+
+/// Plays the driver station over loopback so the protocol and anything built on it can be
+/// tested without hardware.
+#[derive(Debug)]
+pub struct FakeDriverStation {
+    socket: UdpSocket,
+    robot: SocketAddr,
+    packet: ControlPacket,
+}
+
+impl FakeDriverStation {
+    /// Starts a [`DriverStationServer`] on a free loopback port and a fake driver station
+    /// talking to it.
+    ///
+    /// # Errors
+    /// If either socket can't be bound.
+    pub fn with_server() -> std::io::Result<(Self, DriverStationServer)> {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let server = DriverStationServer::start(DriverStationConfig {
+            bind_address: Ipv4Addr::LOCALHOST.into(),
+            robot_port: 0,
+            ds_port: socket.local_addr()?.port(),
+            tcp_port: 0,
+            ..DriverStationConfig::default()
+        })?;
+        Ok((Self::new(socket, server.local_addr()), server))
+    }
+
+    /// Wraps an already bound socket that sends control packets to `robot`.
+    #[must_use]
+    pub fn new(socket: UdpSocket, robot: SocketAddr) -> Self {
+        Self {
+            socket,
+            robot,
+            packet: ControlPacket::default(),
+        }
+    }
+
+    /// The packet sent by the next [`FakeDriverStation::send`], without its sequence number.
+    pub const fn packet_mut(&mut self) -> &mut ControlPacket {
+        &mut self.packet
+    }
+
+    pub const fn set_control_word(&mut self, control: ControlWord) {
+        self.packet.control = control;
+    }
+
+    pub const fn set_station(&mut self, station: AllianceStation) {
+        self.packet.station = station;
+    }
+
+    pub fn set_joystick(&mut self, slot: usize, joystick: JoystickData) {
+        if self.packet.joysticks.len() <= slot {
+            self.packet
+                .joysticks
+                .resize(slot + 1, JoystickData::default());
+        }
+        self.packet.joysticks[slot] = joystick;
+    }
+
+    /// Sends the current packet and advances the sequence number.
+    ///
+    /// # Errors
+    /// If the packet can't be sent.
+    pub fn send(&mut self) -> std::io::Result<()> {
+        self.socket.send_to(&self.packet.encode(), self.robot)?;
+        self.packet.sequence = self.packet.sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for the next status packet from the robot.
+    ///
+    /// # Errors
+    /// If nothing arrives in time or the packet can't be decoded.
+    pub fn receive_status(&self, timeout: Duration) -> std::io::Result<StatusPacket> {
+        self.socket.set_read_timeout(Some(timeout))?;
+        let mut buf = [0u8; 1500];
+        let (len, _) = self.socket.recv_from(&mut buf)?;
+        StatusPacket::decode(&buf[..len]).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    /// Sends the current packet and waits for the robot's answer.
+    ///
+    /// # Errors
+    /// If sending or receiving fails.
+    pub fn exchange(&mut self, timeout: Duration) -> std::io::Result<StatusPacket> {
+        self.send()?;
+        self.receive_status(timeout)
+    }
+}
@@ -0,0 +1,17 @@
+pub mod fake;
+pub mod protocol;
+pub mod server;
+pub mod state;
+#[cfg(test)]
+mod test;
+
+pub use fake::FakeDriverStation;
+pub use protocol::{
+    AllianceStation, ControlPacket, DsDate, ErrorMessage, JoystickData, JoystickOutputs,
+    ProtocolError, StatusPacket,
+};
+pub use server::{DriverStationConfig, DriverStationHandle, DriverStationServer, RobotStatus};
+pub use state::{
+    Alliance, DriverStation, DriverStationData, DsEvent, DsSubscriber, MatchInfo, MatchType,
+    JOYSTICK_SLOTS,
+};
@@ -0,0 +1,629 @@
+//! Encoding of the UDP packets exchanged between the driver station and the robot.
+//!
+//! The driver station sends a [`ControlPacket`] to the robot every 20ms, the robot
+//! answers each one with a [`StatusPacket`] carrying the outputs of each joystick. Both
+//! start with a fixed header followed by tags, each tag is `[size, id, data...]` where
+//! `size` counts the id and the data.
+//!
+//! Console messages like an [`ErrorMessage`] go over a separate TCP connection, where
+//! every tag has a two byte size.
+
+use crate::robots::{ControlWord, RobotMode};
+
+pub const COMM_VERSION: u8 = 0x01;
+
+//control byte, shared by both directions
+const CONTROL_E_STOP: u8 = 0x80;
+const CONTROL_FMS_ATTACHED: u8 = 0x08;
+const CONTROL_ENABLED: u8 = 0x04;
+const CONTROL_MODE_MASK: u8 = 0x03;
+const MODE_TELEOP: u8 = 0x00;
+const MODE_TEST: u8 = 0x01;
+const MODE_AUTONOMOUS: u8 = 0x02;
+
+const REQUEST_REBOOT: u8 = 0x08;
+const REQUEST_RESTART_CODE: u8 = 0x04;
+
+const STATUS_BROWNOUT: u8 = 0x10;
+const STATUS_CODE_INITIALIZING: u8 = 0x08;
+//status bits that share the control byte, fms attached isn't reported back so they reuse its bit
+const STATUS_MASK: u8 = STATUS_BROWNOUT | STATUS_CODE_INITIALIZING;
+
+const TRACE_ROBOT_CODE: u8 = 0x20;
+const TRACE_IS_ROBORIO: u8 = 0x10;
+const TRACE_TEST: u8 = 0x08;
+const TRACE_AUTONOMOUS: u8 = 0x04;
+const TRACE_TELEOP: u8 = 0x02;
+const TRACE_DISABLED: u8 = 0x01;
+
+const STATUS_REQUEST_DATE: u8 = 0x01;
+
+const TAG_JOYSTICK_OUTPUTS: u8 = 0x01;
+const TAG_COUNTDOWN: u8 = 0x07;
+const TAG_JOYSTICK: u8 = 0x0c;
+const TAG_DATE: u8 = 0x0f;
+const TAG_TIMEZONE: u8 = 0x10;
+
+const TCP_TAG_ERROR_MESSAGE: u8 = 0x0b;
+//keeps the three strings of an error message inside a tag's two byte size
+const MAX_MESSAGE_STRING_LEN: usize = 16 * 1024;
+
+const CONTROL_HEADER_LEN: usize = 6;
+const STATUS_HEADER_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("packet is shorter than its header")]
+    TooShort,
+    #[error("unsupported comm version {0}")]
+    UnsupportedVersion(u8),
+    #[error("tag {id:#04x} overruns the end of the packet")]
+    TruncatedTag { id: u8 },
+    #[error("invalid alliance station {0}")]
+    InvalidStation(u8),
+    #[error("invalid robot mode bits {0}")]
+    InvalidMode(u8),
+    #[error("unexpected tag {0:#04x}")]
+    UnexpectedTag(u8),
+}
+
+/// Which of the six driver station positions the robot is assigned to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AllianceStation {
+    #[default]
+    Red1 = 0,
+    Red2 = 1,
+    Red3 = 2,
+    Blue1 = 3,
+    Blue2 = 4,
+    Blue3 = 5,
+}
+
+impl AllianceStation {
+    #[must_use]
+    pub const fn is_red(&self) -> bool {
+        matches!(self, Self::Red1 | Self::Red2 | Self::Red3)
+    }
+
+    #[must_use]
+    pub const fn is_blue(&self) -> bool {
+        !self.is_red()
+    }
+
+    /// The station number within the alliance, from 1 to 3.
+    #[must_use]
+    pub const fn get_number(&self) -> u8 {
+        (*self as u8) % 3 + 1
+    }
+}
+
+impl TryFrom<u8> for AllianceStation {
+    type Error = ProtocolError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Red1),
+            1 => Ok(Self::Red2),
+            2 => Ok(Self::Red3),
+            3 => Ok(Self::Blue1),
+            4 => Ok(Self::Blue2),
+            5 => Ok(Self::Blue3),
+            _ => Err(ProtocolError::InvalidStation(value)),
+        }
+    }
+}
+
+/// The state of a single joystick as reported by the driver station.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JoystickData {
+    pub axes: Vec<i8>,
+    pub buttons: Vec<bool>,
+    /// Angle in degrees of each POV hat, -1 when not pressed.
+    pub povs: Vec<i16>,
+}
+
+/// What the robot sends back to a joystick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct JoystickOutputs {
+    /// One bit per output, 0 indexed.
+    pub outputs: u32,
+    /// From 0 for off to `u16::MAX` for full strength.
+    pub left_rumble: u16,
+    pub right_rumble: u16,
+}
+
+/// The wall clock time sent by the driver station when the robot asks for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DsDate {
+    pub microseconds: u32,
+    pub second: u8,
+    pub minute: u8,
+    pub hour: u8,
+    pub day: u8,
+    /// Zero based, January is 0.
+    pub month: u8,
+    /// Years since 1900.
+    pub year: u8,
+}
+
+/// Driver station → robot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ControlPacket {
+    pub sequence: u16,
+    pub control: ControlWord,
+    pub reboot_requested: bool,
+    pub restart_code_requested: bool,
+    pub station: AllianceStation,
+    /// Seconds left in the current match period, if the driver station sent it.
+    pub countdown: Option<f32>,
+    /// One entry per joystick slot, in slot order.
+    pub joysticks: Vec<JoystickData>,
+    pub date: Option<DsDate>,
+    pub timezone: Option<String>,
+}
+
+/// Robot → driver station.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusPacket {
+    pub sequence: u16,
+    pub control: ControlWord,
+    pub code_running: bool,
+    pub code_initializing: bool,
+    pub brownout: bool,
+    pub battery_voltage: f64,
+    pub request_date: bool,
+    /// One entry per joystick slot, in slot order.
+    pub joystick_outputs: Vec<JoystickOutputs>,
+}
+
+/// Robot → driver station console, an error or a warning with where it came from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorMessage {
+    /// Seconds since the robot program started.
+    pub timestamp: f32,
+    pub sequence: u16,
+    pub code: i32,
+    /// Whether it's shown as an error rather than a warning.
+    pub is_error: bool,
+    pub details: String,
+    pub location: String,
+    pub call_stack: String,
+}
+
+const fn encode_control(control: ControlWord) -> u8 {
+    let mut byte = match control.mode {
+        RobotMode::Disabled | RobotMode::Teleop => MODE_TELEOP,
+        RobotMode::Test => MODE_TEST,
+        RobotMode::Autonomous => MODE_AUTONOMOUS,
+    };
+    if control.e_stopped {
+        byte |= CONTROL_E_STOP;
+    }
+    if control.fms_attached {
+        byte |= CONTROL_FMS_ATTACHED;
+    }
+    if control.enabled {
+        byte |= CONTROL_ENABLED;
+    }
+    byte
+}
+
+const fn decode_control(byte: u8) -> Result<ControlWord, ProtocolError> {
+    let mode = match byte & CONTROL_MODE_MASK {
+        MODE_TELEOP => RobotMode::Teleop,
+        MODE_TEST => RobotMode::Test,
+        MODE_AUTONOMOUS => RobotMode::Autonomous,
+        bits => return Err(ProtocolError::InvalidMode(bits)),
+    };
+    Ok(ControlWord {
+        mode,
+        enabled: byte & CONTROL_ENABLED != 0,
+        e_stopped: byte & CONTROL_E_STOP != 0,
+        fms_attached: byte & CONTROL_FMS_ATTACHED != 0,
+        ds_attached: true,
+    })
+}
+
+fn push_tag(buf: &mut Vec<u8>, id: u8, data: &[u8]) {
+    //tags longer than a size byte can describe are cut short rather than corrupting the packet
+    let len = data.len().min(usize::from(u8::MAX) - 1);
+    buf.push(u8::try_from(len + 1).unwrap_or(u8::MAX));
+    buf.push(id);
+    buf.extend_from_slice(&data[..len]);
+}
+
+fn count_byte(len: usize) -> u8 {
+    u8::try_from(len).unwrap_or(u8::MAX)
+}
+
+impl JoystickData {
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(count_byte(self.axes.len()));
+        data.extend(self.axes.iter().map(|axis| axis.to_be_bytes()[0]));
+
+        //buttons are packed big endian with button 1 in the lowest bit of the last byte
+        data.push(count_byte(self.buttons.len()));
+        let button_bytes = self.buttons.len().div_ceil(8);
+        let mut packed = vec![0u8; button_bytes];
+        for (i, pressed) in self.buttons.iter().enumerate() {
+            if *pressed {
+                packed[button_bytes - 1 - i / 8] |= 1 << (i % 8);
+            }
+        }
+        data.extend(packed);
+
+        data.push(count_byte(self.povs.len()));
+        for pov in &self.povs {
+            data.extend(pov.to_be_bytes());
+        }
+        data
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let truncated = ProtocolError::TruncatedTag { id: TAG_JOYSTICK };
+        let mut reader = Reader { data, truncated };
+
+        let axis_count = reader.byte()?;
+        let axes = reader
+            .take(usize::from(axis_count))?
+            .iter()
+            .map(|byte| i8::from_be_bytes([*byte]))
+            .collect();
+
+        let button_count = usize::from(reader.byte()?);
+        let packed = reader.take(button_count.div_ceil(8))?;
+        let buttons = (0..button_count)
+            .map(|i| packed[packed.len() - 1 - i / 8] & (1 << (i % 8)) != 0)
+            .collect();
+
+        let pov_count = reader.byte()?;
+        let povs = reader
+            .take(usize::from(pov_count) * 2)?
+            .chunks_exact(2)
+            .map(|pov| i16::from_be_bytes([pov[0], pov[1]]))
+            .collect();
+
+        Ok(Self {
+            axes,
+            buttons,
+            povs,
+        })
+    }
+}
+
+impl JoystickOutputs {
+    fn encode(self) -> Vec<u8> {
+        let mut data = self.outputs.to_be_bytes().to_vec();
+        data.extend(self.left_rumble.to_be_bytes());
+        data.extend(self.right_rumble.to_be_bytes());
+        data
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = Reader {
+            data,
+            truncated: ProtocolError::TruncatedTag {
+                id: TAG_JOYSTICK_OUTPUTS,
+            },
+        };
+        let outputs = reader.take(4)?;
+        let left = reader.take(2)?;
+        let right = reader.take(2)?;
+        Ok(Self {
+            outputs: u32::from_be_bytes([outputs[0], outputs[1], outputs[2], outputs[3]]),
+            left_rumble: u16::from_be_bytes([left[0], left[1]]),
+            right_rumble: u16::from_be_bytes([right[0], right[1]]),
+        })
+    }
+}
+
+impl DsDate {
+    fn encode(&self) -> Vec<u8> {
+        let mut data = self.microseconds.to_be_bytes().to_vec();
+        data.extend([
+            self.second,
+            self.minute,
+            self.hour,
+            self.day,
+            self.month,
+            self.year,
+        ]);
+        data
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let mut reader = Reader {
+            data,
+            truncated: ProtocolError::TruncatedTag { id: TAG_DATE },
+        };
+        let micros = reader.take(4)?;
+        Ok(Self {
+            microseconds: u32::from_be_bytes([micros[0], micros[1], micros[2], micros[3]]),
+            second: reader.byte()?,
+            minute: reader.byte()?,
+            hour: reader.byte()?,
+            day: reader.byte()?,
+            month: reader.byte()?,
+            year: reader.byte()?,
+        })
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    truncated: ProtocolError,
+}
+
+impl<'a> Reader<'a> {
+    const fn take(&mut self, len: usize) -> Result<&'a [u8], ProtocolError> {
+        if self.data.len() < len {
+            return Err(self.truncated);
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, ProtocolError> {
+        Ok(self.take(1)?[0])
+    }
+}
+
+/// Splits the tags following a header into `(id, data)` pairs.
+fn read_tags(mut data: &[u8]) -> Result<Vec<(u8, &[u8])>, ProtocolError> {
+    let mut tags = Vec::new();
+    while let Some((&size, rest)) = data.split_first() {
+        let size = usize::from(size);
+        if size == 0 {
+            //zero sized tags carry no id, skip them like the roboRIO does
+            data = rest;
+            continue;
+        }
+        if rest.len() < size {
+            return Err(ProtocolError::TruncatedTag {
+                id: rest.first().copied().unwrap_or_default(),
+            });
+        }
+        tags.push((rest[0], &rest[1..size]));
+        data = &rest[size..];
+    }
+    Ok(tags)
+}
+
+impl ControlPacket {
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut request = 0;
+        if self.reboot_requested {
+            request |= REQUEST_REBOOT;
+        }
+        if self.restart_code_requested {
+            request |= REQUEST_RESTART_CODE;
+        }
+
+        let mut buf = Vec::with_capacity(CONTROL_HEADER_LEN);
+        buf.extend(self.sequence.to_be_bytes());
+        buf.push(COMM_VERSION);
+        buf.push(encode_control(self.control));
+        buf.push(request);
+        buf.push(self.station as u8);
+
+        if let Some(countdown) = self.countdown {
+            push_tag(&mut buf, TAG_COUNTDOWN, &countdown.to_be_bytes());
+        }
+        for joystick in &self.joysticks {
+            push_tag(&mut buf, TAG_JOYSTICK, &joystick.encode());
+        }
+        if let Some(date) = &self.date {
+            push_tag(&mut buf, TAG_DATE, &date.encode());
+        }
+        if let Some(timezone) = &self.timezone {
+            push_tag(&mut buf, TAG_TIMEZONE, timezone.as_bytes());
+        }
+        buf
+    }
+
+    /// Decodes a packet, unknown tags are ignored.
+    ///
+    /// # Errors
+    /// If the packet is malformed or from an unsupported protocol version.
+    pub fn decode(buf: &[u8]) -> Result<Self, ProtocolError> {
+        if buf.len() < CONTROL_HEADER_LEN {
+            return Err(ProtocolError::TooShort);
+        }
+        if buf[2] != COMM_VERSION {
+            return Err(ProtocolError::UnsupportedVersion(buf[2]));
+        }
+
+        let mut packet = Self {
+            sequence: u16::from_be_bytes([buf[0], buf[1]]),
+            control: decode_control(buf[3])?,
+            reboot_requested: buf[4] & REQUEST_REBOOT != 0,
+            restart_code_requested: buf[4] & REQUEST_RESTART_CODE != 0,
+            station: AllianceStation::try_from(buf[5])?,
+            ..Self::default()
+        };
+
+        for (id, data) in read_tags(&buf[CONTROL_HEADER_LEN..])? {
+            match id {
+                TAG_COUNTDOWN => {
+                    let bytes: [u8; 4] = data
+                        .try_into()
+                        .map_err(|_| ProtocolError::TruncatedTag { id })?;
+                    packet.countdown = Some(f32::from_be_bytes(bytes));
+                }
+                TAG_JOYSTICK => packet.joysticks.push(JoystickData::decode(data)?),
+                TAG_DATE => packet.date = Some(DsDate::decode(data)?),
+                TAG_TIMEZONE => {
+                    packet.timezone = Some(String::from_utf8_lossy(data).into_owned());
+                }
+                _ => {}
+            }
+        }
+        Ok(packet)
+    }
+}
+
+impl StatusPacket {
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut status = encode_control(self.control) & !CONTROL_FMS_ATTACHED;
+        if self.brownout {
+            status |= STATUS_BROWNOUT;
+        }
+        if self.code_initializing {
+            status |= STATUS_CODE_INITIALIZING;
+        }
+
+        let mut trace = TRACE_IS_ROBORIO;
+        if self.code_running {
+            trace |= TRACE_ROBOT_CODE;
+        }
+        trace |= match self.control.effective_mode() {
+            RobotMode::Disabled => TRACE_DISABLED,
+            RobotMode::Teleop => TRACE_TELEOP,
+            RobotMode::Autonomous => TRACE_AUTONOMOUS,
+            RobotMode::Test => TRACE_TEST,
+        };
+
+        //the voltage is sent as whole volts and 256ths of a volt
+        let voltage = self.battery_voltage.clamp(0.0, 255.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (whole, fraction) = (voltage.trunc() as u8, (voltage.fract() * 256.0) as u8);
+
+        let mut buf = Vec::with_capacity(STATUS_HEADER_LEN);
+        buf.extend(self.sequence.to_be_bytes());
+        buf.push(COMM_VERSION);
+        buf.push(status);
+        buf.push(trace);
+        buf.push(whole);
+        buf.push(fraction);
+        buf.push(if self.request_date {
+            STATUS_REQUEST_DATE
+        } else {
+            0
+        });
+
+        for outputs in &self.joystick_outputs {
+            push_tag(&mut buf, TAG_JOYSTICK_OUTPUTS, &outputs.encode());
+        }
+        buf
+    }
+
+    /// Decodes a packet, unknown tags are ignored.
+    ///
+    /// # Errors
+    /// If the packet is malformed or from an unsupported protocol version.
+    pub fn decode(buf: &[u8]) -> Result<Self, ProtocolError> {
+        if buf.len() < STATUS_HEADER_LEN {
+            return Err(ProtocolError::TooShort);
+        }
+        if buf[2] != COMM_VERSION {
+            return Err(ProtocolError::UnsupportedVersion(buf[2]));
+        }
+        let mut packet = Self {
+            sequence: u16::from_be_bytes([buf[0], buf[1]]),
+            control: decode_control(buf[3] & !STATUS_MASK)?,
+            code_running: buf[4] & TRACE_ROBOT_CODE != 0,
+            code_initializing: buf[3] & STATUS_CODE_INITIALIZING != 0,
+            brownout: buf[3] & STATUS_BROWNOUT != 0,
+            battery_voltage: f64::from(buf[5]) + f64::from(buf[6]) / 256.0,
+            request_date: buf[7] & STATUS_REQUEST_DATE != 0,
+            joystick_outputs: Vec::new(),
+        };
+
+        for (id, data) in read_tags(&buf[STATUS_HEADER_LEN..])? {
+            if id == TAG_JOYSTICK_OUTPUTS {
+                packet.joystick_outputs.push(JoystickOutputs::decode(data)?);
+            }
+        }
+        Ok(packet)
+    }
+}
+
+//strings in tcp tags are prefixed with a two byte length, long ones are cut short
+fn push_string(buf: &mut Vec<u8>, string: &str) {
+    let mut len = string.len().min(MAX_MESSAGE_STRING_LEN);
+    while !string.is_char_boundary(len) {
+        len -= 1;
+    }
+    buf.extend(u16::try_from(len).unwrap_or(u16::MAX).to_be_bytes());
+    buf.extend_from_slice(&string.as_bytes()[..len]);
+}
+
+impl Reader<'_> {
+    fn string(&mut self) -> Result<String, ProtocolError> {
+        let len = self.take(2)?;
+        let len = u16::from_be_bytes([len[0], len[1]]);
+        Ok(String::from_utf8_lossy(self.take(usize::from(len))?).into_owned())
+    }
+}
+
+impl ErrorMessage {
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(self.timestamp.to_be_bytes());
+        data.extend(self.sequence.to_be_bytes());
+        //the number of occurrences, the robot sends each one on its own
+        data.extend(1u16.to_be_bytes());
+        data.extend(self.code.to_be_bytes());
+        data.push(u8::from(self.is_error));
+        push_string(&mut data, &self.details);
+        push_string(&mut data, &self.location);
+        push_string(&mut data, &self.call_stack);
+
+        let mut buf = Vec::with_capacity(data.len() + 3);
+        buf.extend(
+            u16::try_from(data.len() + 1)
+                .unwrap_or(u16::MAX)
+                .to_be_bytes(),
+        );
+        buf.push(TCP_TAG_ERROR_MESSAGE);
+        buf.extend(data);
+        buf
+    }
+
+    /// Decodes a message including its two byte size.
+    ///
+    /// # Errors
+    /// If the message is malformed or isn't an error message.
+    pub fn decode(buf: &[u8]) -> Result<Self, ProtocolError> {
+        let truncated = ProtocolError::TruncatedTag {
+            id: TCP_TAG_ERROR_MESSAGE,
+        };
+        let mut reader = Reader {
+            data: buf,
+            truncated,
+        };
+        let size = reader.take(2)?;
+        let size = usize::from(u16::from_be_bytes([size[0], size[1]]));
+        let mut reader = Reader {
+            data: reader.take(size)?,
+            truncated,
+        };
+        let id = reader.byte()?;
+        if id != TCP_TAG_ERROR_MESSAGE {
+            return Err(ProtocolError::UnexpectedTag(id));
+        }
+
+        let timestamp = reader.take(4)?;
+        let timestamp =
+            f32::from_be_bytes([timestamp[0], timestamp[1], timestamp[2], timestamp[3]]);
+        let sequence = reader.take(2)?;
+        let sequence = u16::from_be_bytes([sequence[0], sequence[1]]);
+        reader.take(2)?;
+        let code = reader.take(4)?;
+        Ok(Self {
+            timestamp,
+            sequence,
+            code: i32::from_be_bytes([code[0], code[1], code[2], code[3]]),
+            is_error: reader.byte()? != 0,
+            details: reader.string()?,
+            location: reader.string()?,
+            call_stack: reader.string()?,
+        })
+    }
+}
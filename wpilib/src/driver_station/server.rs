@@ -0,0 +1,398 @@
+use std::{
+    fmt::Debug,
+    io::{ErrorKind, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use super::{
+    protocol::{ControlPacket, ErrorMessage, JoystickData, StatusPacket},
+    state::{DriverStation, DriverStationData},
+};
+use crate::robots::{
+    runtime::{self, ErrorReport},
+    ControlWord, ModeSource,
+};
+
+//how often the server threads wake up to check if the server was stopped
+const RECEIVE_TIMEOUT: Duration = Duration::from_millis(50);
+//error reports held while the driver station's console isn't connected
+const MAX_PENDING_ERRORS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriverStationConfig {
+    pub bind_address: IpAddr,
+    /// Port the robot receives control packets on.
+    pub robot_port: u16,
+    /// Port on the driver station's address that status packets are sent to.
+    pub ds_port: u16,
+    /// Port the driver station's console connects to, error reports are sent over it.
+    pub tcp_port: u16,
+    /// How long without a control packet before the driver station counts as disconnected.
+    pub timeout: Duration,
+}
+
+impl Default for DriverStationConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            robot_port: 1110,
+            ds_port: 1150,
+            tcp_port: 1740,
+            timeout: Duration::from_millis(500),
+        }
+    }
+}
+
+/// What the robot reports back to the driver station in every status packet.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RobotStatus {
+    pub battery_voltage: f64,
+    pub code_running: bool,
+    pub brownout: bool,
+}
+
+#[derive(Debug, Default)]
+struct DsState {
+    last_packet: Option<ControlPacket>,
+    last_received: Option<Instant>,
+    ds_address: Option<SocketAddr>,
+    status: RobotStatus,
+    packets_received: u64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<DsState>,
+    running: AtomicBool,
+    timeout: Duration,
+}
+
+/// Shared access to the state received by a [`DriverStationServer`].
+#[derive(Debug, Clone)]
+pub struct DriverStationHandle {
+    shared: Arc<Shared>,
+}
+
+impl DriverStationHandle {
+    /// Whether a control packet arrived within the configured timeout.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.shared
+            .state
+            .lock()
+            .last_received
+            .is_some_and(|received| received.elapsed() <= self.shared.timeout)
+    }
+
+    /// The control state from the driver station, disabled while disconnected.
+    #[must_use]
+    pub fn get_control_word(&self) -> ControlWord {
+        if !self.is_connected() {
+            return ControlWord::disabled();
+        }
+        self.shared
+            .state
+            .lock()
+            .last_packet
+            .as_ref()
+            .map_or_else(ControlWord::disabled, |packet| packet.control)
+    }
+
+    #[must_use]
+    pub fn get_last_packet(&self) -> Option<ControlPacket> {
+        self.shared.state.lock().last_packet.clone()
+    }
+
+    #[must_use]
+    pub fn get_joystick(&self, slot: usize) -> Option<JoystickData> {
+        self.shared
+            .state
+            .lock()
+            .last_packet
+            .as_ref()
+            .and_then(|packet| packet.joysticks.get(slot).cloned())
+    }
+
+    #[must_use]
+    pub fn get_ds_address(&self) -> Option<SocketAddr> {
+        self.shared.state.lock().ds_address
+    }
+
+    #[must_use]
+    pub fn get_packets_received(&self) -> u64 {
+        self.shared.state.lock().packets_received
+    }
+
+    #[must_use]
+    pub fn get_status(&self) -> RobotStatus {
+        self.shared.state.lock().status
+    }
+
+    pub fn set_status(&self, status: RobotStatus) {
+        self.shared.state.lock().status = status;
+    }
+
+    pub fn set_battery_voltage(&self, voltage: f64) {
+        self.shared.state.lock().status.battery_voltage = voltage;
+    }
+
+    pub fn set_code_running(&self, running: bool) {
+        self.shared.state.lock().status.code_running = running;
+    }
+
+    pub fn set_brownout(&self, brownout: bool) {
+        self.shared.state.lock().status.brownout = brownout;
+    }
+
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.shared.running.load(Ordering::Acquire)
+    }
+
+    /// Stops the server, the robot loop exits on its next cycle if it's using it as its
+    /// [`ModeSource`].
+    pub fn stop(&self) {
+        self.shared.running.store(false, Ordering::Release);
+    }
+}
+
+/// Receives control packets from the driver station on a background thread and answers
+/// each with a status packet, another thread sends reported errors to its console.
+pub struct DriverStationServer {
+    handle: DriverStationHandle,
+    local_addr: SocketAddr,
+    console_addr: SocketAddr,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl DriverStationServer {
+    /// Binds the robot and console ports and starts the server threads.
+    ///
+    /// # Errors
+    /// If a socket can't be bound or configured.
+    pub fn start(config: DriverStationConfig) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(SocketAddr::new(config.bind_address, config.robot_port))?;
+        socket.set_read_timeout(Some(RECEIVE_TIMEOUT))?;
+        let local_addr = socket.local_addr()?;
+        let listener = TcpListener::bind(SocketAddr::new(config.bind_address, config.tcp_port))?;
+        listener.set_nonblocking(true)?;
+        let console_addr = listener.local_addr()?;
+        let errors = runtime::subscribe_errors(MAX_PENDING_ERRORS);
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(DsState::default()),
+            running: AtomicBool::new(true),
+            timeout: config.timeout,
+        });
+        let thread_shared = shared.clone();
+        let receive_thread = std::thread::Builder::new()
+            .name(String::from("driver station"))
+            .spawn(move || receive_loop(&socket, &thread_shared, config.ds_port))?;
+        let thread_shared = shared.clone();
+        let console_thread = std::thread::Builder::new()
+            .name(String::from("driver station console"))
+            .spawn(move || console_loop(&listener, &thread_shared, &errors));
+
+        let mut server = Self {
+            handle: DriverStationHandle { shared },
+            local_addr,
+            console_addr,
+            threads: vec![receive_thread],
+        };
+        //dropping the server stops the receive thread if the console one can't start
+        server.threads.push(console_thread?);
+        tracing::info!(
+            "Driver station server listening on {}, console on {}",
+            local_addr,
+            console_addr
+        );
+        Ok(server)
+    }
+
+    #[must_use]
+    pub fn handle(&self) -> DriverStationHandle {
+        self.handle.clone()
+    }
+
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The address the driver station's console connects to.
+    #[must_use]
+    pub const fn console_addr(&self) -> SocketAddr {
+        self.console_addr
+    }
+}
+
+//sends error reports to the driver station's console while one is connected, the
+//driver station's own messages on the connection aren't used so they're never read
+fn console_loop(listener: &TcpListener, shared: &Shared, errors: &Receiver<ErrorReport>) {
+    let mut console: Option<TcpStream> = None;
+    let mut sequence: u16 = 0;
+    while shared.running.load(Ordering::Acquire) {
+        let Some(stream) = console.as_mut() else {
+            match listener.accept() {
+                Ok((stream, address)) => {
+                    tracing::info!("Driver station console connected from {}", address);
+                    if let Err(err) = stream.set_nonblocking(false) {
+                        tracing::warn!("Failed to configure the console connection: {}", err);
+                        continue;
+                    }
+                    console = Some(stream);
+                }
+                Err(err) => {
+                    if !matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) {
+                        tracing::warn!("Driver station console accept failed: {}", err);
+                    }
+                    std::thread::sleep(RECEIVE_TIMEOUT);
+                }
+            }
+            continue;
+        };
+        let Ok(report) = errors.recv_timeout(RECEIVE_TIMEOUT) else {
+            continue;
+        };
+        let message = error_message(report, sequence);
+        sequence = sequence.wrapping_add(1);
+        if let Err(err) = stream.write_all(&message.encode()) {
+            tracing::info!("Driver station console disconnected: {}", err);
+            console = None;
+        }
+    }
+}
+
+fn error_message(report: ErrorReport, sequence: u16) -> ErrorMessage {
+    #[allow(clippy::cast_possible_truncation)]
+    let timestamp = crate::timer::get_timestamp().value() as f32;
+    ErrorMessage {
+        timestamp,
+        sequence,
+        code: 1,
+        is_error: true,
+        details: report.message,
+        location: report.location,
+        call_stack: report.backtrace,
+    }
+}
+
+fn receive_loop(socket: &UdpSocket, shared: &Shared, ds_port: u16) {
+    let mut buf = [0u8; 1500];
+    while shared.running.load(Ordering::Acquire) {
+        let (len, source) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                continue
+            }
+            Err(err) => {
+                tracing::warn!("Driver station receive failed: {}", err);
+                continue;
+            }
+        };
+        let packet = match ControlPacket::decode(&buf[..len]) {
+            Ok(packet) => packet,
+            Err(err) => {
+                tracing::debug!("Ignoring bad control packet from {}: {}", source, err);
+                continue;
+            }
+        };
+
+        //taken before the server's state, the robot loop holds the driver station while
+        //it reads that state
+        let joystick_outputs = DriverStation::get_all_joystick_outputs();
+        let status = {
+            let mut state = shared.state.lock();
+            let status = StatusPacket {
+                sequence: packet.sequence,
+                control: packet.control,
+                code_running: state.status.code_running,
+                code_initializing: !state.status.code_running,
+                brownout: state.status.brownout,
+                battery_voltage: state.status.battery_voltage,
+                //ask until the driver station sends the time
+                request_date: packet.date.is_none()
+                    && state
+                        .last_packet
+                        .as_ref()
+                        .and_then(|last| last.date)
+                        .is_none(),
+                joystick_outputs,
+            };
+            if state.ds_address.map(|address| address.ip()) != Some(source.ip()) {
+                tracing::info!("Driver station connected from {}", source.ip());
+            }
+            state.ds_address = Some(source);
+            state.last_received = Some(Instant::now());
+            state.packets_received += 1;
+            //date and timezone are only sent when asked for, keep the last ones around
+            let mut packet = packet;
+            if let Some(last) = state.last_packet.take() {
+                packet.date = packet.date.or(last.date);
+                packet.timezone = packet.timezone.or(last.timezone);
+            }
+            state.last_packet = Some(packet);
+            status
+        };
+
+        if let Err(err) = socket.send_to(&status.encode(), SocketAddr::new(source.ip(), ds_port)) {
+            tracing::warn!("Failed to send status to the driver station: {}", err);
+        }
+    }
+}
+
+impl ModeSource for DriverStationServer {
+    fn poll(&mut self) -> Option<ControlWord> {
+        if self.handle.is_running() {
+            Some(self.handle.get_control_word())
+        } else {
+            None
+        }
+    }
+
+    //match info and the game specific message come over the driver station's TCP
+    //connection, which isn't implemented, so they are left as they are
+    fn update_driver_station(&mut self, data: &mut DriverStationData) {
+        let Some(packet) = self.handle.get_last_packet() else {
+            return;
+        };
+        data.station = packet.station;
+        data.match_time = packet
+            .countdown
+            .filter(|_| self.handle.is_connected())
+            .map(f64::from);
+        data.joysticks = packet.joysticks;
+    }
+
+    fn get_name(&self) -> String {
+        String::from("driver station")
+    }
+}
+
+impl Drop for DriverStationServer {
+    fn drop(&mut self) {
+        self.handle.stop();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Debug for DriverStationServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DriverStationServer")
+            .field("local_addr", &self.local_addr)
+            .field("console_addr", &self.console_addr)
+            .field("connected", &self.handle.is_connected())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+
+use super::protocol::{AllianceStation, JoystickData, JoystickOutputs};
+use crate::robots::{ControlWord, RobotMode};
+
+//events older than this are dropped, subscribers that fall further behind miss them
+const MAX_EVENTS: usize = 256;
+
+/// How many joysticks the driver station has slots for, numbered from 0.
+pub const JOYSTICK_SLOTS: usize = 6;
+
+static DRIVER_STATION: Mutex<DsInner> = Mutex::new(DsInner::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alliance {
+    Red,
+    Blue,
+}
+
+impl AllianceStation {
+    #[must_use]
+    pub const fn get_alliance(&self) -> Alliance {
+        if self.is_red() {
+            Alliance::Red
+        } else {
+            Alliance::Blue
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MatchType {
+    #[default]
+    None,
+    Practice,
+    Qualification,
+    Elimination,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MatchInfo {
+    pub event_name: String,
+    pub match_type: MatchType,
+    pub match_number: u16,
+    pub replay_number: u8,
+}
+
+impl MatchInfo {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            event_name: String::new(),
+            match_type: MatchType::None,
+            match_number: 0,
+            replay_number: 0,
+        }
+    }
+}
+
+/// Everything the driver station tells the robot, as last reported by the active
+/// [`ModeSource`](crate::robots::ModeSource).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriverStationData {
+    pub control_word: ControlWord,
+    pub station: AllianceStation,
+    /// Seconds left in the current match period, `None` when the driver station isn't counting down.
+    pub match_time: Option<f64>,
+    pub match_info: MatchInfo,
+    pub game_specific_message: String,
+    pub joysticks: Vec<JoystickData>,
+}
+
+impl DriverStationData {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            control_word: ControlWord::disabled(),
+            station: AllianceStation::Red1,
+            match_time: None,
+            match_info: MatchInfo::new(),
+            game_specific_message: String::new(),
+            joysticks: Vec::new(),
+        }
+    }
+}
+
+/// A change in the driver station state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DsEvent {
+    Connected,
+    Disconnected,
+    Enabled,
+    Disabled,
+    EStopped,
+    FmsAttached,
+    FmsDetached,
+    /// The mode the robot loop runs changed, see [`ControlWord::effective_mode`].
+    ModeChanged(RobotMode),
+    StationChanged(AllianceStation),
+    MatchInfoChanged(MatchInfo),
+    GameSpecificMessageChanged(String),
+}
+
+impl DsEvent {
+    fn diff(old: &DriverStationData, new: &DriverStationData, events: &mut Vec<Self>) {
+        let (old_control, new_control) = (old.control_word, new.control_word);
+        if old_control.ds_attached != new_control.ds_attached {
+            events.push(if new_control.ds_attached {
+                Self::Connected
+            } else {
+                Self::Disconnected
+            });
+        }
+        if old_control.fms_attached != new_control.fms_attached {
+            events.push(if new_control.fms_attached {
+                Self::FmsAttached
+            } else {
+                Self::FmsDetached
+            });
+        }
+        if new_control.e_stopped && !old_control.e_stopped {
+            events.push(Self::EStopped);
+        }
+        if old_control.enabled != new_control.enabled {
+            events.push(if new_control.enabled {
+                Self::Enabled
+            } else {
+                Self::Disabled
+            });
+        }
+        if old_control.effective_mode() != new_control.effective_mode() {
+            events.push(Self::ModeChanged(new_control.effective_mode()));
+        }
+        if old.station != new.station {
+            events.push(Self::StationChanged(new.station));
+        }
+        if old.match_info != new.match_info {
+            events.push(Self::MatchInfoChanged(new.match_info.clone()));
+        }
+        if old.game_specific_message != new.game_specific_message {
+            events.push(Self::GameSpecificMessageChanged(
+                new.game_specific_message.clone(),
+            ));
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DsInner {
+    data: DriverStationData,
+    //what the robot sends back, kept apart from `data` as mode sources replace that wholesale
+    joystick_outputs: Vec<JoystickOutputs>,
+    events: VecDeque<(u64, DsEvent)>,
+    next_event: u64,
+}
+
+impl DsInner {
+    const fn new() -> Self {
+        Self {
+            data: DriverStationData::new(),
+            joystick_outputs: Vec::new(),
+            events: VecDeque::new(),
+            next_event: 0,
+        }
+    }
+}
+
+/// Reads the events published by [`DriverStation`] since it was created or last polled.
+///
+/// Subscribers are plain cursors into a shared history, so they are cheap to clone and
+/// don't need to be unsubscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DsSubscriber {
+    next_event: u64,
+}
+
+impl DsSubscriber {
+    /// Returns every event since the last poll, oldest first.
+    pub fn poll(&mut self) -> Vec<DsEvent> {
+        let ds = DRIVER_STATION.lock();
+        let events = ds
+            .events
+            .iter()
+            .filter(|(id, _)| *id >= self.next_event)
+            .map(|(_, event)| event.clone())
+            .collect();
+        self.next_event = ds.next_event;
+        events
+    }
+}
+
+/// Global access to the driver station state.
+///
+/// The robot loop feeds it from its [`ModeSource`](crate::robots::ModeSource) every cycle,
+/// tests and simulations without a robot loop can feed it with [`DriverStation::update`].
+#[derive(Debug, Clone, Copy)]
+pub struct DriverStation;
+
+impl DriverStation {
+    /// Changes the state through `update` and publishes the resulting events.
+    pub fn update(update: impl FnOnce(&mut DriverStationData)) {
+        let mut ds = DRIVER_STATION.lock();
+        let old = ds.data.clone();
+        update(&mut ds.data);
+
+        let mut events = Vec::new();
+        DsEvent::diff(&old, &ds.data, &mut events);
+        for event in events {
+            let id = ds.next_event;
+            ds.next_event += 1;
+            if ds.events.len() >= MAX_EVENTS {
+                ds.events.pop_front();
+            }
+            ds.events.push_back((id, event));
+        }
+    }
+
+    /// Replaces the whole state, see [`DriverStation::update`].
+    pub fn set_data(data: DriverStationData) {
+        Self::update(|current| *current = data);
+    }
+
+    /// A subscriber that sees events published from now on.
+    #[must_use]
+    pub fn subscribe() -> DsSubscriber {
+        DsSubscriber {
+            next_event: DRIVER_STATION.lock().next_event,
+        }
+    }
+
+    #[must_use]
+    pub fn get_data() -> DriverStationData {
+        DRIVER_STATION.lock().data.clone()
+    }
+
+    #[must_use]
+    pub fn get_control_word() -> ControlWord {
+        DRIVER_STATION.lock().data.control_word
+    }
+
+    #[must_use]
+    pub fn is_ds_attached() -> bool {
+        Self::get_control_word().ds_attached
+    }
+
+    #[must_use]
+    pub fn is_fms_attached() -> bool {
+        Self::get_control_word().fms_attached
+    }
+
+    #[must_use]
+    pub fn is_enabled() -> bool {
+        let control = Self::get_control_word();
+        control.enabled && !control.e_stopped
+    }
+
+    #[must_use]
+    pub fn is_e_stopped() -> bool {
+        Self::get_control_word().e_stopped
+    }
+
+    #[must_use]
+    pub fn get_mode() -> RobotMode {
+        Self::get_control_word().effective_mode()
+    }
+
+    /// The robot's alliance, `None` until a driver station is attached.
+    #[must_use]
+    pub fn get_alliance() -> Option<Alliance> {
+        Self::get_station().map(|station| station.get_alliance())
+    }
+
+    /// The robot's station, `None` until a driver station is attached.
+    #[must_use]
+    pub fn get_station() -> Option<AllianceStation> {
+        let ds = DRIVER_STATION.lock();
+        ds.data.control_word.ds_attached.then_some(ds.data.station)
+    }
+
+    /// The station number within the alliance from 1 to 3, `None` until a driver station
+    /// is attached.
+    #[must_use]
+    pub fn get_location() -> Option<u8> {
+        Self::get_station().map(|station| station.get_number())
+    }
+
+    #[must_use]
+    pub fn get_match_time() -> Option<f64> {
+        DRIVER_STATION.lock().data.match_time
+    }
+
+    #[must_use]
+    pub fn get_match_info() -> MatchInfo {
+        DRIVER_STATION.lock().data.match_info.clone()
+    }
+
+    #[must_use]
+    pub fn get_event_name() -> String {
+        DRIVER_STATION.lock().data.match_info.event_name.clone()
+    }
+
+    #[must_use]
+    pub fn get_match_type() -> MatchType {
+        DRIVER_STATION.lock().data.match_info.match_type
+    }
+
+    #[must_use]
+    pub fn get_match_number() -> u16 {
+        DRIVER_STATION.lock().data.match_info.match_number
+    }
+
+    #[must_use]
+    pub fn get_replay_number() -> u8 {
+        DRIVER_STATION.lock().data.match_info.replay_number
+    }
+
+    #[must_use]
+    pub fn get_game_specific_message() -> String {
+        DRIVER_STATION.lock().data.game_specific_message.clone()
+    }
+
+    #[must_use]
+    pub fn get_joystick(slot: usize) -> Option<JoystickData> {
+        DRIVER_STATION.lock().data.joysticks.get(slot).cloned()
+    }
+
+    /// Sets what's sent back to the joystick in `slot`, a
+    /// [`DriverStationServer`](super::DriverStationServer) sends it with every status packet.
+    ///
+    /// Slots past [`JOYSTICK_SLOTS`] don't exist on the driver station and are ignored.
+    pub fn set_joystick_outputs(slot: usize, outputs: JoystickOutputs) {
+        if slot >= JOYSTICK_SLOTS {
+            return;
+        }
+        let all_outputs = &mut DRIVER_STATION.lock().joystick_outputs;
+        if all_outputs.len() <= slot {
+            all_outputs.resize(slot + 1, JoystickOutputs::default());
+        }
+        all_outputs[slot] = outputs;
+    }
+
+    #[must_use]
+    pub fn get_joystick_outputs(slot: usize) -> JoystickOutputs {
+        DRIVER_STATION
+            .lock()
+            .joystick_outputs
+            .get(slot)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_all_joystick_outputs() -> Vec<JoystickOutputs> {
+        DRIVER_STATION.lock().joystick_outputs.clone()
+    }
+}
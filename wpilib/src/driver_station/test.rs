@@ -0,0 +1,534 @@
+use std::{
+    io::Read,
+    net::{Ipv4Addr, TcpStream, UdpSocket},
+    time::Duration,
+};
+
+use super::{
+    Alliance, AllianceStation, ControlPacket, DriverStation, DriverStationConfig,
+    DriverStationData, DriverStationServer, DsDate, DsEvent, ErrorMessage, FakeDriverStation,
+    JoystickData, JoystickOutputs, MatchInfo, MatchType, ProtocolError, StatusPacket,
+    JOYSTICK_SLOTS,
+};
+use crate::robots::{
+    runtime::{self, ErrorReport},
+    ControlWord, ModeSource, RobotCore, RobotCoreImpl, RobotMode, SimModeSource, UserRobot,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+fn joystick() -> JoystickData {
+    let mut buttons = vec![false; 10];
+    buttons[0] = true;
+    buttons[8] = true;
+    JoystickData {
+        axes: vec![0, 127, -128],
+        buttons,
+        povs: vec![-1, 90],
+    }
+}
+
+#[test]
+fn control_packet_bytes() {
+    let mut control = ControlWord::enabled(RobotMode::Autonomous);
+    control.fms_attached = true;
+    let packet = ControlPacket {
+        sequence: 0x0102,
+        control,
+        station: AllianceStation::Blue2,
+        ..ControlPacket::default()
+    };
+    assert_eq!(packet.encode(), vec![0x01, 0x02, 0x01, 0x0e, 0x00, 0x04]);
+
+    let mut e_stopped = ControlWord::enabled(RobotMode::Test);
+    e_stopped.e_stopped = true;
+    let packet = ControlPacket {
+        control: e_stopped,
+        restart_code_requested: true,
+        ..ControlPacket::default()
+    };
+    assert_eq!(packet.encode()[3..], [0x85, 0x04, 0x00]);
+}
+
+#[test]
+fn joystick_tag_bytes() {
+    let packet = ControlPacket {
+        joysticks: vec![joystick()],
+        ..ControlPacket::default()
+    };
+    let encoded = packet.encode();
+    assert_eq!(
+        encoded[6..],
+        [
+            0x0d, 0x0c, //size, id
+            0x03, 0x00, 0x7f, 0x80, //axes
+            0x0a, 0x01, 0x01, //buttons 1 and 9
+            0x02, 0xff, 0xff, 0x00, 0x5a, //povs
+        ]
+    );
+}
+
+#[test]
+fn control_packet_round_trip() {
+    let packet = ControlPacket {
+        sequence: 42,
+        control: ControlWord::enabled(RobotMode::Teleop),
+        reboot_requested: true,
+        restart_code_requested: false,
+        station: AllianceStation::Red3,
+        countdown: Some(135.5),
+        joysticks: vec![JoystickData::default(), joystick()],
+        date: Some(DsDate {
+            microseconds: 123_456,
+            second: 1,
+            minute: 2,
+            hour: 3,
+            day: 4,
+            month: 5,
+            year: 124,
+        }),
+        timezone: Some(String::from("America/Chicago")),
+    };
+    assert_eq!(ControlPacket::decode(&packet.encode()), Ok(packet));
+}
+
+#[test]
+fn unknown_tags_are_skipped() {
+    let mut encoded = ControlPacket::default().encode();
+    encoded.extend([0x03, 0x7e, 0xaa, 0xbb]);
+    encoded.extend([0x05, 0x07, 0x42, 0x20, 0x00, 0x00]);
+    let packet = ControlPacket::decode(&encoded).expect("packet should decode");
+    assert_eq!(packet.countdown, Some(40.0));
+}
+
+#[test]
+fn malformed_packets() {
+    assert_eq!(
+        ControlPacket::decode(&[0, 1, 1]),
+        Err(ProtocolError::TooShort)
+    );
+    assert_eq!(
+        ControlPacket::decode(&[0, 1, 2, 0, 0, 0]),
+        Err(ProtocolError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        ControlPacket::decode(&[0, 1, 1, 0, 0, 6]),
+        Err(ProtocolError::InvalidStation(6))
+    );
+    assert_eq!(
+        ControlPacket::decode(&[0, 1, 1, 0, 0, 0, 0x05, 0x0c, 0x01]),
+        Err(ProtocolError::TruncatedTag { id: 0x0c })
+    );
+    //the joystick tag claims three axes but only carries one
+    assert_eq!(
+        ControlPacket::decode(&[0, 1, 1, 0, 0, 0, 0x03, 0x0c, 0x03, 0x00]),
+        Err(ProtocolError::TruncatedTag { id: 0x0c })
+    );
+}
+
+#[test]
+fn status_packet_bytes() {
+    let status = StatusPacket {
+        sequence: 7,
+        control: ControlWord::enabled(RobotMode::Teleop),
+        code_running: true,
+        code_initializing: false,
+        brownout: false,
+        battery_voltage: 12.5,
+        request_date: true,
+        joystick_outputs: Vec::new(),
+    };
+    assert_eq!(
+        status.encode(),
+        vec![0x00, 0x07, 0x01, 0x04, 0x32, 12, 128, 0x01]
+    );
+
+    let decoded = StatusPacket::decode(&status.encode()).expect("packet should decode");
+    assert_eq!(decoded.sequence, 7);
+    assert!(decoded.control.enabled);
+    assert!(decoded.code_running);
+    assert!(decoded.request_date);
+    assert!((decoded.battery_voltage - 12.5).abs() < f64::EPSILON);
+
+    //disabled robots report the disabled trace bit
+    let disabled = StatusPacket {
+        control: ControlWord::disabled(),
+        ..status.clone()
+    };
+    assert_eq!(disabled.encode()[3..5], [0x00, 0x31]);
+
+    //each joystick's outputs follow the header as a tag, in slot order
+    let rumbling = StatusPacket {
+        joystick_outputs: vec![
+            JoystickOutputs::default(),
+            JoystickOutputs {
+                outputs: 0b101,
+                left_rumble: u16::MAX,
+                right_rumble: 0x1234,
+            },
+        ],
+        ..status
+    };
+    let bytes = rumbling.encode();
+    assert_eq!(
+        bytes[8..],
+        [
+            0x09, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, //
+            0x09, 0x01, 0, 0, 0, 0b101, 0xff, 0xff, 0x12, 0x34
+        ]
+    );
+    let decoded = StatusPacket::decode(&bytes).expect("packet should decode");
+    assert_eq!(decoded.joystick_outputs, rumbling.joystick_outputs);
+    assert_eq!(
+        StatusPacket::decode(&[0, 1, 1, 0, 0, 0, 0, 0, 0x03, 0x01, 0x00, 0x00]),
+        Err(ProtocolError::TruncatedTag { id: 0x01 })
+    );
+}
+
+#[test]
+fn status_packet_flags_round_trip() {
+    let base = StatusPacket {
+        control: ControlWord::disabled(),
+        ..StatusPacket::default()
+    };
+    let flags: [fn(&mut StatusPacket); 6] = [
+        |status| status.code_running = true,
+        |status| status.code_initializing = true,
+        |status| status.brownout = true,
+        |status| status.request_date = true,
+        |status| status.control = ControlWord::enabled(RobotMode::Teleop),
+        |status| status.control.e_stopped = true,
+    ];
+    for set in flags {
+        let mut status = base.clone();
+        set(&mut status);
+        let decoded = StatusPacket::decode(&status.encode()).expect("packet should decode");
+        assert_eq!(decoded.code_running, status.code_running, "{status:?}");
+        assert_eq!(
+            decoded.code_initializing, status.code_initializing,
+            "{status:?}"
+        );
+        assert_eq!(decoded.brownout, status.brownout, "{status:?}");
+        assert_eq!(decoded.request_date, status.request_date, "{status:?}");
+        assert_eq!(
+            decoded.control.enabled, status.control.enabled,
+            "{status:?}"
+        );
+        assert_eq!(
+            decoded.control.e_stopped, status.control.e_stopped,
+            "{status:?}"
+        );
+        //the status byte never carries fms attached
+        assert!(!decoded.control.fms_attached, "{status:?}");
+    }
+}
+
+#[test]
+fn error_message_bytes() {
+    let message = ErrorMessage {
+        timestamp: 1.5,
+        sequence: 3,
+        code: 1,
+        is_error: true,
+        details: String::from("oops"),
+        location: String::from("a.rs"),
+        call_stack: String::new(),
+    };
+    let bytes = message.encode();
+    assert_eq!(
+        bytes,
+        vec![
+            0x00, 0x1c, 0x0b, 0x3f, 0xc0, 0x00, 0x00, 0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x01, 0x00, 0x04, b'o', b'o', b'p', b's', 0x00, 0x04, b'a', b'.', b'r', b's',
+            0x00, 0x00,
+        ]
+    );
+    assert_eq!(ErrorMessage::decode(&bytes), Ok(message));
+    assert_eq!(
+        ErrorMessage::decode(&bytes[..10]),
+        Err(ProtocolError::TruncatedTag { id: 0x0b })
+    );
+}
+
+#[test]
+fn errors_reach_the_console() {
+    let (_ds, server) = FakeDriverStation::with_server().expect("loopback should bind");
+    //reports are held until the console connects
+    runtime::report_error(ErrorReport {
+        message: String::from("console test error"),
+        location: String::from("test.rs:1:1"),
+        backtrace: String::from("backtrace"),
+    });
+    let mut console = TcpStream::connect(server.console_addr()).expect("console should connect");
+    console
+        .set_read_timeout(Some(TIMEOUT))
+        .expect("timeout should be set");
+
+    //other tests can report errors at the same time, skip past theirs
+    loop {
+        let mut size = [0; 2];
+        console
+            .read_exact(&mut size)
+            .expect("an error should arrive");
+        let mut message = size.to_vec();
+        message.resize(2 + usize::from(u16::from_be_bytes(size)), 0);
+        console
+            .read_exact(&mut message[2..])
+            .expect("the whole error should arrive");
+        let message = ErrorMessage::decode(&message).expect("error should decode");
+        if message.details == "console test error" {
+            assert!(message.is_error);
+            assert_eq!(message.location, "test.rs:1:1");
+            assert_eq!(message.call_stack, "backtrace");
+            break;
+        }
+    }
+}
+
+#[test]
+fn alliance_station() {
+    assert!(AllianceStation::Red2.is_red());
+    assert!(AllianceStation::Blue1.is_blue());
+    assert_eq!(AllianceStation::Blue3.get_number(), 3);
+    assert_eq!(AllianceStation::try_from(3), Ok(AllianceStation::Blue1));
+}
+
+#[test]
+fn loopback_exchange() {
+    let (mut ds, mut server) = FakeDriverStation::with_server().expect("loopback should bind");
+    let handle = server.handle();
+    assert!(!handle.is_connected());
+    assert_eq!(server.poll(), Some(ControlWord::disabled()));
+
+    handle.set_battery_voltage(12.25);
+    ds.set_control_word(ControlWord::enabled(RobotMode::Autonomous));
+    ds.set_station(AllianceStation::Blue1);
+    ds.set_joystick(1, joystick());
+
+    let status = ds.exchange(TIMEOUT).expect("robot should answer");
+    assert_eq!(status.sequence, 0);
+    assert!(status.control.enabled);
+    assert!(status.code_initializing);
+    assert!(!status.code_running);
+    assert!(status.request_date);
+    assert!((status.battery_voltage - 12.25).abs() < f64::EPSILON);
+
+    assert!(handle.is_connected());
+    assert_eq!(handle.get_packets_received(), 1);
+    assert_eq!(
+        server.poll().map(|control| control.effective_mode()),
+        Some(RobotMode::Autonomous)
+    );
+    assert_eq!(handle.get_joystick(1), Some(joystick()));
+    assert_eq!(handle.get_joystick(0), Some(JoystickData::default()));
+    assert_eq!(handle.get_joystick(2), None);
+    assert_eq!(
+        handle.get_last_packet().map(|packet| packet.station),
+        Some(AllianceStation::Blue1)
+    );
+
+    //once the date arrives the robot stops asking, and keeps it while later packets omit it
+    handle.set_code_running(true);
+    ds.packet_mut().date = Some(DsDate::default());
+    let status = ds.exchange(TIMEOUT).expect("robot should answer");
+    assert_eq!(status.sequence, 1);
+    assert!(!status.request_date);
+    assert!(status.code_running);
+    ds.packet_mut().date = None;
+    let status = ds.exchange(TIMEOUT).expect("robot should answer");
+    assert!(!status.request_date);
+    assert!(handle
+        .get_last_packet()
+        .is_some_and(|packet| packet.date.is_some()));
+
+    handle.stop();
+    assert_eq!(server.poll(), None);
+}
+
+#[test]
+fn joystick_outputs_reach_the_driver_station() {
+    let (mut ds, _server) = FakeDriverStation::with_server().expect("loopback should bind");
+    let outputs = JoystickOutputs {
+        outputs: 1,
+        left_rumble: 100,
+        right_rumble: 200,
+    };
+    DriverStation::set_joystick_outputs(2, outputs);
+    assert_eq!(DriverStation::get_joystick_outputs(2), outputs);
+    assert_eq!(
+        DriverStation::get_joystick_outputs(1),
+        JoystickOutputs::default()
+    );
+
+    let status = ds.exchange(TIMEOUT).expect("robot should answer");
+    assert_eq!(status.joystick_outputs.get(2), Some(&outputs));
+    DriverStation::set_joystick_outputs(2, JoystickOutputs::default());
+}
+
+#[test]
+fn joystick_outputs_out_of_range() {
+    let outputs = JoystickOutputs {
+        outputs: 1,
+        left_rumble: 1,
+        right_rumble: 1,
+    };
+    for slot in [JOYSTICK_SLOTS, 1000, usize::MAX] {
+        DriverStation::set_joystick_outputs(slot, outputs);
+        assert_eq!(
+            DriverStation::get_joystick_outputs(slot),
+            JoystickOutputs::default()
+        );
+    }
+    assert!(DriverStation::get_all_joystick_outputs().len() <= JOYSTICK_SLOTS);
+}
+
+#[test]
+fn disconnect_timeout() {
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("loopback should bind");
+    let server = DriverStationServer::start(DriverStationConfig {
+        bind_address: Ipv4Addr::LOCALHOST.into(),
+        robot_port: 0,
+        ds_port: socket.local_addr().expect("socket is bound").port(),
+        tcp_port: 0,
+        timeout: Duration::from_millis(50),
+    })
+    .expect("loopback should bind");
+    let mut ds = FakeDriverStation::new(socket, server.local_addr());
+    let handle = server.handle();
+
+    ds.set_control_word(ControlWord::enabled(RobotMode::Teleop));
+    ds.exchange(TIMEOUT).expect("robot should answer");
+    assert_eq!(
+        handle.get_control_word().effective_mode(),
+        RobotMode::Teleop
+    );
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!handle.is_connected());
+    assert_eq!(handle.get_control_word(), ControlWord::disabled());
+}
+
+//the driver station state is global and also fed by every robot loop test
+fn with_clean_driver_station(test: impl FnOnce()) {
+    let _guard = crate::robots::LOOP_TEST_LOCK.lock();
+    DriverStation::set_data(DriverStationData::new());
+    test();
+    DriverStation::set_data(DriverStationData::new());
+}
+
+#[test]
+fn driver_station_state() {
+    with_clean_driver_station(|| {
+        let mut subscriber = DriverStation::subscribe();
+        assert_eq!(DriverStation::get_alliance(), None);
+        assert_eq!(DriverStation::get_location(), None);
+
+        DriverStation::update(|data| {
+            data.control_word = ControlWord::enabled(RobotMode::Autonomous);
+            data.station = AllianceStation::Blue2;
+            data.match_time = Some(14.5);
+            data.match_info = MatchInfo {
+                event_name: String::from("TXHOU"),
+                match_type: MatchType::Qualification,
+                match_number: 12,
+                replay_number: 1,
+            };
+            data.game_specific_message = String::from("L");
+        });
+
+        assert_eq!(DriverStation::get_alliance(), Some(Alliance::Blue));
+        assert_eq!(DriverStation::get_location(), Some(2));
+        assert!(DriverStation::is_enabled());
+        assert_eq!(DriverStation::get_mode(), RobotMode::Autonomous);
+        assert_eq!(DriverStation::get_match_time(), Some(14.5));
+        assert_eq!(DriverStation::get_event_name(), "TXHOU");
+        assert_eq!(DriverStation::get_match_type(), MatchType::Qualification);
+        assert_eq!(DriverStation::get_match_number(), 12);
+        assert_eq!(DriverStation::get_replay_number(), 1);
+        assert_eq!(DriverStation::get_game_specific_message(), "L");
+
+        let events = subscriber.poll();
+        assert_eq!(events[0], DsEvent::Connected);
+        assert_eq!(events[1], DsEvent::Enabled);
+        assert_eq!(events[2], DsEvent::ModeChanged(RobotMode::Autonomous));
+        assert_eq!(events[3], DsEvent::StationChanged(AllianceStation::Blue2));
+        assert!(matches!(events[4], DsEvent::MatchInfoChanged(_)));
+        assert_eq!(
+            events[5],
+            DsEvent::GameSpecificMessageChanged(String::from("L"))
+        );
+        assert_eq!(events.len(), 6);
+
+        //only changes are published
+        DriverStation::update(|data| data.match_time = Some(13.0));
+        assert!(subscriber.poll().is_empty());
+
+        DriverStation::update(|data| data.control_word.e_stopped = true);
+        assert_eq!(
+            subscriber.poll(),
+            vec![DsEvent::EStopped, DsEvent::ModeChanged(RobotMode::Disabled)]
+        );
+        assert!(!DriverStation::is_enabled());
+    });
+}
+
+struct IdleRobot;
+
+impl UserRobot for IdleRobot {
+    fn robot_init(&mut self) {}
+    fn robot_periodic(&mut self) {}
+    fn robot_end(&mut self) {}
+}
+
+//runs the robot loop for a single cycle with `source`
+struct SingleCycle<T: ModeSource>(T, bool);
+
+impl<T: ModeSource> ModeSource for SingleCycle<T> {
+    fn poll(&mut self) -> Option<ControlWord> {
+        if self.1 {
+            return None;
+        }
+        self.1 = true;
+        self.0.poll()
+    }
+
+    fn update_driver_station(&mut self, data: &mut DriverStationData) {
+        self.0.update_driver_station(data);
+    }
+}
+
+#[test]
+fn mode_source_feeds_driver_station() {
+    with_clean_driver_station(|| {
+        let (source, handle) = SimModeSource::new();
+        handle.set_control_word(ControlWord::enabled(RobotMode::Teleop));
+        handle.set_station(AllianceStation::Red3);
+        handle.set_game_specific_message("R");
+        handle.set_joystick(0, joystick());
+
+        let mut robot =
+            RobotCoreImpl::new(Box::new(IdleRobot), Box::new(SingleCycle(source, false)));
+        robot.start();
+
+        assert_eq!(DriverStation::get_mode(), RobotMode::Teleop);
+        assert_eq!(DriverStation::get_station(), Some(AllianceStation::Red3));
+        assert_eq!(DriverStation::get_game_specific_message(), "R");
+        assert_eq!(DriverStation::get_joystick(0), Some(joystick()));
+    });
+}
+
+#[test]
+fn server_feeds_driver_station_data() {
+    let (mut ds, mut server) = FakeDriverStation::with_server().expect("loopback should bind");
+    ds.set_control_word(ControlWord::enabled(RobotMode::Teleop));
+    ds.set_station(AllianceStation::Blue3);
+    ds.set_joystick(0, joystick());
+    ds.packet_mut().countdown = Some(90.0);
+    ds.exchange(TIMEOUT).expect("robot should answer");
+
+    let mut data = DriverStationData::new();
+    server.update_driver_station(&mut data);
+    assert_eq!(data.station, AllianceStation::Blue3);
+    assert_eq!(data.match_time, Some(90.0));
+    assert_eq!(data.joysticks, vec![joystick()]);
+}
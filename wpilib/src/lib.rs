@@ -28,12 +28,18 @@
 
 extern crate wpilib_macros;
 
-use robots::UserRobot;
+use std::process::ExitCode;
+
+use driver_station::{DriverStationConfig, DriverStationServer};
+use once_cell::sync::OnceCell;
+use robots::{run_robot, runtime, ModeSource, SimModeHandle, SimModeSource, UserRobot};
 
 #[cfg(feature = "command")]
 pub mod command;
+pub mod driver_station;
 pub mod math;
 pub mod robots;
+pub mod timer;
 #[macro_use]
 pub mod macros;
 
@@ -46,6 +52,7 @@ pub mod re_exports {
     pub use parking_lot;
     pub use serde;
     pub use simba;
+    pub use typenum;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -56,5 +63,69 @@ pub enum EventTypes {
     End,
 }
 
+static SIM_MODE_HANDLE: OnceCell<SimModeHandle> = OnceCell::new();
+
+/// The handle driving a robot started by [`wpilib_main`] in simulation, `None` until it
+/// has started or when it talks to a real driver station.
+#[must_use]
+pub fn get_sim_mode_handle() -> Option<SimModeHandle> {
+    SIM_MODE_HANDLE.get().cloned()
+}
+
+//simulation is driven through a SimModeHandle, everything else talks to a real driver station
+fn default_mode_source() -> std::io::Result<Box<dyn ModeSource>> {
+    if cfg!(feature = "simulation") {
+        let (source, handle) = SimModeSource::new();
+        let _ = SIM_MODE_HANDLE.set(handle);
+        return Ok(Box::new(source));
+    }
+    let server = DriverStationServer::start(DriverStationConfig::default())?;
+    server.handle().set_code_running(true);
+    Ok(Box::new(server))
+}
+
+/// Boots `robot`: sets up logging, panic reporting and signal handling, then runs the
+/// robot loop until it's shut down.
+///
+/// In simulation the robot is driven through [`get_sim_mode_handle`], otherwise by the
+/// driver station.
+///
+/// Returns a failure exit code if the robot panicked or the driver station server
+/// couldn't start.
+#[must_use]
 #[no_panic::no_panic]
-pub fn wpilib_main(_robot: Box<dyn UserRobot>) {}
+pub fn wpilib_main(robot: Box<dyn UserRobot>) -> ExitCode {
+    boot(robot, None)
+}
+
+/// Boots `robot` like [`wpilib_main`] with its control state coming from `mode_source`.
+#[must_use]
+#[no_panic::no_panic]
+pub fn wpilib_main_with_source(
+    robot: Box<dyn UserRobot>,
+    mode_source: Box<dyn ModeSource>,
+) -> ExitCode {
+    boot(robot, Some(mode_source))
+}
+
+fn boot(robot: Box<dyn UserRobot>, mode_source: Option<Box<dyn ModeSource>>) -> ExitCode {
+    let _log_guard = runtime::init_logging(&runtime::default_log_dir());
+    runtime::install_panic_hook();
+    if let Err(err) = runtime::install_signal_handlers() {
+        tracing::warn!("Failed to install signal handlers: {}", err);
+    }
+
+    let mode_source = match mode_source.map_or_else(default_mode_source, Ok) {
+        Ok(mode_source) => mode_source,
+        Err(err) => {
+            tracing::error!("Failed to start the driver station server: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if run_robot(robot, mode_source).is_ok() {
+        ExitCode::SUCCESS
+    } else {
+        tracing::error!("Robot program crashed");
+        ExitCode::FAILURE
+    }
+}
@@ -1,14 +1,15 @@
 use crate::math::controllers::controller::Controller;
-use crate::math::units::time::Millisecond;
+use crate::math::units::{time::Millisecond, Unit};
 
+/// A bang-bang controller taking measurements in `M` and giving outputs in `O`.
 #[derive(Debug, Clone, Copy)]
-pub struct BangBangController {
-    pub min_input: f64,
-    pub max_input: f64,
-    pub min_output: f64,
-    pub max_output: f64,
-    pub set_point: f64,
-    pub tolerance: f64,
+pub struct BangBangController<M = f64, O = f64> {
+    pub min_input: M,
+    pub max_input: M,
+    pub min_output: O,
+    pub max_output: O,
+    pub set_point: M,
+    pub tolerance: M,
     pub enabled: bool,
 }
 
@@ -25,26 +26,49 @@ impl BangBangController {
             enabled: true,
         }
     }
+}
+
+impl<M: Unit, O: Unit> BangBangController<M, O> {
+    /// A controller with its measurement and output in units, switching between
+    /// `min_output` and `max_output`.
+    ///
+    /// There's no sensible default output in an arbitrary unit, so they're required.
+    #[must_use]
+    pub fn with_units(min_output: O, max_output: O) -> Self {
+        Self {
+            min_input: M::from_raw(f64::NEG_INFINITY),
+            max_input: M::from_raw(f64::INFINITY),
+            min_output,
+            max_output,
+            set_point: M::from_raw(0.0),
+            tolerance: M::from_raw(0.0),
+            enabled: true,
+        }
+    }
 
-    pub fn set_tolerance(&mut self, tolerance: f64) {
-        self.tolerance = tolerance;
+    pub fn set_tolerance(&mut self, tolerance: impl Into<M>) {
+        self.tolerance = tolerance.into();
     }
 }
 
-impl Controller for BangBangController {
-    fn calculate(&mut self, measurement: f64, _period: impl Into<Millisecond>) -> f64 {
+impl<M: Unit, O: Unit> Controller<M, O> for BangBangController<M, O> {
+    fn calculate(&mut self, measurement: impl Into<M>, _period: impl Into<Millisecond>) -> O {
         if !self.enabled {
-            return 0.0;
+            return O::from_raw(0.0);
         }
-        if measurement.clamp(self.min_input, self.max_input) < self.set_point {
+        let measurement = measurement
+            .into()
+            .get_raw()
+            .clamp(self.min_input.get_raw(), self.max_input.get_raw());
+        if measurement < self.set_point.get_raw() {
             self.max_output
         } else {
             self.min_output
         }
     }
 
-    fn set_set_point(&mut self, set_point: f64) {
-        self.set_point = set_point;
+    fn set_set_point(&mut self, set_point: impl Into<M>) {
+        self.set_point = set_point.into();
     }
 
     fn set_enabled(&mut self, enabled: bool) {
@@ -55,18 +79,18 @@ impl Controller for BangBangController {
         self.enabled
     }
 
-    fn get_set_point(&self) -> f64 {
+    fn get_set_point(&self) -> M {
         self.set_point
     }
 
-    fn set_limits(&mut self, min_input: f64, max_input: f64, min_output: f64, max_output: f64) {
+    fn set_limits(&mut self, min_input: M, max_input: M, min_output: O, max_output: O) {
         self.min_input = min_input;
         self.max_input = max_input;
         self.min_output = min_output;
         self.max_output = max_output;
     }
 
-    fn get_limits(&self) -> (f64, f64, f64, f64) {
+    fn get_limits(&self) -> (M, M, O, O) {
         (
             self.min_input,
             self.max_input,
@@ -76,6 +100,6 @@ impl Controller for BangBangController {
     }
 
     fn reset(&mut self) {
-        self.set_point = 0.0;
+        self.set_point = M::from_raw(0.0);
     }
 }
@@ -1,21 +1,23 @@
-use crate::math::units::time::Millisecond;
+use crate::math::units::{time::Millisecond, Unit};
 
-pub trait Controller {
+/// A controller that drives a measurement in `M` to a set point with an output in `O`,
+/// both plain numbers unless they're given units.
+pub trait Controller<M: Unit = f64, O: Unit = f64> {
     /// Returns the control output.
-    fn calculate(&mut self, measurement: f64, period: impl Into<Millisecond>) -> f64;
+    fn calculate(&mut self, measurement: impl Into<M>, period: impl Into<Millisecond>) -> O;
     /// Sets the set point.
-    fn set_set_point(&mut self, set_point: f64);
+    fn set_set_point(&mut self, set_point: impl Into<M>);
     /// Enables or disables the controller.
     fn set_enabled(&mut self, enabled: bool);
     /// Returns whether the controller is enabled.
     fn get_enabled(&self) -> bool;
     /// Returns the set point.
-    fn get_set_point(&self) -> f64;
+    fn get_set_point(&self) -> M;
     /// Sets the input and output limits.
-    fn set_limits(&mut self, min_input: f64, max_input: f64, min_output: f64, max_output: f64);
+    fn set_limits(&mut self, min_input: M, max_input: M, min_output: O, max_output: O);
     /// Gets the input and output limits.
     /// in the order min_input, max_input, min_output, max_output
-    fn get_limits(&self) -> (f64, f64, f64, f64);
+    fn get_limits(&self) -> (M, M, O, O);
     /// Resets the controller.
     fn reset(&mut self);
 }
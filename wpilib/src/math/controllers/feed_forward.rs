@@ -1,19 +1,32 @@
+use std::marker::PhantomData;
+
 use crate::math::units::angle::Radian;
 use crate::math::units::angular_acceleration::RadianPerSecondSquared;
 use crate::math::units::angular_velocity::RadianPerSecond;
 use crate::math::units::energy::Volt;
+use crate::math::units::linear_acceleration::MeterPerSecondSquared;
+use crate::math::units::linear_velocity::MeterPerSecond;
+use crate::math::units::Unit;
 
+/// A feedforward for a mechanism with velocities in `V` and accelerations in `A`,
+/// its gains are in volts per unit of them.
 #[derive(Debug, Clone, Copy)]
-pub struct Simple {
+pub struct Simple<V = RadianPerSecond, A = RadianPerSecondSquared> {
     k_s: f64,
     k_v: f64,
     k_a: f64,
+    units: PhantomData<fn() -> (V, A)>,
 }
 #[derive(Debug, Clone, Copy)]
-pub struct Static {
+pub struct Static<V = RadianPerSecond, A = RadianPerSecondSquared> {
     k_v: f64,
     k_a: f64,
+    units: PhantomData<fn() -> (V, A)>,
 }
+/// A simple feedforward for a mechanism that moves in a line.
+pub type LinearSimple = Simple<MeterPerSecond, MeterPerSecondSquared>;
+/// A static feedforward for a mechanism that moves in a line.
+pub type LinearStatic = Static<MeterPerSecond, MeterPerSecondSquared>;
 #[derive(Debug, Clone, Copy)]
 pub struct Elevator {
     k_s: f64,
@@ -32,55 +45,55 @@ pub struct Arm {
 impl Simple {
     #[must_use]
     pub const fn new(k_s: f64, k_v: f64, k_a: f64) -> Self {
-        Self { k_s, k_v, k_a }
+        Self::with_units(k_s, k_v, k_a)
     }
+}
 
-    pub fn v_a_calculate(
-        &mut self,
-        velocity: impl Into<RadianPerSecond>,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
-        let velocity: f64 = velocity.into().value();
-        let acceleration: f64 = acceleration.into().value();
-        self.k_a.mul_add(
+impl<V: Unit, A: Unit> Simple<V, A> {
+    #[must_use]
+    pub const fn with_units(k_s: f64, k_v: f64, k_a: f64) -> Self {
+        Self {
+            k_s,
+            k_v,
+            k_a,
+            units: PhantomData,
+        }
+    }
+
+    pub fn v_a_calculate(&mut self, velocity: impl Into<V>, acceleration: impl Into<A>) -> Volt {
+        let velocity: f64 = velocity.into().get_raw();
+        let acceleration: f64 = acceleration.into().get_raw();
+        Volt::new(self.k_a.mul_add(
             acceleration,
             self.k_s.mul_add(num::signum(velocity), self.k_v * velocity),
-        )
+        ))
     }
 
-    pub fn v_calculate(&mut self, velocity: impl Into<RadianPerSecond>) -> f64 {
-        self.v_a_calculate(velocity, 0.0)
+    pub fn v_calculate(&mut self, velocity: impl Into<V>) -> Volt {
+        self.v_a_calculate(velocity, A::from_raw(0.0))
     }
 
-    pub fn max_velocity(
-        &mut self,
-        max_voltage: Volt,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
-        let acceleration: f64 = acceleration.into().value();
-        self.k_a
-            .mul_add(-acceleration, max_voltage.value() - self.k_s)
-            / self.k_v
+    pub fn max_velocity(&mut self, max_voltage: Volt, acceleration: impl Into<A>) -> V {
+        let acceleration: f64 = acceleration.into().get_raw();
+        V::from_raw(
+            self.k_a
+                .mul_add(-acceleration, max_voltage.value() - self.k_s)
+                / self.k_v,
+        )
     }
 
-    pub fn max_acceleration(
-        &mut self,
-        max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
-        let velocity: f64 = velocity.into().value();
-        velocity.mul_add(
-            -self.k_v,
-            self.k_s
-                .mul_add(-num::signum(velocity), max_voltage.value()),
-        ) / self.k_a
+    pub fn max_acceleration(&mut self, max_voltage: Volt, velocity: impl Into<V>) -> A {
+        let velocity: f64 = velocity.into().get_raw();
+        A::from_raw(
+            velocity.mul_add(
+                -self.k_v,
+                self.k_s
+                    .mul_add(-num::signum(velocity), max_voltage.value()),
+            ) / self.k_a,
+        )
     }
 
-    pub fn min_acceleration(
-        &mut self,
-        max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+    pub fn min_acceleration(&mut self, max_voltage: Volt, velocity: impl Into<V>) -> A {
         self.max_acceleration(-max_voltage, velocity)
     }
 }
@@ -88,49 +101,46 @@ impl Simple {
 impl Static {
     #[must_use]
     pub const fn new(k_v: f64, k_a: f64) -> Self {
-        Self { k_v, k_a }
+        Self::with_units(k_v, k_a)
     }
+}
 
-    pub fn v_a_calculate(
-        &mut self,
-        velocity: impl Into<RadianPerSecond>,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
-        let velocity: f64 = velocity.into().value();
-        let acceleration: f64 = acceleration.into().value();
-        self.k_v.mul_add(velocity, self.k_a * acceleration)
+impl<V: Unit, A: Unit> Static<V, A> {
+    #[must_use]
+    pub const fn with_units(k_v: f64, k_a: f64) -> Self {
+        Self {
+            k_v,
+            k_a,
+            units: PhantomData,
+        }
     }
 
-    pub fn v_calculate(&mut self, velocity: impl Into<RadianPerSecond>) -> f64 {
-        self.v_a_calculate(velocity, 0.0)
+    pub fn v_a_calculate(&mut self, velocity: impl Into<V>, acceleration: impl Into<A>) -> Volt {
+        let velocity: f64 = velocity.into().get_raw();
+        let acceleration: f64 = acceleration.into().get_raw();
+        Volt::new(self.k_v.mul_add(velocity, self.k_a * acceleration))
     }
 
-    pub fn max_velocity(
-        &mut self,
-        max_voltage: Volt,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
-        let acceleration: f64 = acceleration.into().value();
-        self.k_a.mul_add(-acceleration, max_voltage.value()) / self.k_v
+    pub fn v_calculate(&mut self, velocity: impl Into<V>) -> Volt {
+        self.v_a_calculate(velocity, A::from_raw(0.0))
     }
 
-    pub fn max_acceleration(
-        &mut self,
-        max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
-        let velocity: f64 = velocity.into().value();
-        max_voltage
-            .value()
-            .mul_add(num::signum(velocity), -velocity * self.k_v)
-            / self.k_a
+    pub fn max_velocity(&mut self, max_voltage: Volt, acceleration: impl Into<A>) -> V {
+        let acceleration: f64 = acceleration.into().get_raw();
+        V::from_raw(self.k_a.mul_add(-acceleration, max_voltage.value()) / self.k_v)
     }
 
-    pub fn min_acceleration(
-        &mut self,
-        max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+    pub fn max_acceleration(&mut self, max_voltage: Volt, velocity: impl Into<V>) -> A {
+        let velocity: f64 = velocity.into().get_raw();
+        A::from_raw(
+            max_voltage
+                .value()
+                .mul_add(num::signum(velocity), -velocity * self.k_v)
+                / self.k_a,
+        )
+    }
+
+    pub fn min_acceleration(&mut self, max_voltage: Volt, velocity: impl Into<V>) -> A {
         self.max_acceleration(-max_voltage, velocity)
     }
 }
@@ -143,63 +153,71 @@ impl Elevator {
 
     pub fn v_a_calculate(
         &mut self,
-        velocity: impl Into<RadianPerSecond>,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+        velocity: impl Into<MeterPerSecond>,
+        acceleration: impl Into<MeterPerSecondSquared>,
+    ) -> Volt {
         let velocity: f64 = velocity.into().value();
         let acceleration: f64 = acceleration.into().value();
-        self.k_a.mul_add(
-            acceleration,
-            self.k_v
-                .mul_add(velocity, self.k_s.mul_add(num::signum(velocity), self.k_g)),
+        Volt::new(
+            self.k_a.mul_add(
+                acceleration,
+                self.k_v
+                    .mul_add(velocity, self.k_s.mul_add(num::signum(velocity), self.k_g)),
+            ),
         )
     }
 
-    pub fn v_calculate(&mut self, velocity: impl Into<RadianPerSecond>) -> f64 {
-        self.v_a_calculate(velocity, 0)
+    pub fn v_calculate(&mut self, velocity: impl Into<MeterPerSecond>) -> Volt {
+        self.v_a_calculate(velocity, 0.0)
     }
 
     pub fn max_velocity(
         &mut self,
         max_voltage: Volt,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+        acceleration: impl Into<MeterPerSecondSquared>,
+    ) -> MeterPerSecond {
         let acceleration: f64 = acceleration.into().value();
-        self.k_a
-            .mul_add(-acceleration, max_voltage.value() - self.k_s - self.k_g)
-            / self.k_v
+        MeterPerSecond::new(
+            self.k_a
+                .mul_add(-acceleration, max_voltage.value() - self.k_s - self.k_g)
+                / self.k_v,
+        )
     }
 
     pub fn min_velocity(
         &mut self,
         max_voltage: Volt,
-        acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+        acceleration: impl Into<MeterPerSecondSquared>,
+    ) -> MeterPerSecond {
         let acceleration: f64 = acceleration.into().value();
-        self.k_a
-            .mul_add(-acceleration, -max_voltage.value() + self.k_s - self.k_g)
-            / self.k_v
+        MeterPerSecond::new(
+            self.k_a
+                .mul_add(-acceleration, -max_voltage.value() + self.k_s - self.k_g)
+                / self.k_v,
+        )
     }
 
     pub fn max_acceleration(
         &mut self,
         max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+        velocity: impl Into<MeterPerSecond>,
+    ) -> MeterPerSecondSquared {
         let velocity: f64 = velocity.into().value();
-        velocity.mul_add(
-            -self.k_v,
-            self.k_s
-                .mul_add(-num::signum(velocity), max_voltage.value())
-                - self.k_g,
-        ) / self.k_a
+        MeterPerSecondSquared::new(
+            velocity.mul_add(
+                -self.k_v,
+                self.k_s
+                    .mul_add(-num::signum(velocity), max_voltage.value())
+                    - self.k_g,
+            ) / self.k_a,
+        )
     }
 
     pub fn min_acceleration(
         &mut self,
         max_voltage: Volt,
-        velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+        velocity: impl Into<MeterPerSecond>,
+    ) -> MeterPerSecondSquared {
         self.max_acceleration(-max_voltage, velocity)
     }
 }
@@ -215,15 +233,17 @@ impl Arm {
         position: impl Into<Radian>,
         velocity: impl Into<RadianPerSecond>,
         acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+    ) -> Volt {
         let position: f64 = position.into().value();
         let velocity: f64 = velocity.into().value();
         let acceleration: f64 = acceleration.into().value();
         let g_cos: f64 = position.cos() * self.k_g;
-        self.k_a.mul_add(
-            acceleration,
-            self.k_v
-                .mul_add(velocity, self.k_s.mul_add(num::signum(velocity), g_cos)),
+        Volt::new(
+            self.k_a.mul_add(
+                acceleration,
+                self.k_v
+                    .mul_add(velocity, self.k_s.mul_add(num::signum(velocity), g_cos)),
+            ),
         )
     }
 
@@ -231,7 +251,7 @@ impl Arm {
         &mut self,
         position: impl Into<Radian>,
         velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+    ) -> Volt {
         self.p_v_a_calculate(position, velocity, 0)
     }
 
@@ -240,15 +260,17 @@ impl Arm {
         max_voltage: Volt,
         angle: impl Into<Radian>,
         acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+    ) -> RadianPerSecond {
         let angle: f64 = angle.into().value();
         let acceleration: f64 = acceleration.into().value();
-        acceleration.mul_add(
-            -self.k_a,
-            angle
-                .cos()
-                .mul_add(-self.k_g, max_voltage.value() - self.k_s),
-        ) / self.k_v
+        RadianPerSecond::new(
+            acceleration.mul_add(
+                -self.k_a,
+                angle
+                    .cos()
+                    .mul_add(-self.k_g, max_voltage.value() - self.k_s),
+            ) / self.k_v,
+        )
     }
 
     pub fn min_velocity(
@@ -256,15 +278,17 @@ impl Arm {
         max_voltage: Volt,
         angle: impl Into<Radian>,
         acceleration: impl Into<RadianPerSecondSquared>,
-    ) -> f64 {
+    ) -> RadianPerSecond {
         let angle: f64 = angle.into().value();
         let acceleration: f64 = acceleration.into().value();
-        acceleration.mul_add(
-            -self.k_a,
-            angle
-                .cos()
-                .mul_add(-self.k_g, -max_voltage.value() + self.k_s),
-        ) / self.k_v
+        RadianPerSecond::new(
+            acceleration.mul_add(
+                -self.k_a,
+                angle
+                    .cos()
+                    .mul_add(-self.k_g, -max_voltage.value() + self.k_s),
+            ) / self.k_v,
+        )
     }
 
     pub fn max_acceleration(
@@ -272,17 +296,19 @@ impl Arm {
         max_voltage: Volt,
         angle: impl Into<Radian>,
         velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+    ) -> RadianPerSecondSquared {
         let angle: f64 = angle.into().value();
         let velocity: f64 = velocity.into().value();
-        velocity.mul_add(
-            -self.k_v,
-            angle.cos().mul_add(
-                -self.k_g,
-                self.k_s
-                    .mul_add(-num::signum(velocity), max_voltage.value()),
-            ),
-        ) / self.k_a
+        RadianPerSecondSquared::new(
+            velocity.mul_add(
+                -self.k_v,
+                angle.cos().mul_add(
+                    -self.k_g,
+                    self.k_s
+                        .mul_add(-num::signum(velocity), max_voltage.value()),
+                ),
+            ) / self.k_a,
+        )
     }
 
     pub fn min_acceleration(
@@ -290,7 +316,7 @@ impl Arm {
         max_voltage: Volt,
         angle: impl Into<Radian>,
         velocity: impl Into<RadianPerSecond>,
-    ) -> f64 {
+    ) -> RadianPerSecondSquared {
         self.max_acceleration(-max_voltage, angle, velocity)
     }
 }
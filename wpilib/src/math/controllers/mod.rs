@@ -2,6 +2,7 @@ mod bang_bang;
 mod controller;
 pub mod feed_forward;
 mod pid;
+#[cfg(test)]
 mod test;
 
 pub use bang_bang::*;
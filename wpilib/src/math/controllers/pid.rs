@@ -1,20 +1,24 @@
 use crate::math::controllers::controller::Controller;
-use crate::math::units::time::Millisecond;
+use crate::math::units::{time::Millisecond, Unit};
 
+/// A PID controller taking measurements in `M` and giving outputs in `O`.
+///
+/// The gains are in output units per measurement unit, so a distance controller
+/// driving a motor is a `PIDController<Meter, Volt>` with `k_p` in volts per meter.
 #[derive(Debug, Clone, Copy)]
-pub struct PIDController {
+pub struct PIDController<M = f64, O = f64> {
     pub k_p: f64,
     pub k_i: f64,
     pub k_d: f64,
     pub i_min: f64,
     pub i_max: f64,
-    pub min_input: f64,
-    pub max_input: f64,
-    pub min_output: f64,
-    pub max_output: f64,
+    pub min_input: M,
+    pub max_input: M,
+    pub min_output: O,
+    pub max_output: O,
     prev_error: f64,
     total_error: f64,
-    set_point: f64,
+    set_point: M,
     enabled: bool,
 }
 
@@ -37,6 +41,37 @@ impl PIDController {
             enabled: true,
         }
     }
+}
+
+impl<M: Unit, O: Unit> PIDController<M, O> {
+    /// A controller with its measurement and output in units.
+    ///
+    /// Nothing is clamped until it's given limits and an i zone, a ±1 default only makes
+    /// sense for duty cycles.
+    /// ```ignore
+    /// let mut controller = PIDController::<Meter, Volt>::with_units(4.0, 0.0, 0.1);
+    /// controller.set_limits(Meter::new(0.0), Meter::new(1.5), Volt::new(-12.0), Volt::new(12.0));
+    /// controller.set_set_point(Feet::new(3.0));
+    /// let output: Volt = controller.calculate(Meter::new(0.5), 20);
+    /// ```
+    #[must_use]
+    pub fn with_units(k_p: f64, k_i: f64, k_d: f64) -> Self {
+        Self {
+            k_p,
+            k_i,
+            k_d,
+            i_min: f64::NEG_INFINITY,
+            i_max: f64::INFINITY,
+            min_input: M::from_raw(f64::NEG_INFINITY),
+            max_input: M::from_raw(f64::INFINITY),
+            min_output: O::from_raw(f64::NEG_INFINITY),
+            max_output: O::from_raw(f64::INFINITY),
+            prev_error: 0.0,
+            total_error: 0.0,
+            set_point: M::from_raw(0.0),
+            enabled: true,
+        }
+    }
 
     pub fn set_i_zone(&mut self, i_min: f64, i_max: f64) {
         self.i_min = i_min;
@@ -44,13 +79,13 @@ impl PIDController {
     }
 }
 
-impl Controller for PIDController {
-    fn calculate(&mut self, measurement: f64, period: impl Into<Millisecond>) -> f64 {
+impl<M: Unit, O: Unit> Controller<M, O> for PIDController<M, O> {
+    fn calculate(&mut self, measurement: impl Into<M>, period: impl Into<Millisecond>) -> O {
         let period: f64 = period.into().value();
         if !self.enabled {
-            return 0.0;
+            return O::from_raw(0.0);
         }
-        let error = self.set_point - measurement;
+        let error = self.set_point.get_raw() - measurement.into().get_raw();
         self.total_error += error * period;
         self.total_error = self.total_error.clamp(self.i_min, self.i_max);
         let d_error = (error - self.prev_error) / period;
@@ -59,11 +94,11 @@ impl Controller for PIDController {
         let i = self.k_i * self.total_error;
         let d = self.k_d * d_error;
         let output = p + i + d;
-        output.clamp(self.min_output, self.max_output)
+        O::from_raw(output.clamp(self.min_output.get_raw(), self.max_output.get_raw()))
     }
 
-    fn set_set_point(&mut self, set_point: f64) {
-        self.set_point = set_point;
+    fn set_set_point(&mut self, set_point: impl Into<M>) {
+        self.set_point = set_point.into();
     }
 
     fn set_enabled(&mut self, enabled: bool) {
@@ -74,18 +109,18 @@ impl Controller for PIDController {
         self.enabled
     }
 
-    fn get_set_point(&self) -> f64 {
+    fn get_set_point(&self) -> M {
         self.set_point
     }
 
-    fn set_limits(&mut self, min_input: f64, max_input: f64, min_output: f64, max_output: f64) {
+    fn set_limits(&mut self, min_input: M, max_input: M, min_output: O, max_output: O) {
         self.min_input = min_input;
         self.max_input = max_input;
         self.min_output = min_output;
         self.max_output = max_output;
     }
 
-    fn get_limits(&self) -> (f64, f64, f64, f64) {
+    fn get_limits(&self) -> (M, M, O, O) {
         (
             self.min_input,
             self.max_input,
@@ -1,4 +1,16 @@
-use crate::math::controllers::{BangBangController, Controller, PIDController};
+use crate::math::{
+    controllers::{
+        feed_forward::{Elevator, LinearSimple, Simple},
+        BangBangController, Controller, PIDController,
+    },
+    units::{
+        angular_velocity::RadianPerSecond,
+        distance::{Centimeter, Meter},
+        energy::Volt,
+        linear_acceleration::MeterPerSecondSquared,
+        linear_velocity::MeterPerSecond,
+    },
+};
 
 #[test]
 fn bang_bang() {
@@ -17,3 +29,73 @@ fn pid() {
 
     assert_eq!(controller.calculate(0.2, 20), 0.21150000000000002);
 }
+
+#[test]
+fn pid_units() {
+    let mut controller = PIDController::<Meter, Volt>::with_units(0.1, 0.2, 0.3);
+
+    //the set point converts into the measurement's unit
+    controller.set_set_point(Centimeter::new(30.0));
+
+    //nothing is clamped, the ±1 duty cycle limits don't carry over into volts
+    let output: Volt = controller.calculate(Meter::new(0.2), 20);
+    assert_eq!(output, Volt::new(0.411_499_999_999_999_9));
+}
+
+#[test]
+fn unit_limits() {
+    let mut controller = PIDController::<Meter, Volt>::with_units(20.0, 1.0, 0.0);
+    controller.set_set_point(Meter::new(1.0));
+    let output: Volt = controller.calculate(Meter::new(0.0), 20);
+    assert_eq!(output, Volt::new(40.0));
+
+    controller.reset();
+    controller.set_limits(
+        Meter::new(0.0),
+        Meter::new(2.0),
+        Volt::new(-12.0),
+        Volt::new(12.0),
+    );
+    let output: Volt = controller.calculate(Meter::new(0.0), 20);
+    assert_eq!(output, Volt::new(12.0));
+    controller.set_set_point(Meter::new(-1.0));
+    controller.reset();
+    let output: Volt = controller.calculate(Meter::new(0.0), 20);
+    assert_eq!(output, Volt::new(-12.0));
+
+    //the i zone bounds the accumulated error
+    controller.reset();
+    controller.k_p = 0.0;
+    controller.set_i_zone(-0.1, 0.1);
+    let output: Volt = controller.calculate(Meter::new(0.0), 20);
+    assert_eq!(output, Volt::new(-0.1));
+
+    let mut bang_bang =
+        BangBangController::<Meter, Volt>::with_units(Volt::new(0.0), Volt::new(12.0));
+    bang_bang.set_set_point(Meter::new(100.0));
+    assert_eq!(bang_bang.calculate(Meter::new(50.0), 20), Volt::new(12.0));
+    assert_eq!(bang_bang.calculate(Meter::new(150.0), 20), Volt::new(0.0));
+}
+
+#[test]
+fn feed_forward_units() {
+    let mut elevator = Elevator::new(0.5, 1.0, 2.0, 0.25);
+    let volts: Volt =
+        elevator.v_a_calculate(MeterPerSecond::new(1.5), MeterPerSecondSquared::new(2.0));
+    assert_eq!(volts, Volt::new(5.0));
+    assert_eq!(
+        elevator.max_velocity(Volt::new(12.0), 0.0),
+        MeterPerSecond::new(5.25)
+    );
+
+    let mut simple = LinearSimple::with_units(0.5, 2.0, 0.25);
+    assert_eq!(
+        simple.v_calculate(MeterPerSecond::new(-1.0)),
+        Volt::new(-2.5)
+    );
+    let mut angular = Simple::new(0.5, 2.0, 0.25);
+    assert_eq!(
+        angular.v_calculate(RadianPerSecond::new(1.0)),
+        Volt::new(2.5)
+    );
+}
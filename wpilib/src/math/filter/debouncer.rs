@@ -1,34 +1,68 @@
-use crate::math::units::time::Second;
-use std::any::Any;
+use crate::{
+    math::units::time::Second,
+    timer::{ClockRef, Timer},
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DebounceType {
     Rising,
     Falling,
     Both,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Debouncer {
     debounce_time: Second,
-    previous_time: Second,
+    timer: Timer,
     debounce_type: DebounceType,
     base_value: bool,
 }
 
 impl Debouncer {
+    /// `base_value` is only used by [`DebounceType::Both`], rising debouncers start low
+    /// and falling ones start high.
     #[must_use]
     pub fn new(debounce_time: Second, debounce_type: DebounceType, base_value: bool) -> Self {
-        Self {
+        Self::with_timer(debounce_time, debounce_type, base_value, Timer::new())
+    }
+
+    /// Same as [`Debouncer::new`] but timed on `clock` instead of the default clock.
+    #[must_use]
+    pub fn with_clock(
+        debounce_time: Second,
+        debounce_type: DebounceType,
+        base_value: bool,
+        clock: ClockRef,
+    ) -> Self {
+        Self::with_timer(
             debounce_time,
-            previous_time: Second::new(0.0),
             debounce_type,
             base_value,
+            Timer::with_clock(clock),
+        )
+    }
+
+    fn with_timer(
+        debounce_time: Second,
+        debounce_type: DebounceType,
+        base_value: bool,
+        mut timer: Timer,
+    ) -> Self {
+        timer.start();
+        Self {
+            debounce_time,
+            timer,
+            debounce_type,
+            base_value: match debounce_type {
+                DebounceType::Rising => false,
+                DebounceType::Falling => true,
+                DebounceType::Both => base_value,
+            },
         }
     }
 
     pub fn reset_timer(&mut self) {
-        self.previous_time = Second::new(0.0);
+        self.timer.reset();
     }
 
     pub fn calculate(&mut self, input: bool) -> bool {
@@ -36,7 +70,7 @@ impl Debouncer {
             self.reset_timer();
         }
         if self.has_elapsed() {
-            if self.debounce_type.type_id() == DebounceType::Both.type_id() {
+            if self.debounce_type == DebounceType::Both {
                 self.base_value = input;
                 self.reset_timer();
             }
@@ -46,12 +80,12 @@ impl Debouncer {
         }
     }
 
-    pub fn reset(&mut self, value: bool, current_time: Second) {
+    pub fn reset(&mut self, value: bool) {
         self.base_value = value;
-        self.previous_time = current_time;
+        self.reset_timer();
     }
 
     fn has_elapsed(&self) -> bool {
-        Second::new(0.0) - self.previous_time >= self.debounce_time
+        Second::new(self.timer.get().as_secs_f64()) >= self.debounce_time
     }
 }
@@ -1,28 +1,48 @@
 use num::clamp;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-#[derive(Debug, Clone, Copy)]
+use crate::timer::{get_default_clock, ClockRef};
+
+#[derive(Debug, Clone)]
 pub struct SlewRateLimiter {
     pub positive_rate_limit: f64,
     pub negative_rate_limit: f64,
     pub last_value: f64,
-    pub previous_timestamp: Instant,
+    pub previous_timestamp: Duration,
+    clock: ClockRef,
 }
 
 impl SlewRateLimiter {
     #[must_use]
     pub fn new(positive_rate_limit: f64, negative_rate_limit: f64, initial_value: f64) -> Self {
+        Self::with_clock(
+            positive_rate_limit,
+            negative_rate_limit,
+            initial_value,
+            get_default_clock(),
+        )
+    }
+
+    /// Same as [`SlewRateLimiter::new`] but timed on `clock` instead of the default clock.
+    #[must_use]
+    pub fn with_clock(
+        positive_rate_limit: f64,
+        negative_rate_limit: f64,
+        initial_value: f64,
+        clock: ClockRef,
+    ) -> Self {
         Self {
             positive_rate_limit,
             negative_rate_limit,
             last_value: initial_value,
-            previous_timestamp: Instant::now(),
+            previous_timestamp: clock.now(),
+            clock,
         }
     }
 
     pub fn calculate(&mut self, input: f64) -> f64 {
-        let timestamp = Instant::now();
-        let delta_time: Duration = timestamp - self.previous_timestamp;
+        let timestamp = self.clock.now();
+        let delta_time = timestamp.saturating_sub(self.previous_timestamp);
         self.last_value += clamp(
             input - self.last_value,
             -self.negative_rate_limit * delta_time.as_secs_f64(),
@@ -34,6 +54,6 @@ impl SlewRateLimiter {
 
     pub fn reset(&mut self, value: f64) {
         self.last_value = value;
-        self.previous_timestamp = Instant::now();
+        self.previous_timestamp = self.clock.now();
     }
 }
@@ -2,7 +2,6 @@ use crate::math::units::{angle::Radian, distance::Meter};
 use std::fmt::Display;
 use std::ops;
 
-use nalgebra::ComplexField;
 use num::clamp;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -17,14 +16,14 @@ impl Rotation2d {
         let value: Radian = angle.into();
         Self {
             value,
-            sin: value.sin().into(),
-            cos: value.cos().into(),
+            sin: value.value().sin(),
+            cos: value.value().cos(),
         }
     }
     pub fn new_xy(x: impl Into<Meter>, y: impl Into<Meter>) -> Self {
         let x = x.into();
         let y = y.into();
-        let magnitude = x.hypot(y);
+        let magnitude = f64::from(x).hypot(f64::from(y));
         let sin;
         let cos;
         if magnitude > 1e-6 {
@@ -1,4 +1,4 @@
-use nalgebra::{ComplexField, Translation2};
+use nalgebra::Translation2;
 use std::ops;
 
 use super::Rotation2d;
@@ -32,12 +32,12 @@ impl Translation2d {
     pub fn get_distance(&self, other: &Self) -> Meter {
         let delta_x = other.x - self.x;
         let delta_y = other.y - self.y;
-        ComplexField::hypot(delta_x, delta_y).into()
+        delta_x.value().hypot(delta_y.value()).into()
     }
 
     #[must_use]
     pub fn get_norm(&self) -> Meter {
-        ComplexField::hypot(self.x, self.y).into()
+        self.x.value().hypot(self.y.value()).into()
     }
 
     #[must_use]
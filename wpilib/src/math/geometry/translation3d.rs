@@ -1,4 +1,4 @@
-use nalgebra::{ComplexField, Quaternion};
+use nalgebra::Quaternion;
 use std::ops;
 
 use crate::math::units::distance::Meter;
@@ -35,14 +35,18 @@ impl Translation3d {
 
     #[must_use]
     fn get_distance(&self, other: &Self) -> Meter {
-        ComplexField::sqrt(
-            (other.x - self.x).square() + (other.y - self.y).square() + (other.z - self.z).square(),
-        )
+        ((other.x - self.x).square() + (other.y - self.y).square() + (other.z - self.z).square())
+            .value()
+            .sqrt()
+            .into()
     }
 
     #[must_use]
     pub fn get_norm(&self) -> Meter {
-        ComplexField::sqrt(self.x.square() + self.y.square() + self.z.square())
+        (self.x.square() + self.y.square() + self.z.square())
+            .value()
+            .sqrt()
+            .into()
     }
 
     #[must_use]
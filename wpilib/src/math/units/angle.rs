@@ -1,35 +1,33 @@
 use crate::math::units::angular_velocity::{
     DegreePerSecond, RadianPerSecond, RotationPerMinute, RotationPerSecond,
 };
-use crate::math::units::time::{Minute, Second};
-use wpilib_macros::{unit, unit_conversion, unit_dimensional_analysis};
+use crate::math::units::dimension::Angle;
+use crate::math::units::time::Second;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 
 crate::crate_namespace!();
 
-unit!(Degree, f64);
-unit!(Radian, f64);
-unit!(Rotation, f64);
+unit!(Degree, f64, Angle, std::f64::consts::PI / 180.0, "deg");
+unit!(Radian, f64, Angle, si);
+unit!(Rotation, f64, Angle, std::f64::consts::TAU, "rot");
 
-unit_conversion!(Degree f64, Radian f64, degree_to_radian);
-unit_conversion!(Degree f64, Rotation f64, degree_to_rotation);
-unit_conversion!(Radian f64, Rotation f64, radian_to_rotation);
-
-unit_dimensional_analysis!(DegreePerSecond * Second = Degree);
-unit_dimensional_analysis!(RadianPerSecond * Second = Radian);
-unit_dimensional_analysis!(RotationPerSecond * Second = Rotation);
-unit_dimensional_analysis!(RotationPerMinute * Minute = Rotation);
+interconvert!(Degree, Radian, Rotation);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Degree::new(degree).to::<Radian>()`")]
 #[must_use]
 pub fn degree_to_radian(degree: f64) -> f64 {
-    degree.to_radians()
+    Degree::new(degree).to::<Radian>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Degree::new(degree).to::<Rotation>()`")]
 #[must_use]
 pub fn degree_to_rotation(degree: f64) -> f64 {
-    degree / 360.0
+    Degree::new(degree).to::<Rotation>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Radian::new(radian).to::<Rotation>()`")]
 #[must_use]
 pub fn radian_to_rotation(radian: f64) -> f64 {
-    degree_to_rotation(radian.to_degrees())
+    Radian::new(radian).to::<Rotation>().value()
 }
 
 impl Degree {
@@ -1,56 +1,101 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::AngularAcceleration;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(DegreePerSecondSquared, f64);
-unit!(RadianPerSecondSquared, f64);
-unit!(RotationPerSecondSquared, f64);
-unit!(RotationPerMinuteSquared, f64);
+unit!(
+    DegreePerSecondSquared,
+    f64,
+    AngularAcceleration,
+    std::f64::consts::PI / 180.0,
+    "deg/s^2"
+);
+unit!(RadianPerSecondSquared, f64, AngularAcceleration, si);
+unit!(
+    RotationPerSecondSquared,
+    f64,
+    AngularAcceleration,
+    std::f64::consts::TAU,
+    "rot/s^2"
+);
+unit!(
+    RotationPerMinuteSquared,
+    f64,
+    AngularAcceleration,
+    std::f64::consts::TAU / 3600.0,
+    "rot/min^2"
+);
 
-unit_conversion!(DegreePerSecondSquared f64, RadianPerSecondSquared f64, degree_per_second_squared_to_radian_per_second_squared);
-unit_conversion!(DegreePerSecondSquared f64, RotationPerSecondSquared f64, degree_per_second_squared_to_rotation_per_second_squared);
-unit_conversion!(DegreePerSecondSquared f64, RotationPerMinuteSquared f64, degree_per_second_squared_to_rotation_per_minute_squared);
-unit_conversion!(RadianPerSecondSquared f64, RotationPerSecondSquared f64, radian_per_second_squared_to_rotation_per_second_squared);
-unit_conversion!(RadianPerSecondSquared f64, RotationPerMinuteSquared f64, radian_per_second_squared_to_rotation_per_minute_squared);
-unit_conversion!(RotationPerSecondSquared f64, RotationPerMinuteSquared f64, rotation_per_second_squared_to_rotation_per_minute_squared);
+interconvert!(
+    DegreePerSecondSquared,
+    RadianPerSecondSquared,
+    RotationPerSecondSquared,
+    RotationPerMinuteSquared
+);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecondSquared::new(degree_per_second_squared).to::<RadianPerSecondSquared>()`"
+)]
 #[must_use]
 pub fn degree_per_second_squared_to_radian_per_second_squared(
     degree_per_second_squared: f64,
 ) -> f64 {
-    degree_per_second_squared.to_radians()
+    DegreePerSecondSquared::new(degree_per_second_squared)
+        .to::<RadianPerSecondSquared>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecondSquared::new(degree_per_second_squared).to::<RotationPerSecondSquared>()`"
+)]
 #[must_use]
 pub fn degree_per_second_squared_to_rotation_per_second_squared(
     degree_per_second_squared: f64,
 ) -> f64 {
-    degree_per_second_squared / 360.0
+    DegreePerSecondSquared::new(degree_per_second_squared)
+        .to::<RotationPerSecondSquared>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecondSquared::new(degree_per_second_squared).to::<RotationPerMinuteSquared>()`"
+)]
 #[must_use]
 pub fn degree_per_second_squared_to_rotation_per_minute_squared(
     degree_per_second_squared: f64,
 ) -> f64 {
-    degree_per_second_squared / 360.0 * 60.0
+    DegreePerSecondSquared::new(degree_per_second_squared)
+        .to::<RotationPerMinuteSquared>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RadianPerSecondSquared::new(radian_per_second_squared).to::<RotationPerSecondSquared>()`"
+)]
 #[must_use]
 pub fn radian_per_second_squared_to_rotation_per_second_squared(
     radian_per_second_squared: f64,
 ) -> f64 {
-    degree_per_second_squared_to_rotation_per_second_squared(radian_per_second_squared.to_degrees())
+    RadianPerSecondSquared::new(radian_per_second_squared)
+        .to::<RotationPerSecondSquared>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RadianPerSecondSquared::new(radian_per_second_squared).to::<RotationPerMinuteSquared>()`"
+)]
 #[must_use]
 pub fn radian_per_second_squared_to_rotation_per_minute_squared(
     radian_per_second_squared: f64,
 ) -> f64 {
-    degree_per_second_squared_to_rotation_per_minute_squared(radian_per_second_squared.to_degrees())
+    RadianPerSecondSquared::new(radian_per_second_squared)
+        .to::<RotationPerMinuteSquared>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RotationPerSecondSquared::new(rotation_per_second_squared).to::<RotationPerMinuteSquared>()`"
+)]
 #[must_use]
 pub fn rotation_per_second_squared_to_rotation_per_minute_squared(
     rotation_per_second_squared: f64,
 ) -> f64 {
-    rotation_per_second_squared * 60.0
+    RotationPerSecondSquared::new(rotation_per_second_squared)
+        .to::<RotationPerMinuteSquared>()
+        .value()
 }
@@ -1,44 +1,89 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::AngularVelocity;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(DegreePerSecond, f64);
-unit!(RadianPerSecond, f64);
-unit!(RotationPerSecond, f64);
-unit!(RotationPerMinute, f64);
+unit!(
+    DegreePerSecond,
+    f64,
+    AngularVelocity,
+    std::f64::consts::PI / 180.0,
+    "deg/s"
+);
+unit!(RadianPerSecond, f64, AngularVelocity, si);
+unit!(
+    RotationPerSecond,
+    f64,
+    AngularVelocity,
+    std::f64::consts::TAU,
+    "rps"
+);
+unit!(
+    RotationPerMinute,
+    f64,
+    AngularVelocity,
+    std::f64::consts::TAU / 60.0,
+    "rpm"
+);
 
-unit_conversion!(DegreePerSecond f64, RadianPerSecond f64, degree_per_second_to_radian_per_second);
-unit_conversion!(DegreePerSecond f64, RotationPerSecond f64, degree_per_second_to_rotation_per_second);
-unit_conversion!(DegreePerSecond f64, RotationPerMinute f64, degree_per_second_to_rotation_per_minute);
-unit_conversion!(RadianPerSecond f64, RotationPerSecond f64, radian_per_second_to_rotation_per_second);
-unit_conversion!(RadianPerSecond f64, RotationPerMinute f64, radian_per_second_to_rotation_per_minute);
-unit_conversion!(RotationPerSecond f64, RotationPerMinute f64, rotation_per_second_to_rotation_per_minute);
+interconvert!(
+    DegreePerSecond,
+    RadianPerSecond,
+    RotationPerSecond,
+    RotationPerMinute
+);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecond::new(degree_per_second).to::<RadianPerSecond>()`"
+)]
 #[must_use]
 pub fn degree_per_second_to_radian_per_second(degree_per_second: f64) -> f64 {
-    degree_per_second.to_radians()
+    DegreePerSecond::new(degree_per_second)
+        .to::<RadianPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecond::new(degree_per_second).to::<RotationPerSecond>()`"
+)]
 #[must_use]
 pub fn degree_per_second_to_rotation_per_second(degree_per_second: f64) -> f64 {
-    degree_per_second / 360.0
+    DegreePerSecond::new(degree_per_second)
+        .to::<RotationPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `DegreePerSecond::new(degree_per_second).to::<RotationPerMinute>()`"
+)]
 #[must_use]
 pub fn degree_per_second_to_rotation_per_minute(degree_per_second: f64) -> f64 {
-    degree_per_second / 360.0 * 60.0
+    DegreePerSecond::new(degree_per_second)
+        .to::<RotationPerMinute>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RadianPerSecond::new(radian_per_second).to::<RotationPerSecond>()`"
+)]
 #[must_use]
 pub fn radian_per_second_to_rotation_per_second(radian_per_second: f64) -> f64 {
-    degree_per_second_to_rotation_per_second(radian_per_second.to_degrees())
+    RadianPerSecond::new(radian_per_second)
+        .to::<RotationPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RadianPerSecond::new(radian_per_second).to::<RotationPerMinute>()`"
+)]
 #[must_use]
 pub fn radian_per_second_to_rotation_per_minute(radian_per_second: f64) -> f64 {
-    degree_per_second_to_rotation_per_minute(radian_per_second.to_degrees())
+    RadianPerSecond::new(radian_per_second)
+        .to::<RotationPerMinute>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `RotationPerSecond::new(rotation_per_second).to::<RotationPerMinute>()`"
+)]
 #[must_use]
 pub fn rotation_per_second_to_rotation_per_minute(rotation_per_second: f64) -> f64 {
-    rotation_per_second * 60.0
+    RotationPerSecond::new(rotation_per_second)
+        .to::<RotationPerMinute>()
+        .value()
 }
@@ -1,50 +1,48 @@
 use crate::math::units::data_rate::{
     BytesPerSecond, GigabytesPerHour, KilobytesPerSecond, MegabytesPerSecond,
 };
+use crate::math::units::dimension::Data;
 use crate::math::units::time::Second;
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Byte, f64);
-unit!(Kilobyte, f64);
-unit!(Megabyte, f64);
-unit!(Gigabyte, f64);
+unit!(Byte, f64, Data, si);
+unit!(Kilobyte, f64, Data, 1e3, "kB");
+unit!(Megabyte, f64, Data, 1e6, "MB");
+unit!(Gigabyte, f64, Data, 1e9, "GB");
 
-unit_conversion!(Byte f64, Kilobyte f64, byte_to_kilobyte);
-unit_conversion!(Byte f64, Megabyte f64, byte_to_megabyte);
-unit_conversion!(Byte f64, Gigabyte f64, byte_to_gigabyte);
-unit_conversion!(Kilobyte f64, Megabyte f64, kilobyte_to_megabyte);
-unit_conversion!(Kilobyte f64, Gigabyte f64, kilobyte_to_gigabyte);
-unit_conversion!(Megabyte f64, Gigabyte f64, megabyte_to_gigabyte);
+interconvert!(Byte, Kilobyte, Megabyte, Gigabyte);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Byte::new(byte).to::<Kilobyte>()`")]
 #[must_use]
 pub fn byte_to_kilobyte(byte: f64) -> f64 {
-    byte / 1000.0
+    Byte::new(byte).to::<Kilobyte>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Byte::new(byte).to::<Megabyte>()`")]
 #[must_use]
 pub fn byte_to_megabyte(byte: f64) -> f64 {
-    byte / 1_000_000.0
+    Byte::new(byte).to::<Megabyte>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Byte::new(byte).to::<Gigabyte>()`")]
 #[must_use]
 pub fn byte_to_gigabyte(byte: f64) -> f64 {
-    byte / 1_000_000_000.0
+    Byte::new(byte).to::<Gigabyte>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Kilobyte::new(kilobyte).to::<Megabyte>()`")]
 #[must_use]
 pub fn kilobyte_to_megabyte(kilobyte: f64) -> f64 {
-    kilobyte / 1000.0
+    Kilobyte::new(kilobyte).to::<Megabyte>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Kilobyte::new(kilobyte).to::<Gigabyte>()`")]
 #[must_use]
 pub fn kilobyte_to_gigabyte(kilobyte: f64) -> f64 {
-    kilobyte / 1_000_000.0
+    Kilobyte::new(kilobyte).to::<Gigabyte>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Megabyte::new(megabyte).to::<Gigabyte>()`")]
 #[must_use]
 pub fn megabyte_to_gigabyte(megabyte: f64) -> f64 {
-    megabyte / 1000.0
+    Megabyte::new(megabyte).to::<Gigabyte>().value()
 }
 
 impl Byte {
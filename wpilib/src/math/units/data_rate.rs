@@ -1,47 +1,75 @@
 use crate::math::units::data::{Byte, Gigabyte, Kilobyte, Megabyte};
+use crate::math::units::dimension::DataRate;
 use crate::math::units::time::Second;
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(BytesPerSecond, f64);
-unit!(KilobytesPerSecond, f64);
-unit!(MegabytesPerSecond, f64);
-unit!(GigabytesPerHour, f64);
+unit!(BytesPerSecond, f64, DataRate, si);
+unit!(KilobytesPerSecond, f64, DataRate, 1e3, "kB/s");
+unit!(MegabytesPerSecond, f64, DataRate, 1e6, "MB/s");
+unit!(GigabytesPerHour, f64, DataRate, 1e9 / 3600.0, "GB/h");
 
-unit_conversion!(BytesPerSecond f64, KilobytesPerSecond f64, byte_per_second_to_kilobyte_per_second);
-unit_conversion!(BytesPerSecond f64, MegabytesPerSecond f64, byte_per_second_to_megabyte_per_second);
-unit_conversion!(BytesPerSecond f64, GigabytesPerHour f64, byte_per_second_to_gigabyte_per_hour);
-unit_conversion!(KilobytesPerSecond f64, MegabytesPerSecond f64, kilobyte_per_second_to_megabyte_per_second);
-unit_conversion!(KilobytesPerSecond f64, GigabytesPerHour f64, kilobyte_per_second_to_gigabyte_per_hour);
+interconvert!(
+    BytesPerSecond,
+    KilobytesPerSecond,
+    MegabytesPerSecond,
+    GigabytesPerHour
+);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `BytesPerSecond::new(byte_per_second).to::<KilobytesPerSecond>()`"
+)]
 #[must_use]
 pub fn byte_per_second_to_kilobyte_per_second(byte_per_second: f64) -> f64 {
-    byte_per_second / 1000.0
+    BytesPerSecond::new(byte_per_second)
+        .to::<KilobytesPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `BytesPerSecond::new(byte_per_second).to::<MegabytesPerSecond>()`"
+)]
 #[must_use]
 pub fn byte_per_second_to_megabyte_per_second(byte_per_second: f64) -> f64 {
-    byte_per_second / 1_000_000.0
+    BytesPerSecond::new(byte_per_second)
+        .to::<MegabytesPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `BytesPerSecond::new(byte_per_second).to::<GigabytesPerHour>()`"
+)]
 #[must_use]
 pub fn byte_per_second_to_gigabyte_per_hour(byte_per_second: f64) -> f64 {
-    byte_per_second * 0.000_036
+    BytesPerSecond::new(byte_per_second)
+        .to::<GigabytesPerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `KilobytesPerSecond::new(kilobyte_per_second).to::<MegabytesPerSecond>()`"
+)]
 #[must_use]
 pub fn kilobyte_per_second_to_megabyte_per_second(kilobyte_per_second: f64) -> f64 {
-    kilobyte_per_second / 1000.0
+    KilobytesPerSecond::new(kilobyte_per_second)
+        .to::<MegabytesPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `KilobytesPerSecond::new(kilobyte_per_second).to::<GigabytesPerHour>()`"
+)]
 #[must_use]
 pub fn kilobyte_per_second_to_gigabyte_per_hour(kilobyte_per_second: f64) -> f64 {
-    kilobyte_per_second * 3600.0 / 1_000_000.0
+    KilobytesPerSecond::new(kilobyte_per_second)
+        .to::<GigabytesPerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `MegabytesPerSecond::new(megabyte_per_second).to::<GigabytesPerHour>()`"
+)]
 #[must_use]
 pub fn megabyte_per_second_to_gigabyte_per_hour(megabyte_per_second: f64) -> f64 {
-    megabyte_per_second * 3600.0 / 1000.0
+    MegabytesPerSecond::new(megabyte_per_second)
+        .to::<GigabytesPerHour>()
+        .value()
 }
 
 impl BytesPerSecond {
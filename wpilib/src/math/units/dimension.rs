@@ -0,0 +1,163 @@
+//! What a [`Quantity`](super::Quantity) measures, as the exponents of the base dimensions.
+
+use std::{fmt::Write, marker::PhantomData};
+
+use typenum::{Diff, Integer, Prod, Quot, Sum, N1, N2, N3, P1, P2, Z0};
+
+/// A dimension as exponents of length, mass, time, current, temperature, angle and data.
+///
+/// Multiplying quantities adds their exponents and dividing subtracts them, so a
+/// `Dimension` is only ever named to spell out a result, like [`Velocity`].
+pub struct Dimension<L, M, T, I, Th, A, B>(PhantomData<(L, M, T, I, Th, A, B)>);
+
+impl<L, M, T, I, Th, A, B> std::fmt::Debug for Dimension<L, M, T, I, Th, A, B>
+where
+    Self: Dim,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dimension({})", Self::symbol())
+    }
+}
+
+/// Something a quantity can be measured in.
+pub trait Dim {
+    /// The exponents of length, mass, time, current, temperature, angle and data.
+    fn exponents() -> [i32; 7];
+
+    /// The SI base units of the dimension, like `m·s^-1`, empty if it's dimensionless.
+    #[must_use]
+    fn symbol() -> String {
+        symbol(Self::exponents())
+    }
+}
+
+impl<L, M, T, I, Th, A, B> Dim for Dimension<L, M, T, I, Th, A, B>
+where
+    L: Integer,
+    M: Integer,
+    T: Integer,
+    I: Integer,
+    Th: Integer,
+    A: Integer,
+    B: Integer,
+{
+    fn exponents() -> [i32; 7] {
+        [L::I32, M::I32, T::I32, I::I32, Th::I32, A::I32, B::I32]
+    }
+}
+
+/// The SI base units of a dimension given by its exponents.
+pub(super) fn symbol(exponents: [i32; 7]) -> String {
+    let mut symbol = String::new();
+    for (base, exponent) in ["m", "kg", "s", "A", "K", "rad", "B"]
+        .into_iter()
+        .zip(exponents)
+    {
+        if exponent == 0 {
+            continue;
+        }
+        if !symbol.is_empty() {
+            symbol.push('·');
+        }
+        symbol.push_str(base);
+        if exponent != 1 {
+            let _ = write!(symbol, "^{exponent}");
+        }
+    }
+    symbol
+}
+
+impl<L1, M1, T1, I1, Th1, A1, B1, L2, M2, T2, I2, Th2, A2, B2>
+    std::ops::Mul<Dimension<L2, M2, T2, I2, Th2, A2, B2>> for Dimension<L1, M1, T1, I1, Th1, A1, B1>
+where
+    L1: std::ops::Add<L2>,
+    M1: std::ops::Add<M2>,
+    T1: std::ops::Add<T2>,
+    I1: std::ops::Add<I2>,
+    Th1: std::ops::Add<Th2>,
+    A1: std::ops::Add<A2>,
+    B1: std::ops::Add<B2>,
+{
+    type Output = Dimension<
+        Sum<L1, L2>,
+        Sum<M1, M2>,
+        Sum<T1, T2>,
+        Sum<I1, I2>,
+        Sum<Th1, Th2>,
+        Sum<A1, A2>,
+        Sum<B1, B2>,
+    >;
+    fn mul(self, _: Dimension<L2, M2, T2, I2, Th2, A2, B2>) -> Self::Output {
+        Dimension(PhantomData)
+    }
+}
+
+impl<L1, M1, T1, I1, Th1, A1, B1, L2, M2, T2, I2, Th2, A2, B2>
+    std::ops::Div<Dimension<L2, M2, T2, I2, Th2, A2, B2>> for Dimension<L1, M1, T1, I1, Th1, A1, B1>
+where
+    L1: std::ops::Sub<L2>,
+    M1: std::ops::Sub<M2>,
+    T1: std::ops::Sub<T2>,
+    I1: std::ops::Sub<I2>,
+    Th1: std::ops::Sub<Th2>,
+    A1: std::ops::Sub<A2>,
+    B1: std::ops::Sub<B2>,
+{
+    type Output = Dimension<
+        Diff<L1, L2>,
+        Diff<M1, M2>,
+        Diff<T1, T2>,
+        Diff<I1, I2>,
+        Diff<Th1, Th2>,
+        Diff<A1, A2>,
+        Diff<B1, B2>,
+    >;
+    fn div(self, _: Dimension<L2, M2, T2, I2, Th2, A2, B2>) -> Self::Output {
+        Dimension(PhantomData)
+    }
+}
+
+pub type Dimensionless = Dimension<Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+
+pub type Length = Dimension<P1, Z0, Z0, Z0, Z0, Z0, Z0>;
+pub type Mass = Dimension<Z0, P1, Z0, Z0, Z0, Z0, Z0>;
+pub type Time = Dimension<Z0, Z0, P1, Z0, Z0, Z0, Z0>;
+pub type Current = Dimension<Z0, Z0, Z0, P1, Z0, Z0, Z0>;
+pub type Temperature = Dimension<Z0, Z0, Z0, Z0, P1, Z0, Z0>;
+pub type Angle = Dimension<Z0, Z0, Z0, Z0, Z0, P1, Z0>;
+pub type Data = Dimension<Z0, Z0, Z0, Z0, Z0, Z0, P1>;
+
+pub type Velocity = Dimension<P1, Z0, N1, Z0, Z0, Z0, Z0>;
+pub type Acceleration = Dimension<P1, Z0, N2, Z0, Z0, Z0, Z0>;
+pub type AngularVelocity = Dimension<Z0, Z0, N1, Z0, Z0, P1, Z0>;
+pub type AngularAcceleration = Dimension<Z0, Z0, N2, Z0, Z0, P1, Z0>;
+pub type DataRate = Dimension<Z0, Z0, N1, Z0, Z0, Z0, P1>;
+
+pub type Energy = Dimension<P2, P1, N2, Z0, Z0, Z0, Z0>;
+pub type Power = Dimension<P2, P1, N3, Z0, Z0, Z0, Z0>;
+pub type Voltage = Dimension<P2, P1, N3, N1, Z0, Z0, Z0>;
+pub type Resistance = Dimension<P2, P1, N3, N2, Z0, Z0, Z0>;
+/// Energy per radian, so a torque over an angle is the work it does.
+///
+/// That keeps torque apart from energy where SI gives both `kg·m^2·s^-2`. A
+/// [`Newton`](super::force::Newton) times a [`Meter`](super::distance::Meter) is a
+/// [`Joule`](super::energy::Joule) of work, not a [`NewtonMeter`](super::torque::NewtonMeter),
+/// a torque is made directly or as work over the angle it's done through. The radian it's
+/// short of is why [`MomentOfInertia`] carries rad^-2, a torque over one leaves the rad of an
+/// angular acceleration.
+pub type Torque = Dimension<P2, P1, N2, Z0, Z0, N1, Z0>;
+/// Per radian squared, so a torque over a moment of inertia is an angular acceleration.
+pub type MomentOfInertia = Dimension<P2, P1, Z0, Z0, Z0, N2, Z0>;
+
+pub type Frequency = Quot<Dimensionless, Time>;
+pub type Force = Prod<Mass, Acceleration>;
+pub type Momentum = Prod<Mass, Velocity>;
+pub type Pressure = Quot<Force, Prod<Length, Length>>;
+/// Volts per meter per second, a feedforward's kV for a linear mechanism.
+pub type VelocityGain = Quot<Voltage, Velocity>;
+/// Volts per meter per second squared, a feedforward's kA for a linear mechanism.
+pub type AccelerationGain = Quot<Voltage, Acceleration>;
+/// Volts per radian per second, a feedforward's kV for a rotating mechanism.
+pub type AngularVelocityGain = Quot<Voltage, AngularVelocity>;
+/// Volts per radian per second squared, a feedforward's kA for a rotating mechanism.
+pub type AngularAccelerationGain = Quot<Voltage, AngularAcceleration>;
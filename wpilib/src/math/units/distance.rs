@@ -1,43 +1,46 @@
+use crate::math::units::dimension::Length;
 use crate::math::units::linear_velocity::{FeetPerSecond, MeterPerSecond};
 use crate::math::units::time::Second;
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Meter, f64);
-unit!(Feet, f64);
-unit!(Inch, f64);
-unit!(Centimeter, f64);
+unit!(Meter, f64, Length, si);
+unit!(Feet, f64, Length, 0.3048, "ft");
+unit!(Inch, f64, Length, 0.0254, "in");
+unit!(Centimeter, f64, Length, 0.01, "cm");
 
-unit_conversion!(Meter f64, Feet f64, meter_to_feet);
-unit_conversion!(Meter f64, Inch f64, meter_to_inch);
-unit_conversion!(Feet f64, Inch f64, foot_to_inch);
-unit_conversion!(Meter f64, Centimeter f64, meter_to_centimeter);
-unit_conversion!(Centimeter f64, Feet f64, centimeter_to_foot);
-unit_conversion!(Centimeter f64, Inch f64, centimeter_to_inch);
+interconvert!(Meter, Feet, Inch, Centimeter);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Meter::new(meter).to::<Feet>()`")]
 #[must_use]
 pub fn meter_to_feet(meter: f64) -> f64 {
-    meter * 3.28084
+    Meter::new(meter).to::<Feet>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Meter::new(meter).to::<Inch>()`")]
 #[must_use]
 pub fn meter_to_inch(meter: f64) -> f64 {
-    meter * 3.28084 * 12.0
+    Meter::new(meter).to::<Inch>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Feet::new(foot).to::<Inch>()`")]
 #[must_use]
 pub fn foot_to_inch(foot: f64) -> f64 {
-    foot * 12.0
+    Feet::new(foot).to::<Inch>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Meter::new(meter).to::<Centimeter>()`")]
 #[must_use]
 pub fn meter_to_centimeter(meter: f64) -> f64 {
-    meter * 100.0
+    Meter::new(meter).to::<Centimeter>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Centimeter::new(centimeter).to::<Feet>()`")]
 #[must_use]
 pub fn centimeter_to_foot(centimeter: f64) -> f64 {
-    meter_to_feet(centimeter / 100.0)
+    Centimeter::new(centimeter).to::<Feet>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Centimeter::new(centimeter).to::<Inch>()`")]
 #[must_use]
 pub fn centimeter_to_inch(centimeter: f64) -> f64 {
-    meter_to_inch(centimeter / 100.0)
+    Centimeter::new(centimeter).to::<Inch>().value()
 }
 
 impl Meter {
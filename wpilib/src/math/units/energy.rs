@@ -1,25 +1,28 @@
+use crate::math::units::dimension::{Current, Energy, Power, Resistance, Voltage};
 use crate::math::units::time::Second;
-use wpilib_macros::{unit, unit_conversion, unit_dimensional_analysis};
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Joule, f64);
-unit!(Volt, f64);
-unit!(Amp, f64);
-unit!(Watt, f64);
-unit!(WattHour, f64);
-unit!(Ohm, f64);
+unit!(Joule, f64, Energy, si);
+unit!(Volt, f64, Voltage, si);
+unit!(Amp, f64, Current, si);
+unit!(Milliamp, f64, Current, 1e-3, "mA");
+unit!(Watt, f64, Power, si);
+unit!(Horsepower, f64, Power, 745.699_871_582_270_2, "hp");
+unit!(WattHour, f64, Energy, 3600.0, "Wh");
+unit!(Ohm, f64, Resistance, si);
 
-unit_conversion!(Joule f64, WattHour f64, joule_to_watt_hour);
+interconvert!(Joule, WattHour);
+interconvert!(Amp, Milliamp);
+interconvert!(Watt, Horsepower);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Joule::new(joule).to::<WattHour>()`")]
 #[must_use]
 pub fn joule_to_watt_hour(joule: f64) -> f64 {
-    joule / 3600.0
+    Joule::new(joule).to::<WattHour>().value()
 }
 
-unit_dimensional_analysis!(Volt * Amp = Watt);
-
-unit_dimensional_analysis!(Watt * Second = Joule);
-
 impl Watt {
     #[must_use]
     pub fn to_watt_hour(&self, seconds: &Second) -> WattHour {
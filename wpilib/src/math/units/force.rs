@@ -0,0 +1,8 @@
+use crate::math::units::dimension::Force;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(Newton, f64, Force, si);
+unit!(PoundForce, f64, Force, 0.453_592_37 * 9.806_65, "lbf");
+
+interconvert!(Newton, PoundForce);
@@ -0,0 +1,8 @@
+use crate::math::units::dimension::Frequency;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(Hertz, f64, Frequency, si);
+unit!(Kilohertz, f64, Frequency, 1e3, "kHz");
+
+interconvert!(Hertz, Kilohertz);
@@ -0,0 +1,15 @@
+use crate::math::units::dimension::{
+    AccelerationGain, AngularAccelerationGain, AngularVelocityGain, VelocityGain,
+};
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(VoltPerMeterPerSecond, f64, VelocityGain, si);
+unit!(VoltPerMeterPerSecondSquared, f64, AccelerationGain, si);
+unit!(VoltPerRadianPerSecond, f64, AngularVelocityGain, si);
+unit!(
+    VoltPerRadianPerSecondSquared,
+    f64,
+    AngularAccelerationGain,
+    si
+);
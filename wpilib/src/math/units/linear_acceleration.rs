@@ -0,0 +1,9 @@
+use crate::math::units::dimension::Acceleration;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(MeterPerSecondSquared, f64, Acceleration, si);
+unit!(FeetPerSecondSquared, f64, Acceleration, 0.3048, "ft/s^2");
+unit!(Gs, f64, Acceleration, 9.806_65, "G");
+
+interconvert!(MeterPerSecondSquared, FeetPerSecondSquared, Gs);
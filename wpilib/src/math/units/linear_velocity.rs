@@ -1,63 +1,82 @@
+use crate::math::units::dimension::Velocity;
 use crate::math::units::distance::{Feet, Meter};
 use crate::math::units::time::Second;
-use wpilib_macros::{unit, unit_conversion, unit_dimensional_analysis};
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(MeterPerSecond, f64);
-unit!(KilometerPerHour, f64);
-unit!(MilePerHour, f64);
-unit!(FeetPerSecond, f64);
+unit!(MeterPerSecond, f64, Velocity, si);
+unit!(KilometerPerHour, f64, Velocity, 1.0 / 3.6, "km/h");
+unit!(MilePerHour, f64, Velocity, 0.447_04, "mph");
+unit!(FeetPerSecond, f64, Velocity, 0.3048, "ft/s");
 
-unit_conversion!(MeterPerSecond f64, KilometerPerHour f64, meter_per_second_to_kilometer_per_hour);
-unit_conversion!(MeterPerSecond f64, MilePerHour f64, meter_per_second_to_mile_per_hour);
-unit_conversion!(MeterPerSecond f64, FeetPerSecond f64, meter_per_second_to_feet_per_second);
-unit_conversion!(FeetPerSecond f64, MilePerHour f64, feet_per_second_to_mile_per_hour);
-unit_conversion!(FeetPerSecond f64, KilometerPerHour f64, feet_per_second_to_kilometer_per_hour);
-unit_conversion!(MilePerHour f64, KilometerPerHour f64, mile_per_hour_to_kilometer_per_hour);
+interconvert!(MeterPerSecond, KilometerPerHour, MilePerHour, FeetPerSecond);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `MeterPerSecond::new(meter_per_second).to::<KilometerPerHour>()`"
+)]
 #[must_use]
 pub fn meter_per_second_to_kilometer_per_hour(meter_per_second: f64) -> f64 {
-    meter_per_second * 3.6
+    MeterPerSecond::new(meter_per_second)
+        .to::<KilometerPerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `MeterPerSecond::new(meter_per_second).to::<MilePerHour>()`"
+)]
 #[must_use]
 pub fn meter_per_second_to_mile_per_hour(meter_per_second: f64) -> f64 {
-    meter_per_second * 2.23694
+    MeterPerSecond::new(meter_per_second)
+        .to::<MilePerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `MeterPerSecond::new(meter_per_second).to::<FeetPerSecond>()`"
+)]
 #[must_use]
 pub fn meter_per_second_to_feet_per_second(meter_per_second: f64) -> f64 {
-    meter_per_second * 3.28084
+    MeterPerSecond::new(meter_per_second)
+        .to::<FeetPerSecond>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `FeetPerSecond::new(feet_per_second).to::<MilePerHour>()`"
+)]
 #[must_use]
 pub fn feet_per_second_to_mile_per_hour(feet_per_second: f64) -> f64 {
-    meter_per_second_to_mile_per_hour(feet_per_second / 3.28084)
+    FeetPerSecond::new(feet_per_second)
+        .to::<MilePerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `FeetPerSecond::new(feet_per_second).to::<KilometerPerHour>()`"
+)]
 #[must_use]
 pub fn feet_per_second_to_kilometer_per_hour(feet_per_second: f64) -> f64 {
-    meter_per_second_to_kilometer_per_hour(feet_per_second / 3.28084)
+    FeetPerSecond::new(feet_per_second)
+        .to::<KilometerPerHour>()
+        .value()
 }
-
+#[deprecated(
+    note = "use `Unit::to`, e.g. `MilePerHour::new(mile_per_hour).to::<KilometerPerHour>()`"
+)]
 #[must_use]
 pub fn mile_per_hour_to_kilometer_per_hour(mile_per_hour: f64) -> f64 {
-    meter_per_second_to_kilometer_per_hour(mile_per_hour / 2.23694)
+    MilePerHour::new(mile_per_hour)
+        .to::<KilometerPerHour>()
+        .value()
 }
 
-unit_dimensional_analysis!(Meter / Second = MeterPerSecond);
-unit_dimensional_analysis!(Feet / Second = FeetPerSecond);
-
 impl MilePerHour {
     #[must_use]
     pub fn to_feet(&self, seconds: Second) -> Feet {
-        Feet::new(FeetPerSecond::from(*self).value() * seconds.value())
+        Feet::new(self.to::<FeetPerSecond>().value() * seconds.value())
     }
 }
 
 impl KilometerPerHour {
     #[must_use]
     pub fn to_meters(&self, seconds: Second) -> Meter {
-        Meter::new(MeterPerSecond::from(*self).value() * seconds.value())
+        Meter::new(self.to::<MeterPerSecond>().value() * seconds.value())
     }
 }
@@ -1,39 +1,42 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::Mass;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Kilogram, f64);
-unit!(Gram, f64);
-unit!(Pound, f64);
-unit!(Ounce, f64);
+unit!(Kilogram, f64, Mass, si);
+unit!(Gram, f64, Mass, 1e-3, "g");
+unit!(Pound, f64, Mass, 0.453_592_37, "lb");
+unit!(Ounce, f64, Mass, 0.453_592_37 / 16.0, "oz");
 
-unit_conversion!(Kilogram f64, Gram f64, kilogram_to_gram);
-unit_conversion!(Kilogram f64, Pound f64, kilogram_to_pound);
-unit_conversion!(Kilogram f64, Ounce f64, kilogram_to_ounce);
-unit_conversion!(Gram f64, Pound f64, gram_to_pound);
-unit_conversion!(Gram f64, Ounce f64, gram_to_ounce);
-unit_conversion!(Pound f64, Ounce f64, pound_to_ounce);
+interconvert!(Kilogram, Gram, Pound, Ounce);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Kilogram::new(kilogram).to::<Gram>()`")]
 #[must_use]
 pub fn kilogram_to_gram(kilogram: f64) -> f64 {
-    kilogram * 1000.0
+    Kilogram::new(kilogram).to::<Gram>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Kilogram::new(kilogram).to::<Pound>()`")]
 #[must_use]
 pub fn kilogram_to_pound(kilogram: f64) -> f64 {
-    kilogram * 2.20462
+    Kilogram::new(kilogram).to::<Pound>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Kilogram::new(kilogram).to::<Ounce>()`")]
 #[must_use]
 pub fn kilogram_to_ounce(kilogram: f64) -> f64 {
-    kilogram * 35.274
+    Kilogram::new(kilogram).to::<Ounce>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Gram::new(gram).to::<Pound>()`")]
 #[must_use]
 pub fn gram_to_pound(gram: f64) -> f64 {
-    kilogram_to_pound(gram / 1000.0)
+    Gram::new(gram).to::<Pound>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Gram::new(gram).to::<Ounce>()`")]
 #[must_use]
 pub fn gram_to_ounce(gram: f64) -> f64 {
-    kilogram_to_ounce(gram / 1000.0)
+    Gram::new(gram).to::<Ounce>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Pound::new(pound).to::<Ounce>()`")]
 #[must_use]
 pub fn pound_to_ounce(pound: f64) -> f64 {
-    kilogram_to_ounce(pound / 2.20462)
+    Pound::new(pound).to::<Ounce>().value()
 }
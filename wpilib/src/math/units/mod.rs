@@ -1,14 +1,119 @@
+//lets every pair of units convert with `From`, they have to share a dimension
+macro_rules! interconvert {
+    ($first:ident $(, $rest:ident)* $(,)?) => {
+        $(
+            impl From<$first> for $rest {
+                fn from(value: $first) -> Self {
+                    crate::math::units::Unit::to(value)
+                }
+            }
+            impl From<&$first> for $rest {
+                fn from(value: &$first) -> Self {
+                    crate::math::units::Unit::to(*value)
+                }
+            }
+            impl From<$rest> for $first {
+                fn from(value: $rest) -> Self {
+                    crate::math::units::Unit::to(value)
+                }
+            }
+            impl From<&$rest> for $first {
+                fn from(value: &$rest) -> Self {
+                    crate::math::units::Unit::to(*value)
+                }
+            }
+        )*
+        interconvert!($($rest),*);
+    };
+    () => {};
+}
+
 pub mod angle;
 pub mod angular_acceleration;
 pub mod angular_velocity;
 pub mod data;
 pub mod data_rate;
+pub mod dimension;
 pub mod distance;
 pub mod energy;
+pub mod force;
+pub mod frequency;
+pub mod gain;
+pub mod linear_acceleration;
 pub mod linear_velocity;
 pub mod mass;
 pub mod moment_of_inertia;
+pub mod momentum;
+pub mod parse;
+pub mod pressure;
+mod quantity;
+pub mod ratio;
+mod symbol;
 pub mod temperature;
+#[cfg(test)]
 mod test;
 pub mod time;
 pub mod torque;
+
+pub use quantity::{Quantity, Scale, Si, Value};
+
+/// A unit of measure in a [`dimension`], every [`Quantity`] is one.
+///
+/// Values go through the dimension's SI base unit, so every unit converts to every other
+/// unit of its dimension and converting between dimensions doesn't compile.
+/// ```ignore
+/// let length: Centimeter = Inch::new(12.0).to();
+/// assert_eq!(length, Feet::new(1.0));
+/// ```
+/// ```ignore
+/// //doesn't compile, a length isn't a time
+/// let time: Second = Meter::new(1.0).to();
+/// ```
+pub trait Unit: Copy {
+    type Dimension;
+    /// How many of the SI base unit one of this unit is.
+    const SCALE: f64;
+    /// Where zero of this unit is in the SI base unit, only temperatures don't share it.
+    const OFFSET: f64;
+
+    /// The value in this unit.
+    fn get_raw(&self) -> f64;
+    /// A value in this unit.
+    fn from_raw(value: f64) -> Self;
+
+    /// The value in the dimension's SI base unit.
+    #[must_use]
+    fn to_si(&self) -> f64 {
+        self.get_raw().mul_add(Self::SCALE, Self::OFFSET)
+    }
+
+    #[must_use]
+    fn from_si(value: f64) -> Self {
+        Self::from_raw((value - Self::OFFSET) / Self::SCALE)
+    }
+
+    /// The same quantity in another unit of the dimension.
+    #[must_use]
+    fn to<U: Unit<Dimension = Self::Dimension>>(self) -> U {
+        //scaling by the ratio keeps a unit converting to itself exact
+        U::from_raw(self.get_raw().mul_add(
+            Self::SCALE / U::SCALE,
+            (Self::OFFSET - U::OFFSET) / U::SCALE,
+        ))
+    }
+}
+
+//plain numbers are dimensionless, so things generic over units can default to them
+impl Unit for f64 {
+    type Dimension = dimension::Dimensionless;
+    const SCALE: Self = 1.0;
+    const OFFSET: Self = 0.0;
+
+    fn get_raw(&self) -> Self {
+        *self
+    }
+
+    fn from_raw(value: Self) -> Self {
+        value
+    }
+}
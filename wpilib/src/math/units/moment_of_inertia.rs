@@ -1,12 +1,25 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::MomentOfInertia;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(KilogramSquareMeter, f64);
-unit!(PoundSquareFoot, f64);
+unit!(KilogramSquareMeter, f64, MomentOfInertia, si);
+unit!(
+    PoundSquareFoot,
+    f64,
+    MomentOfInertia,
+    0.453_592_37 * 0.3048 * 0.3048,
+    "lb·ft^2"
+);
 
-unit_conversion!(KilogramSquareMeter f64, PoundSquareFoot f64, kilogram_square_meter_to_pound_square_foot);
+interconvert!(KilogramSquareMeter, PoundSquareFoot);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `KilogramSquareMeter::new(kilogram_square_meter).to::<PoundSquareFoot>()`"
+)]
 #[must_use]
 pub fn kilogram_square_meter_to_pound_square_foot(kilogram_square_meter: f64) -> f64 {
-    kilogram_square_meter * 0.204_816_143_622_5
+    KilogramSquareMeter::new(kilogram_square_meter)
+        .to::<PoundSquareFoot>()
+        .value()
 }
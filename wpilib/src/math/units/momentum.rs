@@ -0,0 +1,5 @@
+use crate::math::units::dimension::Momentum;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(KilogramMeterPerSecond, f64, Momentum, si);
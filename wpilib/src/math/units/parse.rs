@@ -0,0 +1,243 @@
+//! Reading quantities from strings like `"12.5 in"`, `"90deg"` or `"1.2 m/s"`.
+//!
+//! A unit is a registered symbol or a product of them, like `m/s^2` or `V·s/m`. What
+//! comes out round-trips through `Display`.
+
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+    dimension::{self, Dim},
+    symbol::{self, Symbol},
+    Quantity, Scale, Value,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseUnitError {
+    #[error("{0:?} doesn't start with a number")]
+    MissingValue(String),
+    #[error("invalid number {0:?}")]
+    InvalidValue(String),
+    #[error("unknown unit {0:?}")]
+    UnknownUnit(String),
+    #[error("invalid exponent in {0:?}")]
+    InvalidExponent(String),
+    #[error("{0} doesn't start at zero so it can't be combined with other units")]
+    OffsetInCompound(String),
+    #[error("dimension mismatch: expected {expected}, found {found}")]
+    DimensionMismatch { expected: String, found: String },
+}
+
+impl ParseUnitError {
+    fn mismatch(expected: [i32; 7], found: [i32; 7]) -> Self {
+        let describe = |exponents| {
+            let symbol = dimension::symbol(exponents);
+            if symbol.is_empty() {
+                String::from("dimensionless")
+            } else {
+                symbol
+            }
+        };
+        Self::DimensionMismatch {
+            expected: describe(expected),
+            found: describe(found),
+        }
+    }
+}
+
+/// A quantity whose dimension is only known at runtime, for when a string could be in
+/// any unit.
+///
+/// It turns into a unit with `try_into`, which checks the dimension:
+/// ```ignore
+/// let quantity: DynamicQuantity = "3000 rpm".parse()?;
+/// let speed: RadianPerSecond = quantity.try_into()?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynamicQuantity {
+    value: f64,
+    unit: Symbol,
+}
+
+impl DynamicQuantity {
+    /// The value in the dimension's SI base unit.
+    #[must_use]
+    pub const fn to_si(&self) -> f64 {
+        self.value.mul_add(self.unit.scale, self.unit.offset)
+    }
+
+    /// The exponents of length, mass, time, current, temperature, angle and data.
+    #[must_use]
+    pub const fn dimension(&self) -> [i32; 7] {
+        self.unit.dimension
+    }
+
+    /// Whether it's a quantity of `D`.
+    #[must_use]
+    pub fn is<D: Dim>(&self) -> bool {
+        self.unit.dimension == D::exponents()
+    }
+}
+
+impl Display for DynamicQuantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = dimension::symbol(self.unit.dimension);
+        if symbol.is_empty() {
+            write!(f, "{}", self.to_si())
+        } else {
+            write!(f, "{} {symbol}", self.to_si())
+        }
+    }
+}
+
+impl FromStr for DynamicQuantity {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (value, unit) = s.split_at(number_len(s));
+        if value.is_empty() {
+            return Err(ParseUnitError::MissingValue(s.to_owned()));
+        }
+        Ok(Self {
+            value: value
+                .parse()
+                .map_err(|_| ParseUnitError::InvalidValue(value.to_owned()))?,
+            unit: parse_unit(unit.trim())?,
+        })
+    }
+}
+
+impl<D: Dim, S: Scale, T: Value> TryFrom<DynamicQuantity> for Quantity<D, S, T> {
+    type Error = ParseUnitError;
+
+    fn try_from(quantity: DynamicQuantity) -> Result<Self, Self::Error> {
+        if !quantity.is::<D>() {
+            return Err(ParseUnitError::mismatch(
+                D::exponents(),
+                quantity.unit.dimension,
+            ));
+        }
+        //scaling by the ratio keeps a value written in this unit exact
+        Ok(Self::new(T::from_f64(quantity.value.mul_add(
+            quantity.unit.scale / S::SCALE,
+            (quantity.unit.offset - S::OFFSET) / S::SCALE,
+        ))))
+    }
+}
+
+impl<D: Dim, S: Scale, T: Value> FromStr for Quantity<D, S, T> {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<DynamicQuantity>()?.try_into()
+    }
+}
+
+//how much of the start of a string is a number, an exponent only counts if digits follow it
+//so `5em` isn't cut short
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    while matches!(bytes.get(end), Some(b'0'..=b'9' | b'.')) {
+        end += 1;
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let mut exponent = end + 1;
+        if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+            exponent += 1;
+        }
+        if matches!(bytes.get(exponent), Some(b'0'..=b'9')) {
+            end = exponent;
+            while matches!(bytes.get(end), Some(b'0'..=b'9')) {
+                end += 1;
+            }
+        }
+    }
+    end
+}
+
+fn parse_unit(unit: &str) -> Result<Symbol, ParseUnitError> {
+    if unit.is_empty() {
+        return Ok(Symbol {
+            dimension: [0; 7],
+            scale: 1.0,
+            offset: 0.0,
+        });
+    }
+    if let Some(symbol) = symbol::lookup(unit) {
+        return Ok(symbol);
+    }
+
+    let mut product = Symbol {
+        dimension: [0; 7],
+        scale: 1.0,
+        offset: 0.0,
+    };
+    let mut sign = 1;
+    let mut rest = unit;
+    loop {
+        let end = rest.find(['·', '*', '/']).unwrap_or(rest.len());
+        let term = rest[..end].trim();
+        let (name, exponent) = match term.split_once('^') {
+            Some((name, exponent)) => (
+                name,
+                exponent
+                    .parse::<i32>()
+                    .map_err(|_| ParseUnitError::InvalidExponent(term.to_owned()))?,
+            ),
+            None => (term, 1),
+        };
+        let symbol =
+            symbol::lookup(name).ok_or_else(|| ParseUnitError::UnknownUnit(name.to_owned()))?;
+        if symbol.offset != 0.0 {
+            return Err(ParseUnitError::OffsetInCompound(name.to_owned()));
+        }
+        let exponent = sign * exponent;
+        product.scale *= symbol.scale.powi(exponent);
+        for (total, base) in product.dimension.iter_mut().zip(symbol.dimension) {
+            *total += exponent * base;
+        }
+
+        let Some(separator) = rest[end..].chars().next() else {
+            return Ok(product);
+        };
+        sign = if separator == '/' { -1 } else { 1 };
+        rest = &rest[end + separator.len_utf8()..];
+    }
+}
+
+/// Serializes a quantity as the string it displays as rather than its bare value, for
+/// files people edit by hand:
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "wpilib::math::units::parse::as_string")]
+///     height: Inch,
+/// }
+/// ```
+pub mod as_string {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// # Errors
+    /// Fails if the serializer does.
+    pub fn serialize<Q: Display, S: Serializer>(
+        quantity: &Q,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(quantity)
+    }
+
+    /// # Errors
+    /// Fails if the string isn't a quantity in the right dimension.
+    pub fn deserialize<'de, Q, D>(deserializer: D) -> Result<Q, D::Error>
+    where
+        Q: FromStr,
+        Q::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
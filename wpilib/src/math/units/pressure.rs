@@ -0,0 +1,16 @@
+use crate::math::units::dimension::Pressure;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(Pascal, f64, Pressure, si);
+unit!(Kilopascal, f64, Pressure, 1e3, "kPa");
+unit!(
+    PSI,
+    f64,
+    Pressure,
+    0.453_592_37 * 9.806_65 / (0.0254 * 0.0254),
+    "psi"
+);
+unit!(Bar, f64, Pressure, 1e5, "bar");
+
+interconvert!(Pascal, Kilopascal, PSI, Bar);
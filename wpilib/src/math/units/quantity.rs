@@ -0,0 +1,680 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+use typenum::{Prod, Quot};
+
+use super::{
+    dimension::{Dim, Dimensionless},
+    Unit,
+};
+
+/// How a unit relates to the SI base unit of its dimension, implemented by `unit!`.
+pub trait Scale {
+    /// How many of the SI base unit one of the unit is.
+    const SCALE: f64;
+    /// Where zero of the unit is in the SI base unit, only temperatures don't share it.
+    const OFFSET: f64 = 0.0;
+
+    /// What values of the unit are written with.
+    fn symbol<D: Dim>() -> String;
+}
+
+/// The SI base unit of whatever dimension a quantity is in, products and quotients are
+/// always in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Si;
+
+impl Scale for Si {
+    const SCALE: f64 = 1.0;
+
+    fn symbol<D: Dim>() -> String {
+        D::symbol()
+    }
+}
+
+/// A number a quantity can hold.
+pub trait Value:
+    Copy + PartialOrd + Debug + Display + Add<Output = Self> + Sub<Output = Self>
+{
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Value for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Value for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(value: f64) -> Self {
+        value as Self
+    }
+}
+
+impl Value for i64 {
+    #[allow(clippy::cast_precision_loss)]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    //integer units round to their nearest value rather than truncating
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_f64(value: f64) -> Self {
+        value.round() as Self
+    }
+}
+
+/// A value of dimension `D` in the unit `S`.
+///
+/// Units are aliases of it made with `unit!`, the SI ones all share [`Si`] so that
+/// products and quotients come out as them:
+/// ```ignore
+/// let acceleration: MeterPerSecondSquared = Meter::new(9.8) / Second::new(1.0) / Second::new(1.0);
+/// let alpha: RadianPerSecondSquared = NewtonMeter::new(2.0) / KilogramSquareMeter::new(0.5);
+/// ```
+pub struct Quantity<D, S = Si, T = f64> {
+    pub(super) value: T,
+    unit: PhantomData<fn() -> (D, S)>,
+}
+
+impl<D, S, T: Value> Quantity<D, S, T> {
+    #[inline]
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn value(&self) -> T {
+        self.value
+    }
+
+    #[inline]
+    pub const fn set(&mut self, value: T) {
+        self.value = value;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn map(&self, f: impl FnOnce(T) -> T) -> Self {
+        Self::new(f(self.value))
+    }
+}
+
+impl<D, S: Scale, T: Value> Quantity<D, S, T> {
+    #[inline]
+    #[must_use]
+    pub fn square(&self) -> Quantity<Prod<D, D>, Si>
+    where
+        D: Mul<D>,
+    {
+        *self * *self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn cube(&self) -> Quantity<Prod<Prod<D, D>, D>, Si>
+    where
+        D: Mul<D>,
+        Prod<D, D>: Mul<D>,
+    {
+        self.square() * *self
+    }
+}
+
+impl<D, S: Scale, T: Value> Unit for Quantity<D, S, T> {
+    type Dimension = D;
+    const SCALE: f64 = S::SCALE;
+    const OFFSET: f64 = S::OFFSET;
+
+    #[inline]
+    fn get_raw(&self) -> f64 {
+        self.value.to_f64()
+    }
+
+    #[inline]
+    fn from_raw(value: f64) -> Self {
+        Self::new(T::from_f64(value))
+    }
+}
+
+impl<D, S, T: Clone> Clone for Quantity<D, S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            unit: PhantomData,
+        }
+    }
+}
+
+impl<D, S, T: Copy> Copy for Quantity<D, S, T> {}
+
+impl<D: Dim, S: Scale, T: Value> Debug for Quantity<D, S, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<D: Dim, S: Scale, T: Value> Quantity<D, S, T> {
+    /// What values are written with, like `in` or `m·s^-1`, empty if it's dimensionless.
+    #[must_use]
+    pub fn symbol() -> String {
+        S::symbol::<D>()
+    }
+}
+
+impl<D: Dim, S: Scale, T: Value> Display for Quantity<D, S, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = Self::symbol();
+        if symbol.is_empty() {
+            write!(f, "{}", self.value)
+        } else {
+            write!(f, "{} {symbol}", self.value)
+        }
+    }
+}
+
+//sums and differences of the same dimension come out in the left hand unit
+macro_rules! same_dimension_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl<D, S: Scale, T: Value, S2: Scale, T2: Value> $trait<Quantity<D, S2, T2>>
+            for Quantity<D, S, T>
+        {
+            type Output = Self;
+            #[inline]
+            fn $method(self, rhs: Quantity<D, S2, T2>) -> Self::Output {
+                Self::new(self.value $op rhs.to::<Self>().value)
+            }
+        }
+        impl<D, S: Scale, T: Value, S2: Scale, T2: Value> $trait<&Quantity<D, S2, T2>>
+            for Quantity<D, S, T>
+        {
+            type Output = Self;
+            #[inline]
+            fn $method(self, rhs: &Quantity<D, S2, T2>) -> Self::Output {
+                self $op *rhs
+            }
+        }
+        impl<D, S: Scale, T: Value, S2: Scale, T2: Value> $trait<Quantity<D, S2, T2>>
+            for &Quantity<D, S, T>
+        {
+            type Output = Quantity<D, S, T>;
+            #[inline]
+            fn $method(self, rhs: Quantity<D, S2, T2>) -> Self::Output {
+                *self $op rhs
+            }
+        }
+        impl<D, S: Scale, T: Value, S2: Scale, T2: Value> $trait<&Quantity<D, S2, T2>>
+            for &Quantity<D, S, T>
+        {
+            type Output = Quantity<D, S, T>;
+            #[inline]
+            fn $method(self, rhs: &Quantity<D, S2, T2>) -> Self::Output {
+                *self $op *rhs
+            }
+        }
+        impl<D, S: Scale, T: Value, S2: Scale, T2: Value> $assign_trait<Quantity<D, S2, T2>>
+            for Quantity<D, S, T>
+        {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Quantity<D, S2, T2>) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+same_dimension_op!(Add, add, AddAssign, add_assign, +);
+same_dimension_op!(Sub, sub, SubAssign, sub_assign, -);
+
+impl<D, S: Scale, T: Value + Rem<Output = T>, S2: Scale, T2: Value> Rem<Quantity<D, S2, T2>>
+    for Quantity<D, S, T>
+{
+    type Output = Self;
+    #[inline]
+    fn rem(self, rhs: Quantity<D, S2, T2>) -> Self::Output {
+        Self::new(self.value % rhs.to::<Self>().value)
+    }
+}
+
+impl<D, S: Scale, T: Value + Rem<Output = T>, S2: Scale, T2: Value> RemAssign<Quantity<D, S2, T2>>
+    for Quantity<D, S, T>
+{
+    #[inline]
+    fn rem_assign(&mut self, rhs: Quantity<D, S2, T2>) {
+        *self = *self % rhs;
+    }
+}
+
+impl<D, S, T: Value + Neg<Output = T>> Neg for Quantity<D, S, T> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self::new(-self.value)
+    }
+}
+
+//products and quotients of any two quantities, their dimensions combine and they come out in SI
+macro_rules! dimension_op {
+    ($trait:ident, $method:ident, $result:ident, $op:tt) => {
+        impl<D: $trait<D2>, S: Scale, T: Value, D2, S2: Scale, T2: Value>
+            $trait<Quantity<D2, S2, T2>> for Quantity<D, S, T>
+        {
+            type Output = Quantity<$result<D, D2>, Si>;
+            #[inline]
+            fn $method(self, rhs: Quantity<D2, S2, T2>) -> Self::Output {
+                Quantity::new(self.to_si() $op rhs.to_si())
+            }
+        }
+        impl<D: $trait<D2>, S: Scale, T: Value, D2, S2: Scale, T2: Value>
+            $trait<&Quantity<D2, S2, T2>> for Quantity<D, S, T>
+        {
+            type Output = Quantity<$result<D, D2>, Si>;
+            #[inline]
+            fn $method(self, rhs: &Quantity<D2, S2, T2>) -> Self::Output {
+                self $op *rhs
+            }
+        }
+        impl<D: $trait<D2>, S: Scale, T: Value, D2, S2: Scale, T2: Value>
+            $trait<Quantity<D2, S2, T2>> for &Quantity<D, S, T>
+        {
+            type Output = Quantity<$result<D, D2>, Si>;
+            #[inline]
+            fn $method(self, rhs: Quantity<D2, S2, T2>) -> Self::Output {
+                *self $op rhs
+            }
+        }
+        impl<D: $trait<D2>, S: Scale, T: Value, D2, S2: Scale, T2: Value>
+            $trait<&Quantity<D2, S2, T2>> for &Quantity<D, S, T>
+        {
+            type Output = Quantity<$result<D, D2>, Si>;
+            #[inline]
+            fn $method(self, rhs: &Quantity<D2, S2, T2>) -> Self::Output {
+                *self $op *rhs
+            }
+        }
+    };
+}
+
+dimension_op!(Mul, mul, Prod, *);
+dimension_op!(Div, div, Quot, /);
+
+//scaling by a plain number keeps the unit
+impl<D, S, T: Value + Mul<Output = T>> Mul<T> for Quantity<D, S, T> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.value * rhs)
+    }
+}
+
+impl<D, S, T: Value + Div<Output = T>> Div<T> for Quantity<D, S, T> {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.value / rhs)
+    }
+}
+
+impl<D, S, T: Value + Mul<Output = T>> MulAssign<T> for Quantity<D, S, T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        self.value = self.value * rhs;
+    }
+}
+
+impl<D, S, T: Value + Div<Output = T>> DivAssign<T> for Quantity<D, S, T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: T) {
+        self.value = self.value / rhs;
+    }
+}
+
+//scaling in place by a dimensionless quantity keeps the unit too, anything else would change it
+impl<D, S, T: Value, S2: Scale, T2: Value> MulAssign<Quantity<Dimensionless, S2, T2>>
+    for Quantity<D, S, T>
+{
+    #[inline]
+    fn mul_assign(&mut self, rhs: Quantity<Dimensionless, S2, T2>) {
+        self.value = T::from_f64(self.value.to_f64() * rhs.to_si());
+    }
+}
+
+impl<D, S, T: Value, S2: Scale, T2: Value> DivAssign<Quantity<Dimensionless, S2, T2>>
+    for Quantity<D, S, T>
+{
+    #[inline]
+    fn div_assign(&mut self, rhs: Quantity<Dimensionless, S2, T2>) {
+        self.value = T::from_f64(self.value.to_f64() / rhs.to_si());
+    }
+}
+
+impl<D, S> Mul<Quantity<D, S>> for f64 {
+    type Output = Quantity<D, S>;
+    #[inline]
+    fn mul(self, rhs: Quantity<D, S>) -> Self::Output {
+        Quantity::new(self * rhs.value)
+    }
+}
+
+impl<D, S: Scale, T: Value> Div<Quantity<D, S, T>> for f64
+where
+    Dimensionless: Div<D>,
+{
+    type Output = Quantity<Quot<Dimensionless, D>, Si>;
+    #[inline]
+    fn div(self, rhs: Quantity<D, S, T>) -> Self::Output {
+        Quantity::new(self / rhs.to_si())
+    }
+}
+
+//units of the same scale compare as they are, others compare in the SI base unit
+impl<D, S: Scale, T: Value, S2: Scale, T2: Value> PartialEq<Quantity<D, S2, T2>>
+    for Quantity<D, S, T>
+{
+    fn eq(&self, other: &Quantity<D, S2, T2>) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl<D, S: Scale, T: Value, S2: Scale, T2: Value> PartialOrd<Quantity<D, S2, T2>>
+    for Quantity<D, S, T>
+{
+    fn partial_cmp(&self, other: &Quantity<D, S2, T2>) -> Option<Ordering> {
+        if S::SCALE.to_bits() == S2::SCALE.to_bits() && S::OFFSET.to_bits() == S2::OFFSET.to_bits()
+        {
+            self.get_raw().partial_cmp(&other.get_raw())
+        } else {
+            self.to_si().partial_cmp(&other.to_si())
+        }
+    }
+}
+
+impl<D, S: Scale, T: Value + num::Zero> num::Zero for Quantity<D, S, T> {
+    fn zero() -> Self {
+        Self::new(T::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+}
+
+//a dimensionless SI quantity is closed under multiplication and division, so it's a number
+//in its own right and works as a nalgebra scalar
+type Scalar = Quantity<Dimensionless>;
+
+impl num::One for Scalar {
+    fn one() -> Self {
+        Self::new(1.0)
+    }
+    fn is_one(&self) -> bool {
+        self.value.is_one()
+    }
+}
+
+impl num::Num for Scalar {
+    type FromStrRadixErr = <f64 as num::Num>::FromStrRadixErr;
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        f64::from_str_radix(str, radix).map(Self::new)
+    }
+}
+
+impl nalgebra::SimdValue for Scalar {
+    type Element = Self;
+    type SimdBool = bool;
+
+    #[inline]
+    fn lanes() -> usize {
+        1
+    }
+    #[inline]
+    fn splat(val: Self::Element) -> Self {
+        val
+    }
+    #[inline]
+    fn extract(&self, _: usize) -> Self::Element {
+        *self
+    }
+    #[inline]
+    unsafe fn extract_unchecked(&self, _: usize) -> Self::Element {
+        *self
+    }
+    #[inline]
+    fn replace(&mut self, _: usize, val: Self::Element) {
+        *self = val;
+    }
+    #[inline]
+    unsafe fn replace_unchecked(&mut self, _: usize, val: Self::Element) {
+        *self = val;
+    }
+    #[inline]
+    fn select(self, cond: Self::SimdBool, other: Self) -> Self {
+        if cond {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+impl nalgebra::Field for Scalar {}
+
+impl simba::scalar::SubsetOf<Self> for Scalar {
+    #[inline]
+    fn to_superset(&self) -> Self {
+        *self
+    }
+    #[inline]
+    fn from_superset_unchecked(element: &Self) -> Self {
+        *element
+    }
+    #[inline]
+    fn is_in_subset(_element: &Self) -> bool {
+        true
+    }
+}
+
+impl simba::scalar::SubsetOf<Scalar> for f64 {
+    #[inline]
+    fn to_superset(&self) -> Scalar {
+        Scalar::new(*self)
+    }
+    #[inline]
+    fn from_superset_unchecked(element: &Scalar) -> Self {
+        element.value
+    }
+    #[inline]
+    fn is_in_subset(_element: &Scalar) -> bool {
+        true
+    }
+}
+
+//every method is f64's on the value
+macro_rules! complex_field_methods {
+    ($($method:ident($($arg:ident: $arg_type:tt),*) -> $output:tt;)*) => {
+        $(
+            #[inline]
+            fn $method(self $(, $arg: $arg_type)*) -> complex_field_methods!(@output $output) {
+                complex_field_methods!(@wrap $output, nalgebra::ComplexField::$method(self.value $(, complex_field_methods!(@arg $arg_type, $arg))*))
+            }
+        )*
+    };
+    (@output Self) => { Self };
+    (@output Real) => { f64 };
+    (@wrap Self, $value:expr) => { Self::new($value) };
+    (@wrap Real, $value:expr) => { $value };
+    (@arg Self, $arg:ident) => { $arg.value };
+    (@arg $arg_type:tt, $arg:ident) => { $arg };
+}
+
+impl nalgebra::ComplexField for Scalar {
+    type RealField = f64;
+
+    complex_field_methods! {
+        real() -> Real;
+        imaginary() -> Real;
+        modulus() -> Real;
+        modulus_squared() -> Real;
+        argument() -> Real;
+        norm1() -> Real;
+        abs() -> Real;
+        hypot(other: Self) -> Real;
+        scale(factor: f64) -> Self;
+        unscale(factor: f64) -> Self;
+        floor() -> Self;
+        ceil() -> Self;
+        round() -> Self;
+        trunc() -> Self;
+        fract() -> Self;
+        mul_add(a: Self, b: Self) -> Self;
+        recip() -> Self;
+        conjugate() -> Self;
+        sin() -> Self;
+        cos() -> Self;
+        tan() -> Self;
+        asin() -> Self;
+        acos() -> Self;
+        atan() -> Self;
+        sinh() -> Self;
+        cosh() -> Self;
+        tanh() -> Self;
+        asinh() -> Self;
+        acosh() -> Self;
+        atanh() -> Self;
+        log(base: f64) -> Self;
+        log2() -> Self;
+        log10() -> Self;
+        ln() -> Self;
+        ln_1p() -> Self;
+        sqrt() -> Self;
+        exp() -> Self;
+        exp2() -> Self;
+        exp_m1() -> Self;
+        powi(n: i32) -> Self;
+        powf(n: f64) -> Self;
+        powc(n: Self) -> Self;
+        cbrt() -> Self;
+    }
+
+    #[inline]
+    fn from_real(re: Self::RealField) -> Self {
+        Self::new(re)
+    }
+
+    #[inline]
+    fn sin_cos(self) -> (Self, Self) {
+        let (sin, cos) = self.value.sin_cos();
+        (Self::new(sin), Self::new(cos))
+    }
+
+    #[inline]
+    fn is_finite(&self) -> bool {
+        self.value.is_finite()
+    }
+
+    #[inline]
+    fn try_sqrt(self) -> Option<Self> {
+        nalgebra::ComplexField::try_sqrt(self.value).map(Self::new)
+    }
+}
+
+impl<D, S, T: num::ToPrimitive> num::ToPrimitive for Quantity<D, S, T> {
+    fn to_i64(&self) -> Option<i64> {
+        self.value.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.value.to_u64()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        self.value.to_f64()
+    }
+}
+
+impl<D, S, T: Value + num::FromPrimitive> num::FromPrimitive for Quantity<D, S, T> {
+    fn from_i64(n: i64) -> Option<Self> {
+        T::from_i64(n).map(Self::new)
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        T::from_u64(n).map(Self::new)
+    }
+    fn from_f64(n: f64) -> Option<Self> {
+        <T as num::FromPrimitive>::from_f64(n).map(Self::new)
+    }
+}
+
+impl<D, S, T: serde::Serialize> serde::Serialize for Quantity<D, S, T> {
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, D, S, T: Value + serde::Deserialize<'de>> serde::Deserialize<'de> for Quantity<D, S, T> {
+    fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+//plain numbers go in and out of quantities as the value in their unit
+macro_rules! value_conversions {
+    ($value:ty: $($primitive:ty),*) => {
+        impl<D, S> From<Quantity<D, S, $value>> for $value {
+            #[inline]
+            fn from(value: Quantity<D, S, $value>) -> Self {
+                value.value
+            }
+        }
+        impl<D, S> From<&Quantity<D, S, $value>> for $value {
+            #[inline]
+            fn from(value: &Quantity<D, S, $value>) -> Self {
+                value.value
+            }
+        }
+        impl<D, S> From<$value> for Quantity<D, S, $value> {
+            #[inline]
+            fn from(value: $value) -> Self {
+                Self::new(value)
+            }
+        }
+        impl<D, S> From<&$value> for Quantity<D, S, $value> {
+            #[inline]
+            fn from(value: &$value) -> Self {
+                Self::new(*value)
+            }
+        }
+        $(
+            #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_lossless, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+            impl<D, S> From<$primitive> for Quantity<D, S, $value> {
+                #[inline]
+                fn from(value: $primitive) -> Self {
+                    Self::new(value as $value)
+                }
+            }
+            #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_lossless, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+            impl<D, S> From<&$primitive> for Quantity<D, S, $value> {
+                #[inline]
+                fn from(value: &$primitive) -> Self {
+                    Self::new(*value as $value)
+                }
+            }
+        )*
+    };
+}
+
+value_conversions!(f64: f32, u64, u32, u16, u8, i64, i32, i16, i8);
+value_conversions!(f32: f64, u64, u32, u16, u8, i64, i32, i16, i8);
+value_conversions!(i64: f64, f32, u64, u32, u16, u8, i32, i16, i8);
@@ -0,0 +1,8 @@
+use crate::math::units::dimension::Dimensionless;
+use wpilib_macros::unit;
+crate::crate_namespace!();
+
+unit!(Ratio, f64, Dimensionless, si);
+unit!(Percent, f64, Dimensionless, 0.01, "%");
+
+interconvert!(Ratio, Percent);
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use super::{
+    angle::{Degree, Radian, Rotation},
+    angular_acceleration::{
+        DegreePerSecondSquared, RadianPerSecondSquared, RotationPerMinuteSquared,
+        RotationPerSecondSquared,
+    },
+    angular_velocity::{DegreePerSecond, RadianPerSecond, RotationPerMinute, RotationPerSecond},
+    data::{Byte, Gigabyte, Kilobyte, Megabyte},
+    data_rate::{BytesPerSecond, GigabytesPerHour, KilobytesPerSecond, MegabytesPerSecond},
+    dimension::Dim,
+    distance::{Centimeter, Feet, Inch, Meter},
+    energy::{Amp, Horsepower, Joule, Milliamp, Ohm, Volt, Watt, WattHour},
+    force::{Newton, PoundForce},
+    frequency::{Hertz, Kilohertz},
+    gain::{
+        VoltPerMeterPerSecond, VoltPerMeterPerSecondSquared, VoltPerRadianPerSecond,
+        VoltPerRadianPerSecondSquared,
+    },
+    linear_acceleration::{FeetPerSecondSquared, Gs, MeterPerSecondSquared},
+    linear_velocity::{FeetPerSecond, KilometerPerHour, MeterPerSecond, MilePerHour},
+    mass::{Gram, Kilogram, Ounce, Pound},
+    moment_of_inertia::{KilogramSquareMeter, PoundSquareFoot},
+    momentum::KilogramMeterPerSecond,
+    pressure::{Bar, Kilopascal, Pascal, PSI},
+    ratio::{Percent, Ratio},
+    temperature::{Celsius, Fahrenheit, Kelvin},
+    time::{Hour, Microsecond, Millisecond, Minute, Second},
+    torque::{FootPound, InchPound, KilogramMeter, NewtonCentimeter, NewtonMeter},
+    Unit,
+};
+
+/// What a unit symbol stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Symbol {
+    pub(super) dimension: [i32; 7],
+    pub(super) scale: f64,
+    pub(super) offset: f64,
+}
+
+impl Symbol {
+    fn of<U: Unit>() -> Self
+    where
+        U::Dimension: Dim,
+    {
+        Self {
+            dimension: U::Dimension::exponents(),
+            scale: U::SCALE,
+            offset: U::OFFSET,
+        }
+    }
+}
+
+//every unit is registered under the symbol it displays with and any other names it's written as,
+//units with angles in them like torque have to be registered whole as `N·m` would be an energy
+macro_rules! symbols {
+    ($($unit:ty $(=> $($alias:literal),+)?;)*) => {
+        fn registry() -> HashMap<String, Symbol> {
+            let mut symbols = HashMap::new();
+            $(
+                let symbol = Symbol::of::<$unit>();
+                let display = <$unit>::symbol();
+                if !display.is_empty() {
+                    symbols.insert(display, symbol);
+                }
+                $($(symbols.insert(String::from($alias), symbol);)+)?
+            )*
+            symbols
+        }
+    };
+}
+
+symbols! {
+    Meter => "meter", "meters";
+    Feet => "foot", "feet";
+    Inch => "inch", "inches";
+    Centimeter;
+    Degree => "°", "degree", "degrees";
+    Radian => "radian", "radians";
+    Rotation => "rev", "rotation", "rotations";
+    DegreePerSecond => "°/s";
+    RadianPerSecond => "rad/s";
+    RotationPerSecond => "rot/s", "rev/s";
+    RotationPerMinute => "RPM", "rev/min";
+    DegreePerSecondSquared => "°/s^2";
+    RadianPerSecondSquared => "rad/s^2";
+    RotationPerSecondSquared;
+    RotationPerMinuteSquared;
+    Byte;
+    Kilobyte;
+    Megabyte;
+    Gigabyte;
+    BytesPerSecond;
+    KilobytesPerSecond;
+    MegabytesPerSecond;
+    GigabytesPerHour;
+    Joule => "J";
+    Volt => "V", "volt", "volts";
+    Amp => "amp", "amps";
+    Milliamp;
+    Watt => "W";
+    Horsepower;
+    WattHour;
+    Ohm => "Ω", "ohm", "ohms";
+    Newton => "N";
+    PoundForce;
+    Hertz => "Hz";
+    Kilohertz;
+    VoltPerMeterPerSecond;
+    VoltPerMeterPerSecondSquared;
+    VoltPerRadianPerSecond => "V·s/rad";
+    VoltPerRadianPerSecondSquared => "V·s^2/rad";
+    MeterPerSecondSquared;
+    FeetPerSecondSquared;
+    Gs;
+    MeterPerSecond;
+    KilometerPerHour => "kph";
+    MilePerHour;
+    FeetPerSecond;
+    Kilogram;
+    Gram;
+    Pound => "lbs";
+    Ounce;
+    KilogramSquareMeter => "kg·m^2";
+    PoundSquareFoot;
+    KilogramMeterPerSecond;
+    Pascal => "Pa";
+    Kilopascal;
+    PSI;
+    Bar;
+    Ratio;
+    Percent;
+    Celsius => "degC";
+    Fahrenheit => "degF";
+    Kelvin;
+    Hour => "hr";
+    Minute;
+    Second => "sec";
+    Millisecond;
+    Microsecond => "µs";
+    NewtonMeter => "N·m", "Nm";
+    NewtonCentimeter;
+    KilogramMeter;
+    FootPound => "ft·lb";
+    InchPound => "in·lb";
+}
+
+static SYMBOLS: Lazy<HashMap<String, Symbol>> = Lazy::new(registry);
+
+/// The unit a symbol stands for, if one is registered under it.
+pub(super) fn lookup(symbol: &str) -> Option<Symbol> {
+    SYMBOLS.get(symbol).copied()
+}
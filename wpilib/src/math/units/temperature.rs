@@ -1,25 +1,33 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::Temperature;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Celsius, f64);
-unit!(Fahrenheit, f64);
-unit!(Kelvin, f64);
+unit!(Celsius, f64, Temperature, 1.0, 273.15, "°C");
+unit!(
+    Fahrenheit,
+    f64,
+    Temperature,
+    5.0 / 9.0,
+    459.67 * 5.0 / 9.0,
+    "°F"
+);
+unit!(Kelvin, f64, Temperature, si);
 
-unit_conversion!(Celsius f64, Fahrenheit f64, celsius_to_fahrenheit);
-unit_conversion!(Celsius f64, Kelvin f64, celsius_to_kelvin);
-unit_conversion!(Fahrenheit f64, Kelvin f64, fahrenheit_to_kelvin);
+interconvert!(Celsius, Fahrenheit, Kelvin);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Celsius::new(celsius).to::<Fahrenheit>()`")]
 #[must_use]
 pub fn celsius_to_fahrenheit(celsius: f64) -> f64 {
-    celsius.mul_add(1.8, 32.0)
+    Celsius::new(celsius).to::<Fahrenheit>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Celsius::new(celsius).to::<Kelvin>()`")]
 #[must_use]
 pub fn celsius_to_kelvin(celsius: f64) -> f64 {
-    celsius + 273.15
+    Celsius::new(celsius).to::<Kelvin>().value()
 }
-
+#[deprecated(note = "use `Unit::to`, e.g. `Fahrenheit::new(fahrenheit).to::<Kelvin>()`")]
 #[must_use]
 pub fn fahrenheit_to_kelvin(fahrenheit: f64) -> f64 {
-    (fahrenheit + 459.67) * 5.0 / 9.0
+    Fahrenheit::new(fahrenheit).to::<Kelvin>().value()
 }
@@ -1,25 +1,336 @@
 use crate::{
-    math::units::distance::{Feet, Meter},
-    math::units::linear_velocity::MeterPerSecond,
-    math::units::time::Second,
+    math::units::angle::{Degree, Radian},
+    math::units::angular_acceleration::RadianPerSecondSquared,
+    math::units::angular_velocity::{RadianPerSecond, RotationPerMinute},
+    math::units::dimension::{Acceleration, AngularVelocity, Energy, Torque, Velocity},
+    math::units::distance::{Centimeter, Feet, Inch, Meter},
+    math::units::energy::{Amp, Horsepower, Joule, Milliamp, Volt, Watt},
+    math::units::force::{Newton, PoundForce},
+    math::units::frequency::Hertz,
+    math::units::gain::{VoltPerMeterPerSecond, VoltPerRadianPerSecond},
+    math::units::linear_acceleration::{Gs, MeterPerSecondSquared},
+    math::units::linear_velocity::{FeetPerSecond, MeterPerSecond, MilePerHour},
+    math::units::mass::Kilogram,
+    math::units::moment_of_inertia::KilogramSquareMeter,
+    math::units::momentum::KilogramMeterPerSecond,
+    math::units::parse::{DynamicQuantity, ParseUnitError},
+    math::units::pressure::{Kilopascal, Pascal, PSI},
+    math::units::ratio::{Percent, Ratio},
+    math::units::temperature::{Celsius, Fahrenheit, Kelvin},
+    math::units::time::{Microsecond, Millisecond, Second},
+    math::units::torque::NewtonMeter,
+    math::units::{Quantity, Unit},
 };
 
 #[test]
 fn conversion() {
+    //3.28084 feet is a meter to within the precision it's written with
     let meter = Meter::new(1.0);
     let feet = Feet::new(3.28084);
+    assert!((feet.to_si() - meter.to_si()).abs() < 1e-5);
+    assert!((meter.to::<Feet>().value() - feet.value()).abs() < 1e-5);
+    let combined = feet + meter;
+    assert!((combined.to::<Feet>().value() - 6.56168).abs() < 1e-5);
+    assert!((combined.to::<Meter>().value() - 2.0).abs() < 1e-5);
+    assert!(combined > feet);
+}
+
+#[test]
+fn exact_conversion() {
+    let meter = Meter::new(0.3048);
+    let feet = Feet::new(1.0);
     assert_eq!(feet, meter);
     assert_eq!(meter, feet);
     let combined = feet + meter;
-    assert_eq!(combined, Feet::new(6.56168));
-    assert_eq!(combined, Meter::new(2.0));
+    assert_eq!(combined, Feet::new(2.0));
+    assert_eq!(combined, Meter::new(0.6096));
     assert!(combined > feet);
 }
 
+#[test]
+fn same_dimension() {
+    //units that never had a conversion written between them
+    let centimeters: Centimeter = Inch::new(12.0).to();
+    assert!((centimeters.value() - 30.48).abs() < 1e-9);
+    let speed: MeterPerSecond = MilePerHour::new(60.0).to();
+    assert!((speed.value() - 26.8224).abs() < 1e-9);
+    let spin = RadianPerSecond::from(RotationPerMinute::new(60.0));
+    assert!((spin.value() - std::f64::consts::TAU).abs() < 1e-9);
+
+    //a unit converts to itself exactly
+    assert_eq!(Inch::new(0.1).to::<Inch>(), Inch::new(0.1));
+    //integer units round rather than truncate
+    assert_eq!(Second::new(0.02).to::<Microsecond>().value(), 20_000);
+}
+
+#[test]
+fn temperature() {
+    assert!((Celsius::new(100.0).to::<Fahrenheit>().value() - 212.0).abs() < 1e-9);
+    assert!((Fahrenheit::new(32.0).to::<Kelvin>().value() - 273.15).abs() < 1e-9);
+    assert!(Celsius::new(0.0) > Fahrenheit::new(0.0));
+}
+
 #[test]
 fn dim_analysis() {
     let meter = Meter::new(1.0);
     let second = Second::new(1.0);
     let meter_per_second: MeterPerSecond = meter / second;
     assert_eq!(meter_per_second, MeterPerSecond::new(1.0));
+
+    //products and quotients come out in SI whatever they were made from
+    let speed: MeterPerSecond = Feet::new(10.0) / Second::new(2.0);
+    assert_eq!(speed, FeetPerSecond::new(5.0));
+    let acceleration: Quantity<Acceleration> = Meter::new(9.8) / Second::new(2.0) / second;
+    assert_eq!(acceleration, Quantity::<Acceleration>::new(4.9));
+    let alpha: RadianPerSecondSquared = NewtonMeter::new(2.0) / KilogramSquareMeter::new(0.5);
+    assert_eq!(alpha, RadianPerSecondSquared::new(4.0));
+    let power: Watt = Volt::new(12.0) * Amp::new(2.0);
+    let energy: Joule = power * Second::new(0.5);
+    assert_eq!(energy, NewtonMeter::new(3.0) * Radian::new(4.0));
+    let frequency = 1.0 / Second::new(0.5);
+    assert_eq!(RadianPerSecond::new(2.0), Radian::new(1.0) * frequency);
+    assert_eq!(meter.square(), meter * meter);
+}
+
+#[test]
+fn display() {
+    assert_eq!(Meter::new(1.5).to_string(), "1.5 m");
+    let acceleration = Meter::new(9.8) / Second::new(1.0).square();
+    assert_eq!(acceleration.to_string(), "9.8 m·s^-2");
+    assert_eq!(NewtonMeter::new(1.0).to_string(), "1 m^2·kg·s^-2·rad^-1");
+    assert_eq!(Inch::new(12.5).to_string(), "12.5 in");
+    assert_eq!(Celsius::new(-40.0).to_string(), "-40 °C");
+}
+
+#[test]
+fn parse() {
+    assert_eq!("12.5 in".parse::<Inch>(), Ok(Inch::new(12.5)));
+    assert_eq!("90deg".parse::<Degree>(), Ok(Degree::new(90.0)));
+    assert_eq!(
+        "3000 rpm".parse::<RotationPerMinute>(),
+        Ok(RotationPerMinute::new(3000.0))
+    );
+    assert_eq!(
+        "1.2 m/s".parse::<MeterPerSecond>(),
+        Ok(MeterPerSecond::new(1.2))
+    );
+    assert_eq!(" 2e3 ms ".parse::<Second>(), Ok(Second::new(2.0)));
+    assert_eq!("50%".parse::<Ratio>(), Ok(Ratio::new(0.5)));
+    assert_eq!(
+        "9.8 m/s^2".parse::<MeterPerSecondSquared>(),
+        "9.8 m·s^-2".parse::<MeterPerSecondSquared>()
+    );
+    assert_eq!(
+        "12 V·s/m".parse::<VoltPerMeterPerSecond>(),
+        Ok(VoltPerMeterPerSecond::new(12.0))
+    );
+    //torque carries an angle, so it's only read whole
+    assert_eq!("2 N·m".parse::<NewtonMeter>(), Ok(NewtonMeter::new(2.0)));
+
+    //anything parsed converts into the unit it's read as
+    let length: Inch = "1 ft".parse().unwrap_or_else(|_| unreachable!());
+    assert!((length.value() - 12.0).abs() < 1e-9);
+    let temperature: Kelvin = "100 °C".parse().unwrap_or_else(|_| unreachable!());
+    assert!((temperature.value() - 373.15).abs() < 1e-9);
+
+    let quantity: DynamicQuantity = "3000 rpm".parse().unwrap_or_else(|_| unreachable!());
+    assert!(quantity.is::<AngularVelocity>());
+    assert!(!quantity.is::<Velocity>());
+    assert!((quantity.to_si() - RotationPerMinute::new(3000.0).to_si()).abs() < 1e-9);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        "3 s".parse::<Meter>(),
+        Err(ParseUnitError::DimensionMismatch {
+            expected: String::from("m"),
+            found: String::from("s"),
+        })
+    );
+    assert_eq!(
+        "1.2 m/s".parse::<Ratio>().map_err(|err| err.to_string()),
+        Err(String::from(
+            "dimension mismatch: expected dimensionless, found m·s^-1"
+        ))
+    );
+    assert_eq!(
+        "fast".parse::<MeterPerSecond>(),
+        Err(ParseUnitError::MissingValue(String::from("fast")))
+    );
+    assert_eq!(
+        "1.2.3 m".parse::<Meter>(),
+        Err(ParseUnitError::InvalidValue(String::from("1.2.3")))
+    );
+    assert_eq!(
+        "4 furlongs".parse::<Meter>(),
+        Err(ParseUnitError::UnknownUnit(String::from("furlongs")))
+    );
+    assert_eq!(
+        "4 m^x".parse::<Meter>(),
+        Err(ParseUnitError::InvalidExponent(String::from("m^x")))
+    );
+    assert_eq!(
+        "4 °C/s".parse::<DynamicQuantity>(),
+        Err(ParseUnitError::OffsetInCompound(String::from("°C")))
+    );
+}
+
+#[test]
+fn round_trip() {
+    fn round_trips<U: std::str::FromStr + std::fmt::Display + PartialEq + std::fmt::Debug>(unit: U)
+    where
+        U::Err: std::fmt::Debug,
+    {
+        assert_eq!(unit.to_string().parse::<U>().ok(), Some(unit));
+    }
+    round_trips(Inch::new(0.1));
+    round_trips(Feet::new(-3.25));
+    round_trips(Fahrenheit::new(98.6));
+    round_trips(RotationPerMinute::new(5676.0));
+    round_trips(Percent::new(12.5));
+    round_trips(Microsecond::new(20_000));
+    round_trips(Millisecond::new(20.0));
+    round_trips(Volt::new(12.6));
+    round_trips(NewtonMeter::new(0.5) * Radian::new(1.0));
+    round_trips(KilogramSquareMeter::new(0.004));
+    round_trips(Ratio::new(0.75));
+
+    //dynamic quantities display in SI
+    let quantity: DynamicQuantity = "32 °F".parse().unwrap_or_else(|_| unreachable!());
+    let displayed: DynamicQuantity = quantity
+        .to_string()
+        .parse()
+        .unwrap_or_else(|_| unreachable!());
+    assert!((displayed.to_si() - 273.15).abs() < 1e-9);
+    assert_eq!(displayed.to_si().to_bits(), quantity.to_si().to_bits());
+}
+
+#[test]
+fn serde_string() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Config {
+        #[serde(with = "crate::math::units::parse::as_string")]
+        height: Inch,
+        speed: MeterPerSecond,
+    }
+    let config = Config {
+        height: Inch::new(12.5),
+        speed: MeterPerSecond::new(1.5),
+    };
+    let json = serde_json::to_string(&config).unwrap_or_default();
+    assert_eq!(json, r#"{"height":"12.5 in","speed":1.5}"#);
+    assert_eq!(serde_json::from_str::<Config>(&json).ok(), Some(config));
+    assert_eq!(
+        serde_json::from_str::<Config>(r#"{"height":"0.5 m","speed":1.5}"#)
+            .map(|config| config.height)
+            .ok(),
+        Some(Meter::new(0.5).to())
+    );
+    assert!(serde_json::from_str::<Config>(r#"{"height":"2 s","speed":1.5}"#).is_err());
+}
+
+#[test]
+fn unit_families() {
+    let acceleration: MeterPerSecondSquared = MeterPerSecond::new(6.0) / Second::new(2.0);
+    let force: Newton = Kilogram::new(2.0) * acceleration;
+    assert_eq!(force, Newton::new(6.0));
+    assert!((Gs::from(MeterPerSecondSquared::new(9.806_65)).value() - 1.0).abs() < 1e-9);
+    assert!((PoundForce::new(1.0).to::<Newton>().value() - 4.448_221_615).abs() < 1e-9);
+
+    let momentum: KilogramMeterPerSecond = force * Second::new(0.5);
+    assert_eq!(momentum, Kilogram::new(1.5) * MeterPerSecond::new(2.0));
+
+    let frequency: Hertz = 1.0 / Second::new(0.02);
+    assert_eq!(frequency, Hertz::new(50.0));
+    assert_eq!(Radian::new(2.0) * frequency, RadianPerSecond::new(100.0));
+
+    assert_eq!(Milliamp::new(1500.0), Amp::new(1.5));
+    let power: Watt = force * MeterPerSecond::new(2.0);
+    assert!((power.to::<Horsepower>().value() - 12.0 / 745.699_871_582_270_2).abs() < 1e-12);
+
+    let pressure: Pascal = PSI::new(60.0).to();
+    assert!((pressure.value() - 413_685.4).abs() < 0.1);
+    assert!(PSI::new(60.0) > Kilopascal::new(400.0));
+
+    let k_v: VoltPerMeterPerSecond = Volt::new(12.0) / MeterPerSecond::new(4.0);
+    assert_eq!(k_v * MeterPerSecond::new(2.0), Volt::new(6.0));
+    let k_v: VoltPerRadianPerSecond =
+        Volt::new(12.0) / RotationPerMinute::new(6000.0).to::<RadianPerSecond>();
+    assert!((k_v.value() - 12.0 / (100.0 * std::f64::consts::TAU)).abs() < 1e-12);
+
+    assert_eq!(Percent::new(50.0), Ratio::new(0.5));
+    assert!((f64::from(Ratio::from(Percent::new(25.0))) - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn assign_ops() {
+    let mut length = Meter::new(2.0);
+    length *= 3.0;
+    assert_eq!(length, Meter::new(6.0));
+    length /= 4.0;
+    assert_eq!(length, Meter::new(1.5));
+    //only dimensionless quantities scale in place
+    length *= Percent::new(50.0);
+    assert_eq!(length, Meter::new(0.75));
+    length /= Ratio::new(0.5);
+    assert_eq!(length, Meter::new(1.5));
+    length %= Centimeter::new(100.0);
+    assert_eq!(length, Meter::new(0.5));
+}
+
+#[test]
+fn dimensionless_scalar() {
+    use nalgebra::{ComplexField, Matrix2, Vector2};
+    use num::{Num, One};
+
+    assert_eq!(Ratio::one(), Ratio::new(1.0));
+    assert_eq!(Ratio::from_str_radix("10", 2).ok(), Some(Ratio::new(2.0)));
+    assert_eq!(ComplexField::sqrt(Ratio::new(9.0)), Ratio::new(3.0));
+    assert_eq!(
+        Ratio::new(ComplexField::abs(Ratio::new(-2.0))),
+        Ratio::new(2.0)
+    );
+
+    let matrix = Matrix2::new(
+        Ratio::new(1.0),
+        Ratio::new(2.0),
+        Ratio::new(3.0),
+        Ratio::new(4.0),
+    );
+    let vector = Vector2::new(Ratio::new(1.0), Ratio::new(1.0));
+    assert_eq!(
+        matrix * vector,
+        Vector2::new(Ratio::new(3.0), Ratio::new(7.0))
+    );
+    let inverse = matrix.try_inverse().expect("the matrix is invertible");
+    assert!((inverse[(0, 0)].value() + 2.0).abs() < 1e-12);
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_conversions() {
+    crate::crate_namespace!();
+    use crate::math::units::{distance::meter_to_feet, time::second_to_microsecond};
+
+    assert!((meter_to_feet(1.0) - 3.28084).abs() < 1e-5);
+    assert_eq!(second_to_microsecond(0.02), 20_000);
+
+    wpilib_macros::unit_conversion!(Meter f64, Feet f64, meter_to_feet);
+    assert!((inverse_meter_to_feet(3.28084) - 1.0).abs() < 1e-5);
+    wpilib_macros::unit_dimensional_analysis!(Volt * Amp = Watt);
+    wpilib_macros::unit_dimensional_analysis!(Meter / Second = MeterPerSecond);
+}
+
+#[test]
+fn torque_convention() {
+    //a force along a distance is work, torque is kept apart as work per radian
+    let work: Quantity<Energy> = Newton::new(2.0) * Meter::new(3.0);
+    assert_eq!(work, Joule::new(6.0));
+    let turned: Quantity<Energy> = NewtonMeter::new(2.0) * Radian::new(3.0);
+    assert_eq!(turned, work);
+    let torque: Quantity<Torque> = work / Radian::new(3.0);
+    assert_eq!(torque, NewtonMeter::new(2.0));
+    let alpha: RadianPerSecondSquared = torque / KilogramSquareMeter::new(0.5);
+    assert_eq!(alpha, RadianPerSecondSquared::new(4.0));
 }
@@ -1,50 +1,53 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::Time;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(Hour, f64);
-unit!(Minute, f64);
-unit!(Second, f64);
-unit!(Millisecond, f64);
-unit!(Microsecond, i64);
+unit!(Hour, f64, Time, 3600.0, "h");
+unit!(Minute, f64, Time, 60.0, "min");
+unit!(Second, f64, Time, si);
+unit!(Millisecond, f64, Time, 1e-3, "ms");
+unit!(Microsecond, i64, Time, 1e-6, "us");
 
-unit_conversion!(Second f64, Millisecond f64, second_to_millisecond);
-unit_conversion!(Second f64, Microsecond i64, second_to_microsecond);
-unit_conversion!(Millisecond f64, Microsecond i64, millisecond_to_microsecond);
-unit_conversion!(Hour f64, Second f64, hour_to_second);
-unit_conversion!(Minute f64, Second f64, minute_to_second);
-unit_conversion!(Hour f64, Minute f64, hour_to_minute);
-unit_conversion!(Minute f64, Millisecond f64, minute_to_millisecond);
-unit_conversion!(Minute f64, Microsecond i64, minute_to_microsecond);
+interconvert!(Hour, Minute, Second, Millisecond, Microsecond);
 
+#[deprecated(note = "use `Unit::to`, e.g. `Second::new(second).to::<Millisecond>()`")]
 #[must_use]
 pub fn second_to_millisecond(second: f64) -> f64 {
-    second * 1000.0
+    Second::new(second).to::<Millisecond>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Second::new(second).to::<Microsecond>()`")]
 #[must_use]
 pub fn second_to_microsecond(second: f64) -> i64 {
-    (second * 1_000_000.0) as i64
+    Second::new(second).to::<Microsecond>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Millisecond::new(millisecond).to::<Microsecond>()`")]
 #[must_use]
 pub fn millisecond_to_microsecond(millisecond: f64) -> i64 {
-    (millisecond * 1000.0) as i64
+    Millisecond::new(millisecond).to::<Microsecond>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Hour::new(hour).to::<Second>()`")]
 #[must_use]
 pub fn hour_to_second(hour: f64) -> f64 {
-    hour * 3600.0
+    Hour::new(hour).to::<Second>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Minute::new(minute).to::<Second>()`")]
 #[must_use]
 pub fn minute_to_second(minute: f64) -> f64 {
-    minute * 60.0
+    Minute::new(minute).to::<Second>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Hour::new(hour).to::<Minute>()`")]
 #[must_use]
 pub fn hour_to_minute(hour: f64) -> f64 {
-    hour * 60.0
+    Hour::new(hour).to::<Minute>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Minute::new(minute).to::<Millisecond>()`")]
 #[must_use]
 pub fn minute_to_millisecond(minute: f64) -> f64 {
-    minute * 60000.0
+    Minute::new(minute).to::<Millisecond>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `Minute::new(minute).to::<Microsecond>()`")]
 #[must_use]
 pub fn minute_to_microsecond(minute: f64) -> i64 {
-    (minute * 60_000_000.0) as i64
+    Minute::new(minute).to::<Microsecond>().value()
 }
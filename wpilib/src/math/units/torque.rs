@@ -1,50 +1,87 @@
-use wpilib_macros::{unit, unit_conversion};
+use crate::math::units::dimension::Torque;
+use crate::math::units::Unit;
+use wpilib_macros::unit;
 crate::crate_namespace!();
 
-unit!(NewtonMeter, f64);
-unit!(NewtonCentimeter, f64);
-unit!(KilogramMeter, f64);
-unit!(FootPound, f64);
-unit!(InchPound, f64);
+unit!(NewtonMeter, f64, Torque, si);
+unit!(NewtonCentimeter, f64, Torque, 0.01, "N·cm");
+unit!(KilogramMeter, f64, Torque, 9.806_65, "kgf·m");
+unit!(
+    FootPound,
+    f64,
+    Torque,
+    0.3048 * 0.453_592_37 * 9.806_65,
+    "ft·lbf"
+);
+unit!(
+    InchPound,
+    f64,
+    Torque,
+    0.0254 * 0.453_592_37 * 9.806_65,
+    "in·lbf"
+);
 
-unit_conversion!(NewtonMeter f64, NewtonCentimeter f64, newton_meter_to_newton_centimeter);
-unit_conversion!(NewtonMeter f64, KilogramMeter f64, newton_meter_to_kilogram_meter);
-unit_conversion!(NewtonMeter f64, FootPound f64, newton_meter_to_foot_pound);
-unit_conversion!(NewtonMeter f64, InchPound f64, newton_meter_to_inch_pound);
-unit_conversion!(NewtonCentimeter f64, KilogramMeter f64, newton_centimeter_to_kilogram_meter);
-unit_conversion!(NewtonCentimeter f64, FootPound f64, newton_centimeter_to_foot_pound);
-unit_conversion!(NewtonCentimeter f64, InchPound f64, newton_centimeter_to_inch_pound);
-unit_conversion!(KilogramMeter f64, FootPound f64, kilogram_meter_to_foot_pound);
+interconvert!(
+    NewtonMeter,
+    NewtonCentimeter,
+    KilogramMeter,
+    FootPound,
+    InchPound
+);
 
+#[deprecated(
+    note = "use `Unit::to`, e.g. `NewtonMeter::new(newton_meter).to::<NewtonCentimeter>()`"
+)]
 #[must_use]
 pub fn newton_meter_to_newton_centimeter(newton_meter: f64) -> f64 {
-    newton_meter * 100.0
+    NewtonMeter::new(newton_meter)
+        .to::<NewtonCentimeter>()
+        .value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `NewtonMeter::new(newton_meter).to::<KilogramMeter>()`")]
 #[must_use]
 pub fn newton_meter_to_kilogram_meter(newton_meter: f64) -> f64 {
-    newton_meter * 0.101_972
+    NewtonMeter::new(newton_meter).to::<KilogramMeter>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `NewtonMeter::new(newton_meter).to::<FootPound>()`")]
 #[must_use]
 pub fn newton_meter_to_foot_pound(newton_meter: f64) -> f64 {
-    newton_meter * 0.737_562
+    NewtonMeter::new(newton_meter).to::<FootPound>().value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `NewtonMeter::new(newton_meter).to::<InchPound>()`")]
 #[must_use]
 pub fn newton_meter_to_inch_pound(newton_meter: f64) -> f64 {
-    newton_meter * 8.85075
+    NewtonMeter::new(newton_meter).to::<InchPound>().value()
 }
+#[deprecated(
+    note = "use `Unit::to`, e.g. `NewtonCentimeter::new(newton_centimeter).to::<KilogramMeter>()`"
+)]
 #[must_use]
 pub fn newton_centimeter_to_kilogram_meter(newton_centimeter: f64) -> f64 {
-    newton_meter_to_kilogram_meter(newton_centimeter / 100.0)
+    NewtonCentimeter::new(newton_centimeter)
+        .to::<KilogramMeter>()
+        .value()
 }
+#[deprecated(
+    note = "use `Unit::to`, e.g. `NewtonCentimeter::new(newton_centimeter).to::<FootPound>()`"
+)]
 #[must_use]
 pub fn newton_centimeter_to_foot_pound(newton_centimeter: f64) -> f64 {
-    newton_meter_to_foot_pound(newton_centimeter / 100.0)
+    NewtonCentimeter::new(newton_centimeter)
+        .to::<FootPound>()
+        .value()
 }
+#[deprecated(
+    note = "use `Unit::to`, e.g. `NewtonCentimeter::new(newton_centimeter).to::<InchPound>()`"
+)]
 #[must_use]
 pub fn newton_centimeter_to_inch_pound(newton_centimeter: f64) -> f64 {
-    newton_meter_to_inch_pound(newton_centimeter / 100.0)
+    NewtonCentimeter::new(newton_centimeter)
+        .to::<InchPound>()
+        .value()
 }
+#[deprecated(note = "use `Unit::to`, e.g. `KilogramMeter::new(kilogram_meter).to::<FootPound>()`")]
 #[must_use]
 pub fn kilogram_meter_to_foot_pound(kilogram_meter: f64) -> f64 {
-    kilogram_meter * 7.23301
+    KilogramMeter::new(kilogram_meter).to::<FootPound>().value()
 }
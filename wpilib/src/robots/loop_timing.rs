@@ -0,0 +1,152 @@
+use std::{fmt::Display, time::Duration};
+
+/// How long each part of a single robot loop cycle took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoopTiming {
+    pub mode_transition: Duration,
+    pub mode_periodic: Duration,
+    pub robot_periodic: Duration,
+    pub command_manager: Duration,
+    pub sim_periodic: Duration,
+}
+
+impl LoopTiming {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            mode_transition: Duration::ZERO,
+            mode_periodic: Duration::ZERO,
+            robot_periodic: Duration::ZERO,
+            command_manager: Duration::ZERO,
+            sim_periodic: Duration::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.mode_transition
+            + self.mode_periodic
+            + self.robot_periodic
+            + self.command_manager
+            + self.sim_periodic
+    }
+}
+
+impl Display for LoopTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mode transition: {:?}, mode periodic: {:?}, robot_periodic: {:?}, CommandManager::run: {:?}, sim_periodic: {:?}",
+            self.mode_transition,
+            self.mode_periodic,
+            self.robot_periodic,
+            self.command_manager,
+            self.sim_periodic
+        )
+    }
+}
+
+/// Running statistics of how late each loop cycle started relative to its deadline.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JitterStats {
+    samples: u64,
+    mean: f64,
+    m2: f64,
+    min: Duration,
+    max: Duration,
+}
+
+impl JitterStats {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            samples: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    pub fn record(&mut self, jitter: Duration) {
+        if self.samples == 0 {
+            self.min = jitter;
+            self.max = jitter;
+        } else {
+            self.min = self.min.min(jitter);
+            self.max = self.max.max(jitter);
+        }
+        //welford's online algorithm, keeps the variance numerically stable over long matches
+        self.samples += 1;
+        let value = jitter.as_secs_f64();
+        let delta = value - self.mean;
+        #[allow(clippy::cast_precision_loss)]
+        let samples = self.samples as f64;
+        self.mean += delta / samples;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    #[must_use]
+    pub const fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    #[must_use]
+    pub const fn min(&self) -> Duration {
+        self.min
+    }
+
+    #[must_use]
+    pub const fn max(&self) -> Duration {
+        self.max
+    }
+
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        Duration::from_secs_f64(self.mean)
+    }
+
+    #[must_use]
+    pub fn std_dev(&self) -> Duration {
+        if self.samples < 2 {
+            return Duration::ZERO;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let variance = self.m2 / (self.samples - 1) as f64;
+        Duration::from_secs_f64(variance.max(0.0).sqrt())
+    }
+}
+
+/// Statistics collected by the robot loop scheduler.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoopStats {
+    pub cycles: u64,
+    pub overruns: u64,
+    pub last_timing: LoopTiming,
+    pub worst_timing: LoopTiming,
+    pub jitter: JitterStats,
+}
+
+impl LoopStats {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            cycles: 0,
+            overruns: 0,
+            last_timing: LoopTiming::new(),
+            worst_timing: LoopTiming::new(),
+            jitter: JitterStats::new(),
+        }
+    }
+
+    pub fn record(&mut self, timing: LoopTiming, overrun: bool) {
+        self.cycles += 1;
+        if overrun {
+            self.overruns += 1;
+        }
+        if timing.total() > self.worst_timing.total() {
+            self.worst_timing = timing;
+        }
+        self.last_timing = timing;
+    }
+}
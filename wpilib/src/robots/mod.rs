@@ -0,0 +1,410 @@
+use std::{fmt::Debug, panic::AssertUnwindSafe, time::Duration};
+
+use parking_lot::Mutex;
+
+use crate::{
+    command::CommandManager,
+    driver_station::DriverStation,
+    if_not_athena, if_sim,
+    timer::{get_default_clock, ClockRef},
+    EventTypes,
+};
+use periodic::PeriodicRunner;
+
+pub mod loop_timing;
+pub mod mode;
+pub mod periodic;
+pub mod runtime;
+#[cfg(test)]
+mod test;
+pub mod watchdog;
+
+pub use loop_timing::{JitterStats, LoopStats, LoopTiming};
+pub use mode::{ControlWord, ModeSource, ScriptedModeSource, SimModeHandle, SimModeSource};
+pub use periodic::{CallbackStats, PeriodicHandle, PeriodicScheduler};
+pub use watchdog::{Tracer, Watchdog};
+
+static PERIODIC_TIME: Mutex<f64> = Mutex::new(0.02);
+static LOOP_STATS: Mutex<LoopStats> = Mutex::new(LoopStats::new());
+
+/// Serializes tests that run the robot loop or touch what it feeds, the periodic time,
+/// shutdown flag and [`DriverStation`] are global.
+#[cfg(test)]
+pub(crate) static LOOP_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn set_periodic_time(time: f64) {
+    *PERIODIC_TIME.lock() = time;
+}
+
+#[must_use]
+pub fn get_periodic_time() -> Duration {
+    Duration::from_secs_f64(*PERIODIC_TIME.lock())
+}
+
+/// Returns the timing statistics of the running robot loop.
+#[must_use]
+pub fn get_loop_stats() -> LoopStats {
+    *LOOP_STATS.lock()
+}
+
+/// Moves a `deadline` that `now` has reached forward by whole periods until it's in the future.
+fn skip_missed_periods(deadline: &mut Duration, period: Duration, now: Duration) {
+    if period.is_zero() || now < *deadline {
+        return;
+    }
+    let missed = now.saturating_sub(*deadline).as_nanos() / period.as_nanos() + 1;
+    *deadline += period * u32::try_from(missed).unwrap_or(u32::MAX);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RobotMode {
+    Disabled = 0,
+    Teleop = 1,
+    Autonomous = 2,
+    Test = 3,
+}
+impl RobotMode {
+    #[must_use]
+    pub const fn is_disabled(&self) -> bool {
+        matches!(self, Self::Disabled)
+    }
+    #[must_use]
+    pub const fn is_autonomous(&self) -> bool {
+        matches!(self, Self::Autonomous)
+    }
+    #[must_use]
+    pub const fn is_teleop(&self) -> bool {
+        matches!(self, Self::Teleop)
+    }
+    #[must_use]
+    pub const fn is_test(&self) -> bool {
+        matches!(self, Self::Test)
+    }
+    #[must_use]
+    pub const fn get_name(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Teleop => "teleop",
+            Self::Autonomous => "autonomous",
+            Self::Test => "test",
+        }
+    }
+}
+
+pub trait RobotCore {
+    fn start(&mut self);
+
+    fn end(&mut self);
+
+    fn get_mode(&self) -> RobotMode;
+
+    fn get_control_word(&self) -> ControlWord;
+}
+
+pub trait UserRobot: Send + Sync {
+    //robot
+    fn robot_init(&mut self);
+    fn robot_periodic(&mut self);
+    fn robot_end(&mut self);
+    /// Called before `robot_init` with the scheduler of this robot loop's periodic
+    /// callbacks, it can be kept to add callbacks later.
+    fn add_periodics(&mut self, _periodic: &PeriodicScheduler) {}
+
+    //disabled
+    fn robot_disabled_init(&mut self) {}
+    fn robot_disabled_periodic(&mut self) {}
+    fn robot_disabled_end(&mut self) {}
+
+    //autonomous
+    fn robot_autonomous_init(&mut self) {}
+    fn robot_autonomous_periodic(&mut self) {}
+    fn robot_autonomous_end(&mut self) {}
+
+    //teleop
+    fn robot_teleop_init(&mut self) {}
+    fn robot_teleop_periodic(&mut self) {}
+    fn robot_teleop_end(&mut self) {}
+
+    //test
+    fn robot_test_init(&mut self) {}
+    fn robot_test_periodic(&mut self) {}
+    fn robot_test_end(&mut self) {}
+
+    //sim
+    fn sim_init(&mut self) {}
+    fn sim_periodic(&mut self) {}
+}
+
+pub struct RobotCoreImpl {
+    user_robot: Box<dyn UserRobot>,
+    mode_source: Box<dyn ModeSource>,
+    control_word: ControlWord,
+    loop_stats: LoopStats,
+    watchdog: Watchdog,
+    periodic: PeriodicScheduler,
+    clock: ClockRef,
+}
+impl RobotCoreImpl {
+    #[must_use]
+    pub fn new(user_robot: Box<dyn UserRobot>, mode_source: Box<dyn ModeSource>) -> Self {
+        Self::with_clock(user_robot, mode_source, get_default_clock())
+    }
+
+    /// A robot loop that times and paces its cycles on `clock`, on a
+    /// [`SimClock`](crate::timer::SimClock) it runs without waiting.
+    #[must_use]
+    pub fn with_clock(
+        user_robot: Box<dyn UserRobot>,
+        mode_source: Box<dyn ModeSource>,
+        clock: ClockRef,
+    ) -> Self {
+        Self {
+            user_robot,
+            mode_source,
+            control_word: ControlWord::disabled(),
+            loop_stats: LoopStats::default(),
+            watchdog: Self::loop_watchdog(clock.clone()),
+            periodic: PeriodicScheduler::with_clock(clock.clone()),
+            clock,
+        }
+    }
+
+    //the loop reports overruns itself so it can include the per-section timing
+    fn loop_watchdog(clock: ClockRef) -> Watchdog {
+        let mut watchdog = Watchdog::new(get_periodic_time()).with_clock(clock);
+        watchdog.suppress_timeout_message(true);
+        watchdog
+    }
+
+    #[must_use]
+    pub const fn loop_stats(&self) -> LoopStats {
+        self.loop_stats
+    }
+
+    /// Where callbacks that run between cycles of this robot loop are registered.
+    #[must_use]
+    pub const fn periodic_scheduler(&self) -> &PeriodicScheduler {
+        &self.periodic
+    }
+
+    /// The watchdog armed each cycle, holds the epochs of the last cycle.
+    #[must_use]
+    pub const fn watchdog(&self) -> &Watchdog {
+        &self.watchdog
+    }
+
+    fn mode_init(&mut self, mode: RobotMode) {
+        match mode {
+            RobotMode::Disabled => {
+                self.user_robot.robot_disabled_init();
+            }
+            RobotMode::Autonomous => {
+                self.user_robot.robot_autonomous_init();
+            }
+            RobotMode::Teleop => {
+                self.user_robot.robot_teleop_init();
+            }
+            RobotMode::Test => {
+                self.user_robot.robot_test_init();
+            }
+        }
+    }
+
+    fn mode_end(&mut self, mode: RobotMode) {
+        match mode {
+            RobotMode::Disabled => {
+                self.user_robot.robot_disabled_end();
+            }
+            RobotMode::Autonomous => {
+                self.user_robot.robot_autonomous_end();
+            }
+            RobotMode::Teleop => {
+                self.user_robot.robot_teleop_end();
+            }
+            RobotMode::Test => {
+                self.user_robot.robot_test_end();
+            }
+        }
+    }
+
+    fn mode_periodic(&mut self, mode: RobotMode) {
+        match mode {
+            RobotMode::Disabled => {
+                self.user_robot.robot_disabled_periodic();
+            }
+            RobotMode::Autonomous => {
+                self.user_robot.robot_autonomous_periodic();
+            }
+            RobotMode::Teleop => {
+                self.user_robot.robot_teleop_periodic();
+            }
+            RobotMode::Test => {
+                self.user_robot.robot_test_periodic();
+            }
+        }
+    }
+}
+impl RobotCore for RobotCoreImpl {
+    #[no_panic::no_panic]
+    fn start(&mut self) {
+        if_not_athena! {
+            println!("WARNING: Running on non-Athena hardware. This is not officially supported.");
+        }
+
+        self.user_robot.add_periodics(&self.periodic);
+        self.user_robot.robot_init();
+
+        if_sim! {
+            self.user_robot.sim_init();
+        }
+
+        let mut period = get_periodic_time();
+
+        let mut last_mode: Option<RobotMode> = None;
+        let mut deadline = self.clock.now();
+        let mut periodic = PeriodicRunner::new(self.periodic.clone(), deadline);
+        *LOOP_STATS.lock() = self.loop_stats;
+
+        loop {
+            if runtime::is_shutdown_requested() {
+                tracing::info!("Shutdown requested");
+                break;
+            }
+
+            let start = self.clock.now();
+            let mut timing = LoopTiming::new();
+
+            let Some(control_word) = self.mode_source.poll() else {
+                tracing::info!("Mode source {} closed", self.mode_source.get_name());
+                break;
+            };
+            self.control_word = control_word;
+            DriverStation::update(|data| {
+                data.control_word = control_word;
+                self.mode_source.update_driver_station(data);
+            });
+            self.loop_stats
+                .jitter
+                .record(start.saturating_sub(deadline));
+            self.watchdog.set_timeout(period);
+            self.watchdog.enable();
+
+            let mode = self.get_mode();
+
+            let mut section = self.clock.now();
+            if last_mode != Some(mode) {
+                if let Some(last_mode) = last_mode {
+                    self.mode_end(last_mode);
+                    self.watchdog
+                        .add_epoch(&format!("robot_{}_end()", last_mode.get_name()));
+                }
+                self.mode_init(mode);
+                self.watchdog
+                    .add_epoch(&format!("robot_{}_init()", mode.get_name()));
+            }
+            timing.mode_transition = self.clock.now().saturating_sub(section);
+
+            section = self.clock.now();
+            self.mode_periodic(mode);
+            self.watchdog
+                .add_epoch(&format!("robot_{}_periodic()", mode.get_name()));
+            last_mode = Some(mode);
+            timing.mode_periodic = self.clock.now().saturating_sub(section);
+
+            section = self.clock.now();
+            self.user_robot.robot_periodic();
+            self.watchdog.add_epoch("robot_periodic()");
+            timing.robot_periodic = self.clock.now().saturating_sub(section);
+
+            #[cfg(feature = "command")]
+            {
+                section = self.clock.now();
+                CommandManager::set_disabled(mode.is_disabled());
+                CommandManager::run_with_watchdog(&mut self.watchdog);
+                timing.command_manager = self.clock.now().saturating_sub(section);
+            }
+
+            if_sim! {
+                {
+                    section = self.clock.now();
+                    self.user_robot.sim_periodic();
+                    self.watchdog.add_epoch("sim_periodic()");
+                    timing.sim_periodic = self.clock.now().saturating_sub(section);
+                }
+            }
+
+            //deadlines are absolute so a slow cycle doesn't push every later cycle back,
+            //a changed period re-anchors the schedule on this cycle
+            let new_period = get_periodic_time();
+            if new_period != period {
+                period = new_period;
+                deadline = start;
+            }
+            deadline += period;
+            self.watchdog.disable();
+            let now = self.clock.now();
+            let overrun = now >= deadline;
+            if overrun {
+                tracing::warn!(
+                    event = ?EventTypes::Overrun,
+                    "Loop time of {:?} overrun by {:?}: {}",
+                    period,
+                    now.saturating_sub(deadline),
+                    timing
+                );
+                self.watchdog.print_epochs();
+                //skip the periods we missed instead of running them back to back
+                skip_missed_periods(&mut deadline, period, now);
+            }
+            self.loop_stats.record(timing, overrun);
+            *LOOP_STATS.lock() = self.loop_stats;
+
+            periodic.run_until(deadline);
+        }
+
+        if let Some(last_mode) = last_mode {
+            self.mode_end(last_mode);
+        }
+    }
+
+    fn end(&mut self) {
+        self.user_robot.robot_end();
+    }
+
+    fn get_mode(&self) -> RobotMode {
+        self.control_word.effective_mode()
+    }
+
+    fn get_control_word(&self) -> ControlWord {
+        self.control_word
+    }
+}
+impl Debug for RobotCoreImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RobotCoreImpl")
+            .field("mode_source", &self.mode_source.get_name())
+            .field("control_word", &self.control_word)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs the robot loop until `mode_source` closes or a shutdown is requested, then runs
+/// `robot_end`, which also runs if the loop panicked.
+///
+/// # Errors
+/// The panic that stopped the loop or `robot_end`, if either panicked.
+#[no_panic::no_panic]
+pub fn run_robot(
+    user_robot: Box<dyn UserRobot>,
+    mode_source: Box<dyn ModeSource>,
+) -> std::thread::Result<()> {
+    let mut robot = RobotCoreImpl::new(user_robot, mode_source);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| robot.start()));
+    if result.is_ok() {
+        tracing::info!("Robot exited");
+    } else {
+        tracing::error!("Robot loop panicked, ending the robot");
+    }
+    let ended = std::panic::catch_unwind(AssertUnwindSafe(|| robot.end()));
+    result.and(ended)
+}
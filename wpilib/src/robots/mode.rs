@@ -0,0 +1,237 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use parking_lot::Mutex;
+
+use super::RobotMode;
+use crate::driver_station::{AllianceStation, DriverStationData, JoystickData, MatchInfo};
+
+/// A snapshot of the control state the robot loop acts on.
+///
+/// `mode` is the mode selected on the driver station, which only takes effect
+/// while the robot is `enabled` and not `e_stopped`; see [`ControlWord::effective_mode`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ControlWord {
+    pub mode: RobotMode,
+    pub enabled: bool,
+    pub e_stopped: bool,
+    pub fms_attached: bool,
+    pub ds_attached: bool,
+}
+
+impl ControlWord {
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            mode: RobotMode::Disabled,
+            enabled: false,
+            e_stopped: false,
+            fms_attached: false,
+            ds_attached: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn enabled(mode: RobotMode) -> Self {
+        Self {
+            mode,
+            enabled: true,
+            e_stopped: false,
+            fms_attached: false,
+            ds_attached: true,
+        }
+    }
+
+    /// The mode the robot loop should run, disabled unless enabled and not e-stopped.
+    #[must_use]
+    pub const fn effective_mode(&self) -> RobotMode {
+        if self.enabled && !self.e_stopped {
+            self.mode
+        } else {
+            RobotMode::Disabled
+        }
+    }
+}
+
+impl Default for ControlWord {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+/// Supplies the robot loop with its control state once per cycle.
+pub trait ModeSource: Send {
+    /// Returns the current control state, or `None` once the source has shut down,
+    /// which ends the robot loop.
+    fn poll(&mut self) -> Option<ControlWord>;
+
+    /// Fills in the driver station state besides the control word, called after every
+    /// successful [`ModeSource::poll`].
+    fn update_driver_station(&mut self, _data: &mut DriverStationData) {}
+
+    fn get_name(&self) -> String {
+        String::from("unnamed mode source")
+    }
+}
+
+#[derive(Debug)]
+struct SimModeState {
+    data: DriverStationData,
+    closed: bool,
+}
+
+/// A stand-in for the simulation GUI, the control state is set through a [`SimModeHandle`].
+#[derive(Debug)]
+pub struct SimModeSource {
+    state: Arc<Mutex<SimModeState>>,
+}
+
+/// Shared handle used to drive a [`SimModeSource`] from another thread.
+#[derive(Debug, Clone)]
+pub struct SimModeHandle {
+    state: Arc<Mutex<SimModeState>>,
+}
+
+impl SimModeSource {
+    #[must_use]
+    pub fn new() -> (Self, SimModeHandle) {
+        let state = Arc::new(Mutex::new(SimModeState {
+            data: DriverStationData::new(),
+            closed: false,
+        }));
+        (
+            Self {
+                state: state.clone(),
+            },
+            SimModeHandle { state },
+        )
+    }
+}
+
+impl ModeSource for SimModeSource {
+    fn poll(&mut self) -> Option<ControlWord> {
+        let state = self.state.lock();
+        if state.closed {
+            None
+        } else {
+            Some(state.data.control_word)
+        }
+    }
+
+    fn update_driver_station(&mut self, data: &mut DriverStationData) {
+        *data = self.state.lock().data.clone();
+    }
+
+    fn get_name(&self) -> String {
+        String::from("simulation")
+    }
+}
+
+impl SimModeHandle {
+    pub fn set_mode(&self, mode: RobotMode) {
+        self.state.lock().data.control_word.mode = mode;
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.lock().data.control_word.enabled = enabled;
+    }
+
+    pub fn set_e_stopped(&self, e_stopped: bool) {
+        self.state.lock().data.control_word.e_stopped = e_stopped;
+    }
+
+    pub fn set_fms_attached(&self, fms_attached: bool) {
+        self.state.lock().data.control_word.fms_attached = fms_attached;
+    }
+
+    pub fn set_ds_attached(&self, ds_attached: bool) {
+        self.state.lock().data.control_word.ds_attached = ds_attached;
+    }
+
+    pub fn set_control_word(&self, control_word: ControlWord) {
+        self.state.lock().data.control_word = control_word;
+    }
+
+    #[must_use]
+    pub fn get_control_word(&self) -> ControlWord {
+        self.state.lock().data.control_word
+    }
+
+    pub fn set_station(&self, station: AllianceStation) {
+        self.state.lock().data.station = station;
+    }
+
+    pub fn set_match_time(&self, match_time: Option<f64>) {
+        self.state.lock().data.match_time = match_time;
+    }
+
+    pub fn set_match_info(&self, match_info: MatchInfo) {
+        self.state.lock().data.match_info = match_info;
+    }
+
+    pub fn set_game_specific_message(&self, message: &str) {
+        self.state.lock().data.game_specific_message = String::from(message);
+    }
+
+    pub fn set_joystick(&self, slot: usize, joystick: JoystickData) {
+        let joysticks = &mut self.state.lock().data.joysticks;
+        if joysticks.len() <= slot {
+            joysticks.resize(slot + 1, JoystickData::default());
+        }
+        joysticks[slot] = joystick;
+    }
+
+    /// Shuts the source down, the robot loop exits on its next cycle.
+    pub fn close(&self) {
+        self.state.lock().closed = true;
+    }
+}
+
+/// Plays back a fixed sequence of control states, each held for a number of loop cycles.
+/// Once the sequence is exhausted the source shuts down.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedModeSource {
+    steps: VecDeque<(ControlWord, usize)>,
+}
+
+impl ScriptedModeSource {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            steps: VecDeque::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn then(mut self, control_word: ControlWord, cycles: usize) -> Self {
+        self.steps.push_back((control_word, cycles));
+        self
+    }
+
+    #[must_use]
+    pub fn then_disabled(self, cycles: usize) -> Self {
+        self.then(ControlWord::disabled(), cycles)
+    }
+
+    #[must_use]
+    pub fn then_enabled(self, mode: RobotMode, cycles: usize) -> Self {
+        self.then(ControlWord::enabled(mode), cycles)
+    }
+}
+
+impl ModeSource for ScriptedModeSource {
+    fn poll(&mut self) -> Option<ControlWord> {
+        while let Some((control_word, cycles)) = self.steps.front_mut() {
+            if *cycles > 0 {
+                *cycles -= 1;
+                return Some(*control_word);
+            }
+            self.steps.pop_front();
+        }
+        None
+    }
+
+    fn get_name(&self) -> String {
+        String::from("scripted")
+    }
+}
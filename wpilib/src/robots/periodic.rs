@@ -0,0 +1,278 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+use super::{skip_missed_periods, JitterStats};
+use crate::{
+    timer::{get_default_clock, ClockRef},
+    EventTypes,
+};
+
+/// Where the callbacks a robot loop runs between its cycles are registered, every robot
+/// loop has its own and hands it to [`UserRobot::add_periodics`](super::UserRobot::add_periodics).
+///
+/// Clones share the same callbacks, so one can be kept to add more later.
+#[derive(Clone)]
+pub struct PeriodicScheduler {
+    //callbacks registered since the robot loop last checked, picked up before each wait
+    pending: Arc<Mutex<Vec<PeriodicCallback>>>,
+    clock: ClockRef,
+}
+
+impl PeriodicScheduler {
+    /// A scheduler on the [default clock](crate::timer::get_default_clock).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_clock(get_default_clock())
+    }
+
+    /// A scheduler that times and waits for its callbacks on `clock`.
+    #[must_use]
+    pub fn with_clock(clock: ClockRef) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(Vec::new())),
+            clock,
+        }
+    }
+
+    /// Registers a callback that the robot loop runs every `period`, shifted by `offset`
+    /// from the start of the main loop, until its handle is cancelled.
+    ///
+    /// Callbacks are interleaved with the main loop on the same thread. When several are due
+    /// at once the main loop runs first, then callbacks in the order they were registered.
+    ///
+    /// # Panics
+    /// If `period` is zero.
+    pub fn add_periodic(
+        &self,
+        name: &str,
+        period: Duration,
+        offset: Duration,
+        callback: impl FnMut() + Send + 'static,
+    ) -> PeriodicHandle {
+        assert!(
+            !period.is_zero(),
+            "periodic callback {name} has a zero period"
+        );
+        let stats = Arc::new(Mutex::new(CallbackStats::new()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let now = self.clock.now();
+        self.pending.lock().push(PeriodicCallback {
+            name: String::from(name),
+            period,
+            offset,
+            registered_at: now,
+            deadline: now,
+            callback: Box::new(callback),
+            stats: stats.clone(),
+            cancelled: cancelled.clone(),
+        });
+        PeriodicHandle {
+            name: String::from(name),
+            period,
+            offset,
+            stats,
+            cancelled,
+        }
+    }
+}
+
+impl Default for PeriodicScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for PeriodicScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PeriodicScheduler")
+            .field("pending", &self.pending.lock().len())
+            .field("clock", &self.clock)
+            .finish()
+    }
+}
+
+/// Timing statistics of a single periodic callback.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CallbackStats {
+    pub runs: u64,
+    pub overruns: u64,
+    pub last_duration: Duration,
+    pub worst_duration: Duration,
+    pub jitter: JitterStats,
+}
+
+impl CallbackStats {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            runs: 0,
+            overruns: 0,
+            last_duration: Duration::ZERO,
+            worst_duration: Duration::ZERO,
+            jitter: JitterStats::new(),
+        }
+    }
+}
+
+/// Returned by [`PeriodicScheduler::add_periodic`], reads the statistics of the registered
+/// callback and cancels it.
+#[derive(Debug, Clone)]
+pub struct PeriodicHandle {
+    name: String,
+    period: Duration,
+    offset: Duration,
+    stats: Arc<Mutex<CallbackStats>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PeriodicHandle {
+    #[must_use]
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub const fn get_period(&self) -> Duration {
+        self.period
+    }
+
+    #[must_use]
+    pub const fn get_offset(&self) -> Duration {
+        self.offset
+    }
+
+    #[must_use]
+    pub fn get_stats(&self) -> CallbackStats {
+        *self.stats.lock()
+    }
+
+    /// Stops the callback from running again, it's dropped before the robot loop next waits.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+struct PeriodicCallback {
+    name: String,
+    period: Duration,
+    offset: Duration,
+    registered_at: Duration,
+    deadline: Duration,
+    callback: Box<dyn FnMut() + Send>,
+    stats: Arc<Mutex<CallbackStats>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PeriodicCallback {
+    fn run(&mut self, clock: &ClockRef, deadline: Duration) {
+        let start = clock.now();
+        (self.callback)();
+        let now = clock.now();
+
+        let mut next = deadline + self.period;
+        let overrun = now >= next;
+        if overrun {
+            tracing::warn!(
+                event = ?EventTypes::Overrun,
+                "Periodic callback {} with period {:?} overrun by {:?}",
+                self.name,
+                self.period,
+                now.saturating_sub(next)
+            );
+            skip_missed_periods(&mut next, self.period, now);
+        }
+        self.deadline = next;
+
+        let duration = now.saturating_sub(start);
+        let mut stats = self.stats.lock();
+        stats.runs += 1;
+        if overrun {
+            stats.overruns += 1;
+        }
+        stats.jitter.record(start.saturating_sub(deadline));
+        stats.last_duration = duration;
+        stats.worst_duration = stats.worst_duration.max(duration);
+    }
+}
+
+/// Runs the callbacks registered on a [`PeriodicScheduler`] between cycles of the main loop.
+pub(crate) struct PeriodicRunner {
+    scheduler: PeriodicScheduler,
+    anchor: Duration,
+    callbacks: Vec<PeriodicCallback>,
+}
+
+impl PeriodicRunner {
+    pub(crate) const fn new(scheduler: PeriodicScheduler, anchor: Duration) -> Self {
+        Self {
+            scheduler,
+            anchor,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Runs every callback that comes due before `until`, sleeping in between,
+    /// then sleeps until `until`.
+    pub(crate) fn run_until(&mut self, until: Duration) {
+        loop {
+            self.take_pending();
+            //min_by_key keeps the first of equal deadlines, which is registration order
+            let next = self
+                .callbacks
+                .iter_mut()
+                .filter(|callback| callback.deadline < until)
+                .min_by_key(|callback| callback.deadline);
+            let Some(callback) = next else {
+                break;
+            };
+            let deadline = callback.deadline;
+            self.scheduler.clock.sleep_until(deadline);
+            callback.run(&self.scheduler.clock, deadline);
+        }
+        self.scheduler.clock.sleep_until(until);
+    }
+
+    //callbacks keep their phase relative to the main loop even when registered late,
+    //the first run is the first phase-aligned time after they were registered
+    fn take_pending(&mut self) {
+        let pending = std::mem::take(&mut *self.scheduler.pending.lock());
+        for mut callback in pending {
+            let mut deadline = self.anchor + callback.offset;
+            if deadline < callback.registered_at {
+                skip_missed_periods(&mut deadline, callback.period, callback.registered_at);
+            }
+            callback.deadline = deadline;
+            self.callbacks.push(callback);
+        }
+        self.callbacks
+            .retain(|callback| !callback.cancelled.load(Ordering::Acquire));
+    }
+}
+
+impl Debug for PeriodicRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PeriodicRunner")
+            .field(
+                "callbacks",
+                &self
+                    .callbacks
+                    .iter()
+                    .map(|callback| callback.name.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    backtrace::Backtrace,
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+    },
+};
+
+use parking_lot::Mutex;
+use signal_hook::{
+    consts::{SIGINT, SIGTERM},
+    iterator::Signals,
+};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+
+//reports waiting to be taken, the oldest are dropped when nobody is reading
+const MAX_ERROR_REPORTS: usize = 64;
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+static ERROR_REPORTS: Mutex<VecDeque<ErrorReport>> = Mutex::new(VecDeque::new());
+static ERROR_SUBSCRIBERS: Mutex<Vec<SyncSender<ErrorReport>>> = Mutex::new(Vec::new());
+
+/// Asks the robot loop to stop, it finishes the current cycle, ends the current mode
+/// and then runs `robot_end`.
+pub fn request_shutdown() {
+    SHUTDOWN.store(true, Ordering::SeqCst);
+}
+
+#[must_use]
+pub fn is_shutdown_requested() -> bool {
+    SHUTDOWN.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+pub(crate) fn clear_shutdown() {
+    SHUTDOWN.store(false, Ordering::SeqCst);
+}
+
+/// Requests a shutdown on SIGINT or SIGTERM, a second signal exits the process
+/// in case the shutdown itself hangs.
+///
+/// # Errors
+/// If the signal handlers can't be registered.
+pub fn install_signal_handlers() -> std::io::Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    std::thread::Builder::new()
+        .name(String::from("signal handler"))
+        .spawn(move || {
+            for signal in &mut signals {
+                if is_shutdown_requested() {
+                    tracing::error!("Received signal {} during shutdown, exiting", signal);
+                    std::process::exit(1);
+                }
+                tracing::info!("Received signal {}, shutting down", signal);
+                request_shutdown();
+            }
+        })?;
+    Ok(())
+}
+
+#[must_use]
+pub fn default_log_dir() -> PathBuf {
+    if cfg!(feature = "rio") {
+        PathBuf::from("/home/lvuser/logs")
+    } else {
+        PathBuf::from("logs")
+    }
+}
+
+/// Logs to stdout and to a daily rolling file in `log_dir`.
+///
+/// The returned guard flushes the file when dropped, keep it alive for as long as the
+/// robot runs. If a global subscriber is already set it is left in place.
+pub fn init_logging(log_dir: &Path) -> WorkerGuard {
+    let (file_writer, guard) =
+        tracing_appender::non_blocking(tracing_appender::rolling::daily(log_dir, "robot.log"));
+    let result = tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .try_init();
+    if let Err(err) = result {
+        tracing::warn!("Logging was already initialized: {}", err);
+    }
+    guard
+}
+
+/// An error to show on the driver station.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    pub message: String,
+    pub location: String,
+    pub backtrace: String,
+}
+
+/// Logs `report`, sends it to every subscriber, like a running
+/// [`DriverStationServer`](crate::driver_station::DriverStationServer), and queues it for
+/// [`take_error_reports`].
+pub fn report_error(report: ErrorReport) {
+    tracing::error!(
+        "{} at {}\nbacktrace:\n{}",
+        report.message,
+        report.location,
+        report.backtrace
+    );
+    //subscribers that are full miss the report, ones that were dropped are forgotten
+    ERROR_SUBSCRIBERS.lock().retain(|subscriber| {
+        !matches!(
+            subscriber.try_send(report.clone()),
+            Err(TrySendError::Disconnected(_))
+        )
+    });
+    let mut reports = ERROR_REPORTS.lock();
+    if reports.len() >= MAX_ERROR_REPORTS {
+        reports.pop_front();
+    }
+    reports.push_back(report);
+}
+
+/// Takes the error reports that haven't been taken yet.
+#[must_use]
+pub fn take_error_reports() -> Vec<ErrorReport> {
+    ERROR_REPORTS.lock().drain(..).collect()
+}
+
+/// Receives every error reported from now on, up to `capacity` of them are held while
+/// they aren't being received.
+#[must_use]
+pub fn subscribe_errors(capacity: usize) -> Receiver<ErrorReport> {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    ERROR_SUBSCRIBERS.lock().push(sender);
+    receiver
+}
+
+/// Reports every panic through [`report_error`] with its location, message and backtrace.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("Box<dyn Any>"));
+        let location = info
+            .location()
+            .map_or_else(|| String::from("unknown location"), ToString::to_string);
+        report_error(ErrorReport {
+            message: format!("Robot panicked: {message}"),
+            location,
+            backtrace: Backtrace::force_capture().to_string(),
+        });
+    }));
+}
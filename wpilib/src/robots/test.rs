@@ -0,0 +1,539 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::timer::{Clock, SimClock};
+
+use super::{
+    run_robot, runtime, set_periodic_time, skip_missed_periods, ControlWord, JitterStats,
+    ModeSource, PeriodicHandle, PeriodicScheduler, RobotCore, RobotCoreImpl, RobotMode,
+    ScriptedModeSource, SimModeSource, Tracer, UserRobot, Watchdog, LOOP_TEST_LOCK,
+};
+
+struct RecordingRobot {
+    events: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl RecordingRobot {
+    fn new() -> (Self, Arc<Mutex<Vec<&'static str>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        (
+            Self {
+                events: events.clone(),
+            },
+            events,
+        )
+    }
+
+    fn push(&self, event: &'static str) {
+        self.events.lock().push(event);
+    }
+}
+
+impl UserRobot for RecordingRobot {
+    fn robot_init(&mut self) {
+        self.push("robot_init");
+    }
+    fn robot_periodic(&mut self) {}
+    fn robot_end(&mut self) {
+        self.push("robot_end");
+    }
+
+    fn robot_disabled_init(&mut self) {
+        self.push("disabled_init");
+    }
+    fn robot_disabled_periodic(&mut self) {
+        self.push("disabled_periodic");
+    }
+    fn robot_disabled_end(&mut self) {
+        self.push("disabled_end");
+    }
+
+    fn robot_autonomous_init(&mut self) {
+        self.push("autonomous_init");
+    }
+    fn robot_autonomous_periodic(&mut self) {
+        self.push("autonomous_periodic");
+    }
+    fn robot_autonomous_end(&mut self) {
+        self.push("autonomous_end");
+    }
+
+    fn robot_teleop_init(&mut self) {
+        self.push("teleop_init");
+    }
+    fn robot_teleop_periodic(&mut self) {
+        self.push("teleop_periodic");
+    }
+    fn robot_teleop_end(&mut self) {
+        self.push("teleop_end");
+    }
+}
+
+fn run_scripted(source: ScriptedModeSource) -> Vec<&'static str> {
+    let _guard = LOOP_TEST_LOCK.lock();
+    let (robot, events) = RecordingRobot::new();
+    run_robot(Box::new(robot), Box::new(source)).expect("robot shouldn't panic");
+    let events = events.lock().clone();
+    events
+}
+
+#[test]
+fn control_word() {
+    assert_eq!(
+        ControlWord::enabled(RobotMode::Teleop).effective_mode(),
+        RobotMode::Teleop
+    );
+
+    let mut e_stopped = ControlWord::enabled(RobotMode::Autonomous);
+    e_stopped.e_stopped = true;
+    assert_eq!(e_stopped.effective_mode(), RobotMode::Disabled);
+
+    let mut not_enabled = ControlWord::enabled(RobotMode::Test);
+    not_enabled.enabled = false;
+    assert_eq!(not_enabled.effective_mode(), RobotMode::Disabled);
+}
+
+#[test]
+fn scripted_source() {
+    let mut source = ScriptedModeSource::new()
+        .then_disabled(1)
+        .then_enabled(RobotMode::Teleop, 0)
+        .then_enabled(RobotMode::Autonomous, 2);
+
+    assert_eq!(source.poll(), Some(ControlWord::disabled()));
+    assert_eq!(
+        source.poll(),
+        Some(ControlWord::enabled(RobotMode::Autonomous))
+    );
+    assert_eq!(
+        source.poll(),
+        Some(ControlWord::enabled(RobotMode::Autonomous))
+    );
+    assert_eq!(source.poll(), None);
+}
+
+#[test]
+fn mode_transitions() {
+    let events = run_scripted(
+        ScriptedModeSource::new()
+            .then_disabled(1)
+            .then_enabled(RobotMode::Autonomous, 2)
+            .then_enabled(RobotMode::Teleop, 1),
+    );
+
+    assert_eq!(
+        events,
+        vec![
+            "robot_init",
+            "disabled_init",
+            "disabled_periodic",
+            "disabled_end",
+            "autonomous_init",
+            "autonomous_periodic",
+            "autonomous_periodic",
+            "autonomous_end",
+            "teleop_init",
+            "teleop_periodic",
+            "teleop_end",
+            "robot_end",
+        ]
+    );
+}
+
+#[test]
+fn e_stop_disables() {
+    let mut e_stopped = ControlWord::enabled(RobotMode::Teleop);
+    e_stopped.e_stopped = true;
+
+    let events = run_scripted(
+        ScriptedModeSource::new()
+            .then_enabled(RobotMode::Teleop, 1)
+            .then(e_stopped, 1),
+    );
+
+    assert_eq!(
+        events,
+        vec![
+            "robot_init",
+            "teleop_init",
+            "teleop_periodic",
+            "teleop_end",
+            "disabled_init",
+            "disabled_periodic",
+            "disabled_end",
+            "robot_end",
+        ]
+    );
+}
+
+#[test]
+fn sim_source() {
+    let (mut source, handle) = SimModeSource::new();
+    assert_eq!(source.poll(), Some(ControlWord::disabled()));
+
+    handle.set_mode(RobotMode::Autonomous);
+    handle.set_enabled(true);
+    handle.set_fms_attached(true);
+    let control_word = source.poll().expect("source should still be open");
+    assert_eq!(control_word.effective_mode(), RobotMode::Autonomous);
+    assert!(control_word.fms_attached);
+
+    handle.close();
+    assert_eq!(source.poll(), None);
+}
+
+#[test]
+fn control_word_is_exposed() {
+    let mut robot = RobotCoreImpl::new(
+        Box::new(RecordingRobot::new().0),
+        Box::new(ScriptedModeSource::new()),
+    );
+    assert_eq!(robot.get_mode(), RobotMode::Disabled);
+    robot.start();
+    assert_eq!(robot.get_control_word(), ControlWord::disabled());
+}
+
+struct SlowRobot {
+    work: Duration,
+}
+
+impl UserRobot for SlowRobot {
+    fn robot_init(&mut self) {}
+    fn robot_periodic(&mut self) {
+        std::thread::sleep(self.work);
+    }
+    fn robot_end(&mut self) {}
+}
+
+fn run_timed(work: Duration, period: f64, cycles: usize) -> RobotCoreImpl {
+    let _guard = LOOP_TEST_LOCK.lock();
+    set_periodic_time(period);
+    let mut robot = RobotCoreImpl::new(
+        Box::new(SlowRobot { work }),
+        Box::new(ScriptedModeSource::new().then_disabled(cycles)),
+    );
+    robot.start();
+    set_periodic_time(0.02);
+    robot
+}
+
+#[test]
+fn jitter_stats() {
+    let mut jitter = JitterStats::default();
+    assert_eq!(jitter.std_dev(), Duration::ZERO);
+
+    jitter.record(Duration::from_millis(1));
+    jitter.record(Duration::from_millis(3));
+
+    assert_eq!(jitter.samples(), 2);
+    assert_eq!(jitter.min(), Duration::from_millis(1));
+    assert_eq!(jitter.max(), Duration::from_millis(3));
+    assert!((jitter.mean().as_secs_f64() - 0.002).abs() < 1e-9);
+    assert_eq!(jitter.std_dev().as_micros(), 1414);
+}
+
+#[test]
+fn overrun_detection() {
+    let robot = run_timed(Duration::from_millis(15), 0.005, 3);
+    let stats = robot.loop_stats();
+
+    assert_eq!(stats.cycles, 3);
+    assert_eq!(stats.overruns, 3);
+    assert!(stats.last_timing.robot_periodic >= Duration::from_millis(15));
+    assert!(stats.worst_timing.total() >= stats.last_timing.robot_periodic);
+}
+
+#[test]
+fn stable_period() {
+    let start = Instant::now();
+    let robot = run_timed(Duration::from_millis(2), 0.01, 5);
+    let elapsed = start.elapsed();
+
+    //the first cycle runs immediately, the other four each wait for their deadline
+    assert!(elapsed >= Duration::from_millis(40));
+    assert_eq!(robot.loop_stats().cycles, 5);
+    assert_eq!(robot.loop_stats().jitter.samples(), 5);
+}
+
+#[test]
+fn tracer_epochs() {
+    let clock = SimClock::new();
+    let mut tracer = Tracer::with_clock(Arc::new(clock.clone()));
+    tracer.add_epoch("first");
+    clock.step(Duration::from_millis(2));
+    tracer.add_epoch("second");
+    tracer.add_epoch("first");
+
+    let names: Vec<&str> = tracer
+        .get_epochs()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, vec!["first", "second"]);
+    assert_eq!(tracer.get_epoch("second"), Some(Duration::from_millis(2)));
+    assert!(tracer.get_epoch("third").is_none());
+    assert!(tracer.to_string().contains("second"));
+
+    tracer.clear_epochs();
+    assert!(tracer.get_epochs().is_empty());
+}
+
+#[test]
+fn watchdog_expiry() {
+    let expirations = Arc::new(AtomicUsize::new(0));
+    let counter = expirations.clone();
+    let clock = SimClock::new();
+    let mut watchdog = Watchdog::new(Duration::from_millis(5))
+        .with_clock(Arc::new(clock.clone()))
+        .with_callback(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+    watchdog.enable();
+    watchdog.add_epoch("fast");
+    assert!(!watchdog.disable());
+
+    watchdog.enable();
+    clock.step(Duration::from_millis(10));
+    assert_eq!(watchdog.get_time(), Duration::from_millis(10));
+    watchdog.add_epoch("slow");
+    watchdog.add_epoch("after");
+    assert!(watchdog.is_expired());
+    assert!(watchdog.disable());
+    //the callback runs once per expiration, not once per epoch
+    assert_eq!(expirations.load(Ordering::Relaxed), 1);
+    assert!(watchdog.get_tracer().get_epoch("slow").is_some());
+
+    watchdog.enable();
+    assert!(!watchdog.is_expired());
+    assert!(watchdog.get_tracer().get_epochs().is_empty());
+}
+
+#[test]
+fn loop_watchdog_epochs() {
+    let robot = run_timed(Duration::from_millis(1), 0.02, 1);
+    let tracer = robot.watchdog().get_tracer();
+
+    assert!(tracer.get_epoch("robot_disabled_init()").is_some());
+    assert!(tracer.get_epoch("robot_disabled_periodic()").is_some());
+    assert!(
+        tracer
+            .get_epoch("robot_periodic()")
+            .expect("epoch was added")
+            >= Duration::from_millis(1)
+    );
+    assert!(!robot.watchdog().is_enabled());
+}
+
+#[test]
+fn missed_periods() {
+    let start = Duration::from_secs(1);
+    let period = Duration::from_millis(10);
+
+    let mut deadline = start;
+    skip_missed_periods(&mut deadline, period, start + Duration::from_millis(25));
+    assert_eq!(deadline, start + Duration::from_millis(30));
+
+    //a deadline still in the future is left alone
+    let mut deadline = start + period;
+    skip_missed_periods(&mut deadline, period, start);
+    assert_eq!(deadline, start + period);
+}
+
+struct PeriodicRobot {
+    events: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl UserRobot for PeriodicRobot {
+    fn add_periodics(&mut self, periodic: &PeriodicScheduler) {
+        let events = self.events.clone();
+        periodic.add_periodic(
+            "fast",
+            Duration::from_millis(10),
+            Duration::ZERO,
+            move || {
+                events.lock().push("fast");
+            },
+        );
+        let events = self.events.clone();
+        periodic.add_periodic(
+            "offset",
+            Duration::from_millis(20),
+            Duration::from_millis(10),
+            move || events.lock().push("offset"),
+        );
+        //cancels itself the first time it runs
+        let events = self.events.clone();
+        let once = Arc::new(Mutex::new(None::<PeriodicHandle>));
+        let handle = once.clone();
+        *once.lock() = Some(periodic.add_periodic(
+            "once",
+            Duration::from_millis(10),
+            Duration::ZERO,
+            move || {
+                events.lock().push("once");
+                if let Some(handle) = &*handle.lock() {
+                    handle.cancel();
+                }
+            },
+        ));
+    }
+    fn robot_init(&mut self) {}
+    fn robot_periodic(&mut self) {
+        self.events.lock().push("main");
+    }
+    fn robot_end(&mut self) {}
+}
+
+#[test]
+fn periodic_ordering() {
+    let _guard = LOOP_TEST_LOCK.lock();
+    set_periodic_time(0.02);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    //on a sim clock the loop steps time to each deadline instead of sleeping
+    let clock = SimClock::new();
+    let mut robot = RobotCoreImpl::with_clock(
+        Box::new(PeriodicRobot {
+            events: events.clone(),
+        }),
+        Box::new(ScriptedModeSource::new().then_disabled(2)),
+        Arc::new(clock.clone()),
+    );
+    robot.start();
+
+    //due callbacks run after the main loop and in registration order,
+    //"offset" runs halfway between main loop cycles alongside the second "fast"
+    assert_eq!(
+        *events.lock(),
+        vec!["main", "fast", "once", "fast", "offset", "main", "fast", "fast", "offset"]
+    );
+    assert_eq!(clock.now(), Duration::from_millis(40));
+    assert_eq!(robot.loop_stats().overruns, 0);
+    assert_eq!(robot.loop_stats().jitter.max(), Duration::ZERO);
+}
+
+#[test]
+fn periodic_overruns() {
+    let _guard = LOOP_TEST_LOCK.lock();
+    set_periodic_time(0.05);
+    let mut robot = RobotCoreImpl::new(
+        Box::new(SlowRobot {
+            work: Duration::ZERO,
+        }),
+        Box::new(ScriptedModeSource::new().then_disabled(1)),
+    );
+    let handle = robot.periodic_scheduler().add_periodic(
+        "slow",
+        Duration::from_millis(5),
+        Duration::ZERO,
+        || {
+            std::thread::sleep(Duration::from_millis(8));
+        },
+    );
+    robot.start();
+    set_periodic_time(0.02);
+
+    let stats = handle.get_stats();
+    assert_eq!(handle.get_name(), "slow");
+    assert!(stats.runs >= 3);
+    assert_eq!(stats.overruns, stats.runs);
+    assert!(stats.worst_duration >= Duration::from_millis(8));
+    //the main loop itself never overran
+    assert_eq!(robot.loop_stats().overruns, 0);
+}
+
+struct ShutdownRobot {
+    cycles: Arc<AtomicUsize>,
+    ended: Arc<AtomicUsize>,
+}
+
+impl UserRobot for ShutdownRobot {
+    fn robot_init(&mut self) {}
+    fn robot_periodic(&mut self) {
+        if self.cycles.fetch_add(1, Ordering::Relaxed) == 2 {
+            runtime::request_shutdown();
+        }
+    }
+    fn robot_end(&mut self) {
+        self.ended.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn shutdown_request() {
+    let _guard = LOOP_TEST_LOCK.lock();
+    set_periodic_time(0.001);
+    let cycles = Arc::new(AtomicUsize::new(0));
+    let ended = Arc::new(AtomicUsize::new(0));
+    //the sim source never closes on its own, only the shutdown request ends the loop
+    let (source, _handle) = SimModeSource::new();
+    run_robot(
+        Box::new(ShutdownRobot {
+            cycles: cycles.clone(),
+            ended: ended.clone(),
+        }),
+        Box::new(source),
+    )
+    .expect("robot shouldn't panic");
+    assert!(runtime::is_shutdown_requested());
+    runtime::clear_shutdown();
+    set_periodic_time(0.02);
+
+    assert_eq!(cycles.load(Ordering::Relaxed), 3);
+    assert_eq!(ended.load(Ordering::Relaxed), 1);
+}
+
+struct PanickingRobot {
+    ended: Arc<AtomicUsize>,
+}
+
+impl UserRobot for PanickingRobot {
+    fn robot_init(&mut self) {}
+    fn robot_periodic(&mut self) {
+        panic!("robot periodic panicked");
+    }
+    fn robot_end(&mut self) {
+        self.ended.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn panic_still_ends_robot() {
+    let _guard = LOOP_TEST_LOCK.lock();
+    let ended = Arc::new(AtomicUsize::new(0));
+    let (source, _handle) = SimModeSource::new();
+    let result = run_robot(
+        Box::new(PanickingRobot {
+            ended: ended.clone(),
+        }),
+        Box::new(source),
+    );
+    assert!(result.is_err());
+    assert_eq!(ended.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn error_reports() {
+    let _guard = LOOP_TEST_LOCK.lock();
+    let _ = runtime::take_error_reports();
+    for i in 0..100 {
+        runtime::report_error(runtime::ErrorReport {
+            message: format!("error {i}"),
+            location: String::from("test.rs:1:1"),
+            backtrace: String::new(),
+        });
+    }
+    let reports = runtime::take_error_reports();
+    //only the newest reports are kept while nothing drains the queue
+    assert_eq!(reports.len(), 64);
+    assert_eq!(reports[0].message, "error 36");
+    assert_eq!(reports[63].message, "error 99");
+    assert!(runtime::take_error_reports().is_empty());
+}
@@ -0,0 +1,227 @@
+use std::{
+    fmt::{Debug, Display},
+    time::Duration,
+};
+
+use crate::timer::{get_default_clock, ClockRef};
+
+/// Records how long named sections ("epochs") of code took, in the order they ran.
+///
+/// Adding an epoch with a name that was already recorded accumulates into it.
+#[derive(Debug, Clone)]
+pub struct Tracer {
+    clock: ClockRef,
+    start: Duration,
+    epochs: Vec<(String, Duration)>,
+}
+
+impl Tracer {
+    /// A tracer timing epochs on the [default clock](crate::timer::get_default_clock).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_clock(get_default_clock())
+    }
+
+    #[must_use]
+    pub fn with_clock(clock: ClockRef) -> Self {
+        Self {
+            start: clock.now(),
+            clock,
+            epochs: Vec::new(),
+        }
+    }
+
+    /// Restarts the timer used to measure the next epoch.
+    pub fn reset_timer(&mut self) {
+        self.start = self.clock.now();
+    }
+
+    pub fn clear_epochs(&mut self) {
+        self.epochs.clear();
+        self.reset_timer();
+    }
+
+    /// Records the time since the last epoch (or reset) under `name`.
+    pub fn add_epoch(&mut self, name: &str) {
+        let now = self.clock.now();
+        let elapsed = now.saturating_sub(self.start);
+        self.start = now;
+        if let Some((_, duration)) = self.epochs.iter_mut().find(|(epoch, _)| epoch == name) {
+            *duration += elapsed;
+        } else {
+            self.epochs.push((String::from(name), elapsed));
+        }
+    }
+
+    #[must_use]
+    pub fn get_epochs(&self) -> &[(String, Duration)] {
+        &self.epochs
+    }
+
+    #[must_use]
+    pub fn get_epoch(&self, name: &str) -> Option<Duration> {
+        self.epochs
+            .iter()
+            .find(|(epoch, _)| epoch == name)
+            .map(|(_, duration)| *duration)
+    }
+
+    pub fn print_epochs(&self) {
+        tracing::info!("{}", self);
+    }
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Tracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, duration) in &self.epochs {
+            writeln!(f, "\t{name}: {:.6}s", duration.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks whether a section of code finished within a time budget and reports which
+/// epochs used the time when it didn't.
+///
+/// Expiration is checked whenever an epoch is added and when the watchdog is disabled,
+/// so the report is made from the thread doing the work rather than a timer thread.
+pub struct Watchdog {
+    timeout: Duration,
+    clock: ClockRef,
+    start: Duration,
+    enabled: bool,
+    expired: bool,
+    suppress_timeout_message: bool,
+    tracer: Tracer,
+    callback: Option<Box<dyn FnMut() + Send>>,
+}
+
+impl Watchdog {
+    /// A disarmed watchdog on the [default clock](crate::timer::get_default_clock).
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        let clock = get_default_clock();
+        Self {
+            timeout,
+            start: clock.now(),
+            tracer: Tracer::with_clock(clock.clone()),
+            clock,
+            enabled: false,
+            expired: false,
+            suppress_timeout_message: false,
+            callback: None,
+        }
+    }
+
+    /// Times the watchdog and its epochs on `clock` instead.
+    #[must_use]
+    pub fn with_clock(mut self, clock: ClockRef) -> Self {
+        self.start = clock.now();
+        self.tracer = Tracer::with_clock(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// Sets a callback that runs once each time the watchdog expires.
+    #[must_use]
+    pub fn with_callback(mut self, callback: impl FnMut() + Send + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Arms the watchdog, restarting its timer and clearing the previous epochs.
+    pub fn enable(&mut self) {
+        self.start = self.clock.now();
+        self.enabled = true;
+        self.expired = false;
+        self.tracer.clear_epochs();
+    }
+
+    /// Disarms the watchdog, returning whether it expired while armed.
+    pub fn disable(&mut self) -> bool {
+        if self.enabled {
+            self.check_expired();
+            self.enabled = false;
+        }
+        self.expired
+    }
+
+    pub fn add_epoch(&mut self, name: &str) {
+        self.tracer.add_epoch(name);
+        self.check_expired();
+    }
+
+    #[must_use]
+    pub const fn is_expired(&self) -> bool {
+        self.expired
+    }
+
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Time since the watchdog was last armed.
+    #[must_use]
+    pub fn get_time(&self) -> Duration {
+        self.clock.now().saturating_sub(self.start)
+    }
+
+    #[must_use]
+    pub const fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub const fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Stops the watchdog from logging on its own when it expires, for owners that
+    /// report the expiration themselves.
+    pub const fn suppress_timeout_message(&mut self, suppress: bool) {
+        self.suppress_timeout_message = suppress;
+    }
+
+    #[must_use]
+    pub const fn get_tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
+    pub fn print_epochs(&self) {
+        self.tracer.print_epochs();
+    }
+
+    fn check_expired(&mut self) {
+        if !self.enabled || self.expired || self.get_time() <= self.timeout {
+            return;
+        }
+        self.expired = true;
+        if !self.suppress_timeout_message {
+            tracing::warn!(
+                "Watchdog not fed within {:.6}s\n{}",
+                self.timeout.as_secs_f64(),
+                self.tracer
+            );
+        }
+        if let Some(callback) = self.callback.as_mut() {
+            callback();
+        }
+    }
+}
+
+impl Debug for Watchdog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("timeout", &self.timeout)
+            .field("enabled", &self.enabled)
+            .field("expired", &self.expired)
+            .field("tracer", &self.tracer)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+
+use crate::math::units::time::{Microsecond, Second};
+
+#[cfg(test)]
+mod test;
+
+/// A monotonic source of time, everything time based in the framework reads one.
+///
+/// Swap the default with [`set_default_clock`] to run a whole simulation on a [`SimClock`],
+/// or hand a clock to a single component to test it on its own.
+pub trait Clock: Debug + Send + Sync {
+    /// Time since the clock's epoch, never goes backwards.
+    fn now(&self) -> Duration;
+
+    /// Waits until the clock reads `deadline`, returns straight away if it already does.
+    fn sleep_until(&self, deadline: Duration) {
+        std::thread::sleep(deadline.saturating_sub(self.now()));
+    }
+}
+
+pub type ClockRef = Arc<dyn Clock>;
+
+//when the robot program started, the epoch of the fpga-like clock
+static PROGRAM_START: Lazy<Instant> = Lazy::new(Instant::now);
+static DEFAULT_CLOCK: Lazy<RwLock<ClockRef>> =
+    Lazy::new(|| RwLock::new(Arc::new(RealClock::new())));
+
+/// The clock components are built with unless they're given one, a [`RealClock`] to start with.
+#[must_use]
+pub fn get_default_clock() -> ClockRef {
+    DEFAULT_CLOCK.read().clone()
+}
+
+/// Replaces the default clock, components that were already built keep reading their old one.
+pub fn set_default_clock(clock: impl Clock + 'static) {
+    *DEFAULT_CLOCK.write() = Arc::new(clock);
+}
+
+/// The time on the default clock.
+#[must_use]
+pub fn get_timestamp() -> Second {
+    Second::new(get_default_clock().now().as_secs_f64())
+}
+
+/// Real time, measured from when the clock was made.
+#[derive(Debug, Clone, Copy)]
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Real time in whole microseconds since the robot program started, like the roboRIO's
+/// FPGA timestamp.
+///
+/// Every fpga clock reads the same time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FpgaClock;
+
+impl FpgaClock {
+    #[must_use]
+    pub fn get_timestamp(self) -> Microsecond {
+        Microsecond::new(i64::try_from(PROGRAM_START.elapsed().as_micros()).unwrap_or(i64::MAX))
+    }
+}
+
+impl Clock for FpgaClock {
+    fn now(&self) -> Duration {
+        Duration::from_micros(
+            u64::try_from(PROGRAM_START.elapsed().as_micros()).unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// Time that only moves when it's stepped, starting at zero.
+///
+/// Clones share the same time, so a test or simulation can keep one to step while the
+/// components it's testing read the others. Sleeping on it steps it to the deadline, a
+/// robot loop on a sim clock runs as fast as it can.
+#[derive(Debug, Clone, Default)]
+pub struct SimClock {
+    time: Arc<Mutex<Duration>>,
+}
+
+impl SimClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward by `time`.
+    pub fn step(&self, time: Duration) {
+        *self.time.lock() += time;
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Duration {
+        *self.time.lock()
+    }
+
+    fn sleep_until(&self, deadline: Duration) {
+        let mut time = self.time.lock();
+        *time = (*time).max(deadline);
+    }
+}
+
+/// A stopwatch on a [`Clock`], it accumulates time while running.
+#[derive(Debug, Clone)]
+pub struct Timer {
+    clock: ClockRef,
+    accumulated: Duration,
+    started_at: Option<Duration>,
+}
+
+impl Timer {
+    /// A stopped timer on the default clock.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_clock(get_default_clock())
+    }
+
+    #[must_use]
+    pub const fn with_clock(clock: ClockRef) -> Self {
+        Self {
+            clock,
+            accumulated: Duration::ZERO,
+            started_at: None,
+        }
+    }
+
+    /// Starts accumulating time, does nothing if the timer is already running.
+    pub fn start(&mut self) {
+        if self.started_at.is_none() {
+            self.started_at = Some(self.clock.now());
+        }
+    }
+
+    /// Stops accumulating time, keeping what has been accumulated so far.
+    pub fn stop(&mut self) {
+        self.accumulated = self.get();
+        self.started_at = None;
+    }
+
+    /// Sets the accumulated time back to zero, a running timer keeps running.
+    pub fn reset(&mut self) {
+        self.accumulated = Duration::ZERO;
+        if self.started_at.is_some() {
+            self.started_at = Some(self.clock.now());
+        }
+    }
+
+    /// Resets and starts the timer.
+    pub fn restart(&mut self) {
+        self.reset();
+        self.start();
+    }
+
+    /// The accumulated time.
+    #[must_use]
+    pub fn get(&self) -> Duration {
+        self.accumulated
+            + self.started_at.map_or(Duration::ZERO, |started_at| {
+                self.clock.now().saturating_sub(started_at)
+            })
+    }
+
+    #[must_use]
+    pub fn has_elapsed(&self, time: Duration) -> bool {
+        self.get() >= time
+    }
+
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    #[must_use]
+    pub fn get_clock(&self) -> ClockRef {
+        self.clock.clone()
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
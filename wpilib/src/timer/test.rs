@@ -0,0 +1,91 @@
+use std::{sync::Arc, time::Duration};
+
+use super::{Clock, FpgaClock, RealClock, SimClock, Timer};
+use crate::math::{
+    filter::{
+        debouncer::{DebounceType, Debouncer},
+        slew_rate_limiter::SlewRateLimiter,
+    },
+    units::time::Second,
+};
+
+#[test]
+fn sim_clock() {
+    let clock = SimClock::new();
+    let reader = clock.clone();
+    assert_eq!(reader.now(), Duration::ZERO);
+    clock.step(Duration::from_millis(20));
+    clock.step(Duration::from_millis(20));
+    assert_eq!(reader.now(), Duration::from_millis(40));
+}
+
+#[test]
+fn real_clocks() {
+    let real = RealClock::new();
+    let first = real.now();
+    assert!(real.now() >= first);
+
+    let fpga = FpgaClock.now();
+    //whole microseconds, and every fpga clock shares its epoch
+    assert_eq!(fpga.subsec_nanos() % 1000, 0);
+    assert!(FpgaClock.now() >= fpga);
+    assert!(FpgaClock.get_timestamp().value() >= 0);
+}
+
+#[test]
+fn timer() {
+    let clock = SimClock::new();
+    let mut timer = Timer::with_clock(Arc::new(clock.clone()));
+    clock.step(Duration::from_secs(1));
+    //stopped timers don't accumulate
+    assert_eq!(timer.get(), Duration::ZERO);
+
+    timer.start();
+    clock.step(Duration::from_secs(2));
+    timer.start();
+    assert_eq!(timer.get(), Duration::from_secs(2));
+    timer.stop();
+    clock.step(Duration::from_secs(1));
+    assert!(!timer.is_running());
+    assert_eq!(timer.get(), Duration::from_secs(2));
+
+    timer.start();
+    clock.step(Duration::from_secs(1));
+    assert!(timer.has_elapsed(Duration::from_secs(3)));
+    timer.reset();
+    assert!(timer.is_running());
+    assert_eq!(timer.get(), Duration::ZERO);
+    clock.step(Duration::from_millis(500));
+    assert_eq!(timer.get(), Duration::from_millis(500));
+}
+
+#[test]
+fn debouncer() {
+    let clock = SimClock::new();
+    let mut debouncer = Debouncer::with_clock(
+        Second::new(0.1),
+        DebounceType::Rising,
+        false,
+        Arc::new(clock.clone()),
+    );
+    assert!(!debouncer.calculate(true));
+    clock.step(Duration::from_millis(50));
+    assert!(!debouncer.calculate(true));
+    clock.step(Duration::from_millis(50));
+    assert!(debouncer.calculate(true));
+    //falling edges go through right away
+    assert!(!debouncer.calculate(false));
+}
+
+#[test]
+fn slew_rate_limiter() {
+    let clock = SimClock::new();
+    let mut limiter = SlewRateLimiter::with_clock(1.0, 2.0, 0.0, Arc::new(clock.clone()));
+    clock.step(Duration::from_millis(500));
+    assert!((limiter.calculate(10.0) - 0.5).abs() < 1e-9);
+    clock.step(Duration::from_millis(100));
+    assert!((limiter.calculate(-10.0) - 0.3).abs() < 1e-9);
+    limiter.reset(4.0);
+    //no time has passed since the reset
+    assert!((limiter.calculate(0.0) - 4.0).abs() < 1e-9);
+}